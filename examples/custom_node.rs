@@ -28,8 +28,10 @@ fn main() {
         // All you need to do to register your node is call
         // `RegisterNode::register_node`. This will automatically
         // handle parameter diffing, node connections, and audio
-        // graph management.
-        .register_node::<CustomVolumeNode>()
+        // graph management. Since our node is reflected, we'll
+        // use `register_reflected_node`, which also registers
+        // its type for inspectors and scenes.
+        .register_reflected_node::<CustomVolumeNode>()
         .add_systems(Startup, startup)
         .add_systems(Update, update)
         .run();
@@ -40,9 +42,17 @@ fn main() {
 // `Patch` traits allows this struct to send
 // realtime-safe messages from the ECS to the
 // audio thread.
-#[derive(Diff, Patch, Debug, Clone, Component)]
+//
+// Reflecting the node allows it to
+// appear in inspectors and scenes.
+#[derive(Diff, Patch, Reflect, Debug, Clone, Component)]
+#[reflect(Component)]
 pub struct CustomVolumeNode {
     // The volume we'll apply during audio processing.
+    //
+    // Firewheel's types don't implement `Reflect`, so
+    // `bevy_seedling` provides mirrors for the common ones.
+    #[reflect(remote = bevy_seedling::reflect::VolumeReflect)]
     pub volume: Volume,
 }
 
@@ -36,7 +36,7 @@ fn startup(server: Res<AssetServer>, mut commands: Commands) {
     //
     // `Transform` is a required component of `SpatialListener2D`, so we
     // don't have to explicitly insert one.
-    commands.spawn((SpatialListener2D, Spinner(0.0)));
+    commands.spawn((SpatialListener2D::default(), Spinner(0.0)));
 }
 
 #[derive(Component)]
@@ -66,10 +66,11 @@ pub fn derive_pool_label_inner(input: TokenStream) -> syn::Result<TokenStream2>
                     let value = world.get::<Self>(entity).unwrap();
                     let container = ::bevy_seedling::pool::label::PoolLabelContainer::new(value, id);
 
-                    world
-                        .commands()
-                        .entity(entity)
-                        .insert(container);
+                    let mut commands = world.commands();
+                    commands.entity(entity).insert(container);
+                    // Pools loaded from scenes aren't spawned through `Pool`,
+                    // so their systems are registered here.
+                    commands.queue(::bevy_seedling::pool::register_pool_systems::<Self>);
                 });
             }
         }
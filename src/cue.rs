@@ -0,0 +1,770 @@
+//! Data-driven sound cues.
+//!
+//! A [`SoundCue`] describes everything needed to play a sound: the sample
+//! or [sample set][crate::sample::set], its playback settings, and either a
+//! target pool or a chain of effects. Cues are loaded from RON or JSON files
+//! with the `.cue.ron` and `.cue.json` extensions, so designers can author
+//! and tweak sounds without touching code.
+//!
+//! ```ron
+//! (
+//!     sample: "sfx/door_creak.wav",
+//!     volume: 0.8,
+//!     speed: 0.95,
+//!     fade_in: Some(0.05),
+//!     effects: [
+//!         LowPass(frequency: 2500.0),
+//!         Freeverb(room_size: 0.6, damping: 0.4, width: 1.0),
+//!     ],
+//!     spatial: Some((scale: Some((0.5, 0.5, 0.5)))),
+//! )
+//! ```
+//!
+//! Cues are played with a [`CuePlayer`].
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_seedling::prelude::*;
+//! fn open_door(mut commands: Commands, server: Res<AssetServer>) {
+//!     commands.spawn((
+//!         CuePlayer::new(server.load("door_creak.cue.ron")),
+//!         Transform::from_xyz(4.0, 0.0, 0.0),
+//!     ));
+//! }
+//! ```
+//!
+//! ## Pools
+//!
+//! Since [`PoolLabel`]s are Rust types, cues refer to pools by name.
+//! Names are associated with labels through [`RegisterCuePool`].
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_seedling::prelude::*;
+//! #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+//! struct MusicPool;
+//!
+//! fn plugin(app: &mut App) {
+//!     app.register_cue_pool("music", MusicPool);
+//! }
+//! ```
+//!
+//! A cue with a `pool` plays in that pool, ignoring its `effects`,
+//! since the pool already defines its own effects chain. Otherwise, effects
+//! are applied through a [dynamic pool][crate::pool::dynamic].
+//!
+//! ## Hot reloading
+//!
+//! When a cue asset changes, every [`CuePlayer`] currently playing it
+//! restarts with the new definition.
+
+use crate::nodes::{bpf::BandPassNode, freeverb::FreeverbNode, lpf::LowPassNode};
+use crate::pool::{builder::PoolBuilder, label::PoolLabel};
+use crate::prelude::SpatialBasicNode;
+use crate::sample::{
+    set::{SampleSet, SampleSetPlayer},
+    Fade, OnComplete, PlaybackSettings, PlaybackSpeed, Playhead, Sample, SamplePlayer,
+    SamplePriority,
+};
+use crate::spatial::SpatialScale;
+use bevy_asset::{Asset, AssetEvent, AssetLoader, Assets, Handle};
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::{BuildChildren, DespawnRecursiveExt};
+use bevy_log::warn;
+use bevy_math::Vec3;
+use bevy_reflect::{Reflect, TypePath};
+use bevy_transform::components::Transform;
+use bevy_utils::HashMap;
+use core::time::Duration;
+use firewheel::{nodes::sampler::RepeatMode, Volume};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// A designer-authored sound.
+///
+/// For more details, see the [module docs][self].
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct SoundCue {
+    /// The sound's source.
+    pub source: CueSource,
+    /// The name of the pool to play in, registered with [`RegisterCuePool`].
+    pub pool: Option<String>,
+    /// Effects applied through a dynamic pool.
+    pub effects: Vec<CueEffect>,
+    /// The playback settings.
+    pub playback: CuePlayback,
+    /// Spatial settings. When present, the sound is spatialized
+    /// relative to the [`CuePlayer`]'s transform.
+    pub spatial: Option<CueSpatial>,
+}
+
+/// The source of a [`SoundCue`].
+#[derive(Debug, Clone)]
+pub enum CueSource {
+    /// A single sample.
+    Sample(Handle<Sample>),
+    /// A sample chosen from a [`SampleSet`].
+    Set(Handle<SampleSet>),
+}
+
+/// A [`SoundCue`]'s playback settings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CuePlayback {
+    /// The linear volume.
+    pub volume: f32,
+    /// The playback speed.
+    pub speed: f32,
+    /// How many times the sample plays.
+    pub repeat: CueRepeat,
+    /// Where playback begins, in seconds.
+    pub start: f64,
+    /// The fade-in duration in seconds.
+    pub fade_in: Option<f32>,
+    /// The fade-out duration in seconds.
+    pub fade_out: Option<f32>,
+    /// The sample's [`SamplePriority`].
+    pub priority: i32,
+    /// What happens to the [`CuePlayer`] entity once playback completes.
+    pub on_complete: OnComplete,
+}
+
+impl Default for CuePlayback {
+    fn default() -> Self {
+        Self {
+            volume: 1.0,
+            speed: 1.0,
+            repeat: CueRepeat::Once,
+            start: 0.0,
+            fade_in: None,
+            fade_out: None,
+            priority: 0,
+            on_complete: OnComplete::Despawn,
+        }
+    }
+}
+
+impl CuePlayback {
+    fn settings(&self) -> PlaybackSettings {
+        let seconds = |s: f32| Fade::new(Duration::from_secs_f32(s.max(0.0)));
+
+        PlaybackSettings {
+            repeat_mode: match self.repeat {
+                CueRepeat::Once => RepeatMode::PlayOnce,
+                CueRepeat::Loop => RepeatMode::RepeatEndlessly,
+                CueRepeat::Times(times) => RepeatMode::RepeatMultiple {
+                    num_times_to_repeat: times.into(),
+                },
+            },
+            // The cue player manages its own completion.
+            on_complete: OnComplete::Despawn,
+            volume: Volume::Linear(self.volume),
+            start: Playhead::Seconds(self.start),
+            fade_in: self.fade_in.map(seconds),
+            fade_out: self.fade_out.map(seconds),
+            ..Default::default()
+        }
+    }
+}
+
+/// Determines how many times a [`SoundCue`] plays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CueRepeat {
+    /// Play once.
+    #[default]
+    Once,
+    /// Loop until the [`CuePlayer`] is removed.
+    Loop,
+    /// Repeat the provided number of additional times.
+    Times(u16),
+}
+
+/// An effect applied to a [`SoundCue`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CueEffect {
+    /// A [`LowPassNode`].
+    LowPass {
+        /// The cutoff frequency in hertz.
+        frequency: f32,
+    },
+    /// A [`BandPassNode`].
+    BandPass {
+        /// The center frequency in hertz.
+        frequency: f32,
+        /// The filter's quality factor.
+        q: f32,
+    },
+    /// A [`FreeverbNode`].
+    Freeverb {
+        /// The size of the emulated room, from 0 to 1.
+        room_size: f32,
+        /// The high-frequency damping, from 0 to 1.
+        damping: f32,
+        /// The L/R blending, from 0 to 1.
+        width: f32,
+    },
+}
+
+impl CueEffect {
+    fn apply<B: PoolBuilder>(&self, builder: B) -> B::Output {
+        match *self {
+            Self::LowPass { frequency } => builder.effect(LowPassNode::new(frequency)),
+            Self::BandPass { frequency, q } => builder.effect(BandPassNode::new(frequency, q)),
+            Self::Freeverb {
+                room_size,
+                damping,
+                width,
+            } => builder.effect(FreeverbNode {
+                room_size,
+                damping,
+                width,
+            }),
+        }
+    }
+}
+
+/// A [`SoundCue`]'s spatial settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CueSpatial {
+    /// Overrides the [`DefaultSpatialScale`][crate::prelude::DefaultSpatialScale].
+    pub scale: Option<(f32, f32, f32)>,
+}
+
+/// A component that plays a [`SoundCue`].
+///
+/// Once the cue loads, a child [`SamplePlayer`] entity is spawned according
+/// to the cue's definition. For spatial cues, this entity should have a `Transform`.
+/// When playback finishes, the cue's `on_complete` behavior is applied to this entity.
+#[derive(Debug, Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct CuePlayer(pub(crate) Handle<SoundCue>);
+
+impl CuePlayer {
+    /// Construct a new [`CuePlayer`].
+    pub fn new(handle: Handle<SoundCue>) -> Self {
+        Self(handle)
+    }
+}
+
+/// Tracks a [`CuePlayer`]'s playback.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CueState {
+    Playing(Entity),
+    Finished,
+}
+
+type PoolInserter = Arc<dyn Fn(&mut EntityCommands) + Send + Sync>;
+
+/// Pools that [`SoundCue`]s can refer to by name.
+#[derive(Resource, Default)]
+pub(crate) struct CuePools(HashMap<String, PoolInserter>);
+
+/// Associate names with pools for [`SoundCue`]s.
+pub trait RegisterCuePool {
+    /// Allow cues to play in the pool `label` by referring to `name`.
+    fn register_cue_pool<L>(&mut self, name: impl Into<String>, label: L) -> &mut Self
+    where
+        L: PoolLabel + Component + Clone;
+}
+
+impl RegisterCuePool for bevy_app::App {
+    fn register_cue_pool<L>(&mut self, name: impl Into<String>, label: L) -> &mut Self
+    where
+        L: PoolLabel + Component + Clone,
+    {
+        let inserter: PoolInserter = Arc::new(move |commands: &mut EntityCommands| {
+            commands.insert(label.clone());
+        });
+
+        self.init_resource::<CuePools>()
+            .world_mut()
+            .resource_mut::<CuePools>()
+            .0
+            .insert(name.into(), inserter);
+
+        self
+    }
+}
+
+/// Spawn sample players for newly loaded cues.
+pub(crate) fn spawn_cues(
+    players: Query<(Entity, &CuePlayer), Without<CueState>>,
+    cues: Res<Assets<SoundCue>>,
+    pools: Res<CuePools>,
+    mut commands: Commands,
+) {
+    for (entity, player) in players.iter() {
+        let Some(cue) = cues.get(&player.0) else {
+            continue;
+        };
+
+        let mut child = commands.spawn((
+            cue.playback.settings(),
+            SamplePriority(cue.playback.priority),
+        ));
+
+        // Spatial cues follow the player's transform.
+        if cue.spatial.is_some() {
+            child.insert(Transform::default());
+        }
+
+        match &cue.source {
+            CueSource::Sample(handle) => child.insert(SamplePlayer::new(handle.clone())),
+            CueSource::Set(handle) => child.insert(SampleSetPlayer::new(handle.clone())),
+        };
+
+        if cue.playback.speed != 1.0 {
+            child.insert(PlaybackSpeed::new(cue.playback.speed));
+        }
+
+        if let Some(scale) = cue.spatial.as_ref().and_then(|s| s.scale) {
+            child.insert(SpatialScale(Vec3::from(scale)));
+        }
+
+        let child_id = child.id();
+        child.set_parent(entity);
+
+        match &cue.pool {
+            Some(name) => {
+                match pools.0.get(name) {
+                    Some(insert) => insert(&mut child),
+                    None => warn!("sound cue refers to unregistered pool \"{name}\""),
+                }
+
+                if !cue.effects.is_empty() {
+                    warn!("sound cue effects are ignored when playing in pool \"{name}\"");
+                }
+            }
+            None => {
+                let mut effects = cue.effects.iter();
+
+                match (cue.spatial.is_some(), effects.next()) {
+                    (true, _) => {
+                        let pool = child.effect(SpatialBasicNode::default());
+                        cue.effects
+                            .iter()
+                            .fold(pool, |pool, effect| effect.apply(pool));
+                    }
+                    (false, Some(first)) => {
+                        let pool = first.apply(child);
+                        effects.fold(pool, |pool, effect| effect.apply(pool));
+                    }
+                    (false, None) => {}
+                }
+            }
+        }
+
+        commands.entity(entity).insert(CueState::Playing(child_id));
+    }
+}
+
+/// Restart cue players when their cues are modified.
+///
+/// Finished players are left as they are.
+pub(crate) fn reload_cues(
+    mut events: EventReader<AssetEvent<SoundCue>>,
+    players: Query<(Entity, &CuePlayer, &CueState)>,
+    mut commands: Commands,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+
+        for (entity, player, state) in players.iter() {
+            let CueState::Playing(child) = state else {
+                continue;
+            };
+
+            if player.0.id() != *id {
+                continue;
+            }
+
+            if let Some(child) = commands.get_entity(*child) {
+                child.despawn_recursive();
+            }
+
+            commands.entity(entity).remove::<CueState>();
+        }
+    }
+}
+
+/// Apply cues' completion behavior once their sample players are gone.
+pub(crate) fn finish_cues(
+    players: Query<(Entity, &CuePlayer, &CueState)>,
+    samples: Query<(), Or<(With<SamplePlayer>, With<SampleSetPlayer>)>>,
+    cues: Res<Assets<SoundCue>>,
+    mut commands: Commands,
+) {
+    for (entity, player, state) in players.iter() {
+        let CueState::Playing(child) = state else {
+            continue;
+        };
+
+        if samples.contains(*child) {
+            continue;
+        }
+
+        let on_complete = cues
+            .get(&player.0)
+            .map(|cue| cue.playback.on_complete)
+            .unwrap_or_default();
+
+        match on_complete {
+            OnComplete::Preserve => {
+                commands.entity(entity).insert(CueState::Finished);
+            }
+            OnComplete::Remove => {
+                commands.entity(entity).remove::<(CuePlayer, CueState)>();
+            }
+            OnComplete::Despawn => {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+    }
+}
+
+/// A serialized [`SoundCue`].
+///
+/// The playback fields are inlined rather than flattened,
+/// since RON doesn't support flattening.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct SoundCueManifest {
+    sample: Option<String>,
+    set: Option<String>,
+    pool: Option<String>,
+    effects: Vec<CueEffect>,
+    spatial: Option<CueSpatial>,
+    volume: f32,
+    speed: f32,
+    repeat: CueRepeat,
+    start: f64,
+    fade_in: Option<f32>,
+    fade_out: Option<f32>,
+    priority: i32,
+    on_complete: OnComplete,
+}
+
+impl Default for SoundCueManifest {
+    fn default() -> Self {
+        let playback = CuePlayback::default();
+
+        Self {
+            sample: None,
+            set: None,
+            pool: None,
+            effects: Vec::new(),
+            spatial: None,
+            volume: playback.volume,
+            speed: playback.speed,
+            repeat: playback.repeat,
+            start: playback.start,
+            fade_in: playback.fade_in,
+            fade_out: playback.fade_out,
+            priority: playback.priority,
+            on_complete: playback.on_complete,
+        }
+    }
+}
+
+impl SoundCueManifest {
+    fn playback(&self) -> CuePlayback {
+        CuePlayback {
+            volume: self.volume,
+            speed: self.speed,
+            repeat: self.repeat,
+            start: self.start,
+            fade_in: self.fade_in,
+            fade_out: self.fade_out,
+            priority: self.priority,
+            on_complete: self.on_complete,
+        }
+    }
+}
+
+/// A loader for [`SoundCue`] RON and JSON files.
+#[derive(Debug, Default)]
+pub struct SoundCueLoader;
+
+/// Errors produced while loading sound cues.
+#[derive(Debug)]
+pub enum SoundCueLoaderError {
+    /// An I/O error, such as missing files.
+    StdIo(std::io::Error),
+    /// The RON definition couldn't be parsed.
+    Ron(ron::error::SpannedError),
+    /// The JSON definition couldn't be parsed.
+    Json(serde_json::Error),
+    /// The cue must provide exactly one of `sample` or `set`.
+    MissingSource,
+}
+
+impl From<std::io::Error> for SoundCueLoaderError {
+    fn from(value: std::io::Error) -> Self {
+        Self::StdIo(value)
+    }
+}
+
+impl From<ron::error::SpannedError> for SoundCueLoaderError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Ron(value)
+    }
+}
+
+impl From<serde_json::Error> for SoundCueLoaderError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Json(value)
+    }
+}
+
+impl std::error::Error for SoundCueLoaderError {}
+
+impl std::fmt::Display for SoundCueLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StdIo(stdio) => stdio.fmt(f),
+            Self::Ron(ron) => ron.fmt(f),
+            Self::Json(json) => json.fmt(f),
+            Self::MissingSource => {
+                f.write_str("sound cues must provide exactly one of `sample` or `set`")
+            }
+        }
+    }
+}
+
+impl AssetLoader for SoundCueLoader {
+    type Asset = SoundCue;
+    type Settings = ();
+    type Error = SoundCueLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn bevy_asset::io::Reader,
+        _settings: &Self::Settings,
+        load_context: &mut bevy_asset::LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let is_json = load_context
+            .path()
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+
+        let manifest: SoundCueManifest = if is_json {
+            serde_json::from_slice(&bytes)?
+        } else {
+            ron::de::from_bytes(&bytes)?
+        };
+
+        let playback = manifest.playback();
+        let source = match (manifest.sample, manifest.set) {
+            (Some(sample), None) => CueSource::Sample(load_context.load(sample)),
+            (None, Some(set)) => CueSource::Set(load_context.load(set)),
+            _ => return Err(SoundCueLoaderError::MissingSource),
+        };
+
+        Ok(SoundCue {
+            source,
+            pool: manifest.pool,
+            effects: manifest.effects,
+            playback,
+            spatial: manifest.spatial,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["cue.ron", "cue.json"]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{prelude::*, profiling::ProfilingBackend};
+    use bevy::prelude::*;
+    use bevy_ecs::system::RunSystemOnce;
+
+    fn prepare_app() -> App {
+        let mut app = App::new();
+
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            SeedlingPlugin::<ProfilingBackend> {
+                default_pool_size: None,
+                main_bus_limiter: None,
+                ..SeedlingPlugin::<ProfilingBackend>::new()
+            },
+            HierarchyPlugin,
+        ));
+
+        app.finish();
+        app.cleanup();
+        app.update();
+
+        app
+    }
+
+    fn run<F: IntoSystem<(), O, M>, O, M>(app: &mut App, system: F) -> O {
+        let world = app.world_mut();
+        world.run_system_once(system).unwrap()
+    }
+
+    /// Add `cue` and spawn a player for it, returning the player.
+    fn play_cue(app: &mut App, cue: fn(Handle<Sample>) -> SoundCue) -> Entity {
+        run(
+            app,
+            move |mut commands: Commands,
+                  server: Res<AssetServer>,
+                  mut cues: ResMut<Assets<SoundCue>>| {
+                let cue = cues.add(cue(server.load("caw.ogg")));
+                commands.spawn(CuePlayer::new(cue)).id()
+            },
+        )
+    }
+
+    /// The player's current child, once spawned.
+    fn cue_child(app: &mut App, player: Entity) -> Option<Entity> {
+        app.update();
+
+        run(app, move |q: Query<&CueState>| match q.get(player) {
+            Ok(CueState::Playing(child)) => Some(*child),
+            _ => None,
+        })
+    }
+
+    fn looping_cue(sample: Handle<Sample>) -> SoundCue {
+        SoundCue {
+            source: CueSource::Sample(sample),
+            pool: None,
+            effects: Vec::new(),
+            playback: CuePlayback {
+                repeat: CueRepeat::Loop,
+                ..Default::default()
+            },
+            spatial: None,
+        }
+    }
+
+    #[test]
+    fn test_spawn_in_pool() {
+        #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct MusicPool;
+
+        let mut app = prepare_app();
+        app.register_cue_pool("music", MusicPool);
+
+        let player = play_cue(&mut app, |sample| SoundCue {
+            pool: Some("music".into()),
+            effects: vec![CueEffect::LowPass { frequency: 500.0 }],
+            playback: CuePlayback {
+                speed: 0.5,
+                repeat: CueRepeat::Loop,
+                ..Default::default()
+            },
+            ..looping_cue(sample)
+        });
+
+        let child = cue_child(&mut app, player).expect("cue player never spawned a child");
+
+        run(
+            &mut app,
+            move |q: Query<(
+                &Parent,
+                &SamplePlayer,
+                &PlaybackSpeed,
+                Has<MusicPool>,
+                Has<LowPassNode>,
+                Has<Transform>,
+            )>| {
+                let (parent, _, speed, in_pool, low_pass, transform) = q.get(child).unwrap();
+
+                assert_eq!(parent.get(), player);
+                assert_eq!(speed.speed.get(), 0.5);
+                assert!(in_pool);
+                // Pools define their own effects.
+                assert!(!low_pass);
+                assert!(!transform);
+            },
+        );
+    }
+
+    #[test]
+    fn test_spawn_with_effects() {
+        let mut app = prepare_app();
+
+        let player = play_cue(&mut app, |sample| SoundCue {
+            effects: vec![CueEffect::LowPass { frequency: 500.0 }],
+            spatial: Some(CueSpatial::default()),
+            ..looping_cue(sample)
+        });
+
+        let child = cue_child(&mut app, player).expect("cue player never spawned a child");
+
+        run(
+            &mut app,
+            move |q: Query<(
+                Option<&LowPassNode>,
+                Has<SpatialBasicNode>,
+                Has<Transform>,
+                Has<PlaybackSpeed>,
+            )>| {
+                let (low_pass, spatial, transform, speed) = q.get(child).unwrap();
+
+                assert_eq!(low_pass.map(|node| node.frequency.get()), Some(500.0));
+                assert!(spatial);
+                assert!(transform);
+                // The default speed doesn't need a component.
+                assert!(!speed);
+            },
+        );
+    }
+
+    #[test]
+    fn test_modified_restarts() {
+        let mut app = prepare_app();
+
+        let player = play_cue(&mut app, looping_cue);
+        let child = cue_child(&mut app, player).expect("cue player never spawned a child");
+
+        run(
+            &mut app,
+            move |players: Query<&CuePlayer>, mut cues: ResMut<Assets<SoundCue>>| {
+                let cue = cues.get_mut(&players.get(player).unwrap().0).unwrap();
+                cue.playback.volume = 0.5;
+            },
+        );
+
+        let restarted = (0..8).find_map(|_| cue_child(&mut app, player).filter(|c| *c != child));
+        let restarted = restarted.expect("cue player was never restarted");
+
+        run(
+            &mut app,
+            move |q: Query<&PlaybackSettings>, all: Query<Entity>| {
+                assert!(!all.contains(child));
+                assert_eq!(q.get(restarted).unwrap().volume, Volume::Linear(0.5));
+            },
+        );
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest: SoundCueManifest = ron::de::from_str(
+            r#"(
+                sample: "caw.ogg",
+                volume: 0.5,
+                repeat: Times(2),
+                effects: [LowPass(frequency: 500.0)],
+            )"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.sample.as_deref(), Some("caw.ogg"));
+        assert_eq!(manifest.volume, 0.5);
+        assert_eq!(manifest.speed, 1.0);
+        assert_eq!(manifest.repeat, CueRepeat::Times(2));
+        assert_eq!(manifest.effects, [CueEffect::LowPass { frequency: 500.0 }]);
+    }
+}
@@ -1,5 +1,5 @@
-use super::{EdgeTarget, NodeMap, PendingEdge, DEFAULT_CONNECTION};
-use crate::{context::AudioContext, node::FirewheelNode};
+use super::{ConnectionTarget, Connections, EdgeTarget, NodeMap, PendingEdge, DEFAULT_CONNECTION};
+use crate::{context::AudioContext, graph::GraphChanged, node::FirewheelNode};
 use bevy_ecs::prelude::*;
 use bevy_log::error_once;
 
@@ -374,20 +374,29 @@ impl core::fmt::Debug for ConnectCommands<'_> {
 
 // this has turned into a bit of a monster
 pub(crate) fn process_connections(
-    mut connections: Query<(&mut PendingConnections, &FirewheelNode)>,
+    mut connections: Query<(
+        &mut PendingConnections,
+        &FirewheelNode,
+        Option<&mut Connections>,
+    )>,
     targets: Query<&FirewheelNode>,
     node_map: Res<NodeMap>,
+    registry: Res<AppTypeRegistry>,
+    mut graph_changed: ResMut<GraphChanged>,
     mut context: ResMut<AudioContext>,
 ) {
+    let mut connected = false;
+    let registry = registry.read();
+
     context.with(|context| {
-        for (mut pending, source_node) in connections.iter_mut() {
+        for (mut pending, source_node, mut recorded) in connections.iter_mut() {
             pending.0.retain(|connection| {
                 let ports = connection.ports.as_deref().unwrap_or(DEFAULT_CONNECTION);
 
                 let target_entity = match connection.target {
                     EdgeTarget::Entity(entity) => entity,
                     EdgeTarget::Label(label) => {
-                        let Some(entity) = node_map.get(&label) else {
+                        let Some(entity) = node_map.resolve(&label, &registry) else {
                             #[cfg(debug_assertions)]
                             {
                                 let location = connection.origin;
@@ -400,12 +409,13 @@ pub(crate) fn process_connections(
                             return true;
                         };
 
-                        *entity
+                        entity
                     }
                     EdgeTarget::Node(dest_node) => {
                         // no questions asked, simply connect
-                        if let Err(e) = context.connect(source_node.0, dest_node, ports, false) {
-                            error_once!("failed to connect audio node to target: {e}");
+                        match context.connect(source_node.0, dest_node, ports, false) {
+                            Ok(_) => connected = true,
+                            Err(e) => error_once!("failed to connect audio node to target: {e}"),
                         }
 
                         // if this fails, the target node must have been removed from the graph
@@ -428,14 +438,28 @@ pub(crate) fn process_connections(
                     }
                 };
 
-                if let Err(e) = context.connect(source_node.0, target.0, ports, false) {
-                    error_once!("failed to connect audio node to target: {e}");
+                match context.connect(source_node.0, target.0, ports, false) {
+                    Ok(_) => {
+                        connected = true;
+
+                        if let (Some(recorded), Some(target)) = (
+                            recorded.as_mut(),
+                            ConnectionTarget::from_edge(&connection.target, &registry),
+                        ) {
+                            recorded.record(target, ports);
+                        }
+                    }
+                    Err(e) => error_once!("failed to connect audio node to target: {e}"),
                 }
 
                 false
             });
         }
     });
+
+    if connected {
+        graph_changed.0 = true;
+    }
 }
 
 #[cfg(test)]
@@ -464,6 +488,7 @@ mod test {
             AssetPlugin::default(),
             SeedlingPlugin::<ProfilingBackend> {
                 default_pool_size: None,
+                main_bus_limiter: None,
                 ..SeedlingPlugin::<ProfilingBackend>::new()
             },
         ))
@@ -0,0 +1,491 @@
+use super::{EdgeTarget, NodeMap, PendingConnections, PendingEdge};
+use crate::node::{label::InternedNodeLabel, FirewheelNode};
+use crate::prelude::NodeLabel;
+use bevy_ecs::{
+    entity::{EntityMapper, MapEntities},
+    prelude::*,
+    reflect::ReflectMapEntities,
+};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect, TypePath, TypeRegistry};
+use core::any::Any;
+use serde::{Deserialize, Serialize};
+
+/// A persistent record of an entity's audio graph connections.
+///
+/// Whenever a connection from a [`FirewheelNode`] entity is made or removed,
+/// it's recorded here. Unlike [`PendingConnections`], this component
+/// survives after the connections are made and can be saved in Bevy scenes.
+///
+/// When an entity with recorded connections acquires a [`FirewheelNode`],
+/// such as when a scene is loaded, its connections are rebuilt in the
+/// audio graph. Since nodes with recorded connections aren't connected to
+/// the [`MainBus`][crate::prelude::MainBus] automatically, the loaded graph
+/// matches the saved one.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// # fn system(mut commands: Commands) {
+/// let bus = commands.spawn(VolumeNode::default()).id();
+///
+/// // Equivalent to `.connect(bus)`.
+/// commands.spawn((
+///     LowPassNode::default(),
+///     Connections::new([Connection::new(bus, [(0, 0), (1, 1)])]),
+/// ));
+/// # }
+/// ```
+///
+/// Labels are stored as a [`LabelKey`], so a label must be saved along
+/// with its node for connections to it to be restored.
+/// To include your own labels in scenes, derive [`Reflect`] for them
+/// and register them. Connections to unregistered labels aren't recorded.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// #[derive(NodeLabel, Reflect, Debug, Clone, PartialEq, Eq, Hash)]
+/// #[reflect(Component)]
+/// struct MusicBus;
+///
+/// # fn plugin(app: &mut App) {
+/// app.register_type::<MusicBus>();
+/// # }
+/// ```
+///
+/// Connections made directly to a Firewheel [`NodeID`][firewheel::node::NodeID]
+/// aren't recorded, since node IDs aren't stable across runs.
+#[derive(Debug, Default, Clone, Component, Reflect)]
+#[reflect(Component, Default, MapEntities)]
+pub struct Connections(Vec<Connection>);
+
+/// A single recorded connection.
+///
+/// For more details, see [`Connections`].
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+pub struct Connection {
+    /// The connection target.
+    pub target: ConnectionTarget,
+    /// The port mapping, where the first element of each pair is
+    /// the source output and the second is the target input.
+    pub ports: Vec<(u32, u32)>,
+}
+
+/// A persistent [`EdgeTarget`].
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+pub enum ConnectionTarget {
+    /// An audio entity.
+    Entity(Entity),
+    /// A node label.
+    Label(LabelKey),
+}
+
+/// A persistent identity for a [`NodeLabel`].
+///
+/// Labels are identified by their [`TypePath`] and [`Debug`] representation,
+/// so labels of different types never collide, even if they're
+/// formatted identically.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub struct LabelKey {
+    type_path: String,
+    value: String,
+}
+
+impl LabelKey {
+    /// Construct the key for a label.
+    pub fn new<L: NodeLabel + TypePath>(label: &L) -> Self {
+        Self {
+            type_path: L::type_path().into(),
+            value: format!("{label:?}"),
+        }
+    }
+
+    /// Construct the key for an interned label.
+    ///
+    /// Returns `None` if the label's type isn't registered.
+    pub fn from_interned(label: InternedNodeLabel, registry: &TypeRegistry) -> Option<Self> {
+        let label_any = label.as_dyn_eq().as_any();
+        if let Some(saved) = label_any.downcast_ref::<SavedLabel>() {
+            return Some(saved.0.clone());
+        }
+
+        let registration = registry.get(Any::type_id(label_any))?;
+
+        Some(Self {
+            type_path: registration.type_info().type_path().into(),
+            value: format!("{label:?}"),
+        })
+    }
+
+    /// The label's type path.
+    pub fn type_path(&self) -> &str {
+        &self.type_path
+    }
+
+    /// Produce an interned label that stands in for this key.
+    ///
+    /// The stand-in resolves to the node whose label matches this key
+    /// once that node is spawned. Until then, connections to it are
+    /// deferred like any other missing label.
+    pub(crate) fn to_label(&self) -> InternedNodeLabel {
+        SavedLabel(self.clone()).intern()
+    }
+}
+
+/// A stand-in for a label restored from a [`LabelKey`].
+#[derive(NodeLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct SavedLabel(LabelKey);
+
+impl NodeMap {
+    /// Find the entity associated with a label.
+    ///
+    /// This also resolves labels produced by [`LabelKey::to_label`].
+    pub(crate) fn resolve(
+        &self,
+        label: &InternedNodeLabel,
+        registry: &TypeRegistry,
+    ) -> Option<Entity> {
+        if let Some(entity) = self.get(label) {
+            return Some(*entity);
+        }
+
+        let saved = label.as_dyn_eq().as_any().downcast_ref::<SavedLabel>()?;
+        self.iter()
+            .find(|(label, _)| {
+                LabelKey::from_interned(**label, registry).as_ref() == Some(&saved.0)
+            })
+            .map(|(_, entity)| *entity)
+    }
+}
+
+impl Connections {
+    /// Construct a new set of [`Connections`].
+    pub fn new(connections: impl IntoIterator<Item = Connection>) -> Self {
+        let mut new = Self::default();
+
+        for connection in connections {
+            new.record(connection.target, &connection.ports);
+        }
+
+        new
+    }
+
+    /// Iterate over the recorded connections.
+    pub fn iter(&self) -> impl Iterator<Item = &Connection> {
+        self.0.iter()
+    }
+
+    /// Record a connection, merging its ports into
+    /// any existing connection with the same target.
+    pub(crate) fn record(&mut self, target: ConnectionTarget, ports: &[(u32, u32)]) {
+        let connection = match self.0.iter_mut().find(|c| c.target == target) {
+            Some(connection) => connection,
+            None => {
+                self.0.push(Connection {
+                    target,
+                    ports: Vec::new(),
+                });
+                self.0.last_mut().unwrap()
+            }
+        };
+
+        for port in ports {
+            if !connection.ports.contains(port) {
+                connection.ports.push(*port);
+            }
+        }
+    }
+
+    /// Forget a connection's ports, removing the
+    /// connection entirely if no ports remain.
+    pub(crate) fn forget(&mut self, target: &ConnectionTarget, ports: &[(u32, u32)]) {
+        for connection in self.0.iter_mut().filter(|c| &c.target == target) {
+            connection.ports.retain(|p| !ports.contains(p));
+        }
+
+        self.0.retain(|c| !c.ports.is_empty());
+    }
+}
+
+impl MapEntities for Connections {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        for connection in &mut self.0 {
+            if let ConnectionTarget::Entity(entity) = &mut connection.target {
+                *entity = entity_mapper.map_entity(*entity);
+            }
+        }
+    }
+}
+
+impl Connection {
+    /// Construct a new [`Connection`].
+    pub fn new(target: impl Into<ConnectionTarget>, ports: impl Into<Vec<(u32, u32)>>) -> Self {
+        Self {
+            target: target.into(),
+            ports: ports.into(),
+        }
+    }
+}
+
+impl ConnectionTarget {
+    /// Convert an [`EdgeTarget`], returning `None` for Firewheel node IDs
+    /// and unregistered labels.
+    pub(crate) fn from_edge(target: &EdgeTarget, registry: &TypeRegistry) -> Option<Self> {
+        match target {
+            EdgeTarget::Entity(entity) => Some(Self::Entity(*entity)),
+            EdgeTarget::Label(label) => LabelKey::from_interned(*label, registry).map(Self::Label),
+            EdgeTarget::Node(_) => None,
+        }
+    }
+
+    /// Convert back into an [`EdgeTarget`].
+    pub(crate) fn to_edge(&self) -> EdgeTarget {
+        match self {
+            Self::Entity(entity) => EdgeTarget::Entity(*entity),
+            Self::Label(key) => EdgeTarget::Label(key.to_label()),
+        }
+    }
+}
+
+impl From<Entity> for ConnectionTarget {
+    fn from(value: Entity) -> Self {
+        Self::Entity(value)
+    }
+}
+
+impl<T> From<T> for ConnectionTarget
+where
+    T: NodeLabel + TypePath,
+{
+    fn from(value: T) -> Self {
+        Self::Label(LabelKey::new(&value))
+    }
+}
+
+/// Rebuild recorded connections for newly acquired nodes.
+pub(crate) fn restore_connections(
+    nodes: Query<(Entity, &Connections), Added<FirewheelNode>>,
+    mut commands: Commands,
+) {
+    for (entity, connections) in nodes.iter() {
+        if connections.0.is_empty() {
+            continue;
+        }
+
+        let edges: Vec<_> = connections
+            .0
+            .iter()
+            .map(|c| PendingEdge::new(c.target.to_edge(), Some(c.ports.clone())))
+            .collect();
+
+        commands
+            .entity(entity)
+            .entry::<PendingConnections>()
+            .or_default()
+            .and_modify(move |mut pending| {
+                for edge in edges {
+                    pending.push(edge);
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        context::AudioContext, prelude::MainBus, profiling::ProfilingBackend, SeedlingPlugin,
+    };
+    use bevy::prelude::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use firewheel::nodes::volume::VolumeNode;
+
+    #[derive(Component)]
+    struct One;
+    #[derive(Component)]
+    struct Two;
+
+    mod first {
+        use crate::prelude::NodeLabel;
+        use bevy_reflect::Reflect;
+
+        #[derive(NodeLabel, Reflect, Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct Bus;
+    }
+
+    mod second {
+        use crate::prelude::NodeLabel;
+        use bevy_reflect::Reflect;
+
+        #[derive(NodeLabel, Reflect, Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct Bus;
+    }
+
+    fn prepare_app<F: IntoSystem<(), (), M>, M>(startup: F) -> App {
+        let mut app = App::new();
+
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            SeedlingPlugin::<ProfilingBackend> {
+                default_pool_size: None,
+                main_bus_limiter: None,
+                ..SeedlingPlugin::<ProfilingBackend>::new()
+            },
+        ))
+        .register_type::<first::Bus>()
+        .register_type::<second::Bus>()
+        .add_systems(Startup, startup);
+
+        app.finish();
+        app.cleanup();
+        app.update();
+
+        app
+    }
+
+    #[test]
+    fn test_record_connections() {
+        let mut app = prepare_app(|mut commands: Commands| {
+            commands.spawn((VolumeNode::default(), One));
+        });
+
+        app.world_mut()
+            .run_system_once(|one: Single<&Connections, With<One>>| {
+                let connections: Vec<_> = one.into_inner().iter().cloned().collect();
+
+                assert_eq!(connections, [Connection::new(MainBus, [(0, 0), (1, 1)])]);
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_restore_connections() {
+        let mut app = prepare_app(|mut commands: Commands| {
+            let one = commands.spawn((VolumeNode::default(), One)).id();
+
+            commands.spawn((
+                VolumeNode::default(),
+                Two,
+                Connections::new([Connection::new(one, [(0, 1), (1, 0)])]),
+            ));
+        });
+
+        app.world_mut()
+            .run_system_once(
+                |mut context: ResMut<AudioContext>,
+                 one: Single<&FirewheelNode, With<One>>,
+                 two: Single<&FirewheelNode, With<Two>>| {
+                    let one = one.into_inner();
+                    let two = two.into_inner();
+
+                    context.with(|context| {
+                        let mut edges: Vec<_> = context
+                            .edges()
+                            .into_iter()
+                            .filter(|e| e.src_node == two.0)
+                            .map(|e| (e.dst_node, e.src_port, e.dst_port))
+                            .collect();
+                        edges.sort_by_key(|e| e.1);
+
+                        assert_eq!(edges, [(one.0, 0, 1), (one.0, 1, 0)]);
+                    });
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_label_keys() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<first::Bus>();
+
+        let first = LabelKey::new(&first::Bus);
+        let second = LabelKey::new(&second::Bus);
+
+        assert_ne!(first, second);
+        assert_eq!(first.type_path(), first::Bus::type_path());
+        assert_eq!(
+            LabelKey::from_interned(first::Bus.intern(), &registry),
+            Some(first.clone())
+        );
+        assert_eq!(
+            LabelKey::from_interned(first.to_label(), &registry),
+            Some(first)
+        );
+        assert_eq!(
+            LabelKey::from_interned(second::Bus.intern(), &registry),
+            None
+        );
+    }
+
+    #[test]
+    fn test_reflect_send_target() {
+        use crate::prelude::SendNode;
+        use bevy_reflect::FromReflect;
+        use firewheel::Volume;
+
+        use bevy_reflect::serde::TypedReflectSerializer;
+
+        let mut registry = TypeRegistry::default();
+        registry.register::<EdgeTarget>();
+
+        let send = SendNode::new(Volume::UNITY_GAIN, second::Bus);
+        let restored = SendNode::from_reflect(&send).unwrap();
+        assert_eq!(restored.target, send.target);
+
+        // Unregistered labels can't be serialized.
+        let serializer = TypedReflectSerializer::new(&send.target, &registry);
+        assert!(ron::to_string(&serializer).is_err());
+
+        registry.register::<second::Bus>();
+
+        let serializer = TypedReflectSerializer::new(&send.target, &registry);
+        let text = ron::to_string(&serializer).unwrap();
+        let restored: EdgeTarget = ron::from_str(&text).unwrap();
+        assert_eq!(
+            ConnectionTarget::from_edge(&restored, &registry),
+            ConnectionTarget::from_edge(&send.target, &registry),
+        );
+    }
+
+    #[test]
+    fn test_restore_label_connections() {
+        let mut app = prepare_app(|mut commands: Commands| {
+            // Both labels are formatted as `Bus`, so only
+            // their types tell them apart.
+            commands.spawn((
+                VolumeNode::default(),
+                One,
+                Connections::new([Connection::new(second::Bus, [(0, 0), (1, 1)])]),
+            ));
+
+            commands.spawn((VolumeNode::default(), first::Bus));
+            commands.spawn((VolumeNode::default(), second::Bus, Two));
+        });
+
+        app.update();
+
+        app.world_mut()
+            .run_system_once(
+                |mut context: ResMut<AudioContext>,
+                 one: Single<&FirewheelNode, With<One>>,
+                 two: Single<&FirewheelNode, With<Two>>| {
+                    let one = one.into_inner();
+                    let two = two.into_inner();
+
+                    context.with(|context| {
+                        let targets: Vec<_> = context
+                            .edges()
+                            .into_iter()
+                            .filter(|e| e.src_node == one.0)
+                            .map(|e| e.dst_node)
+                            .collect();
+
+                        assert_eq!(targets, [two.0, two.0]);
+                    });
+                },
+            )
+            .unwrap();
+    }
+}
@@ -1,5 +1,5 @@
-use super::{EdgeTarget, NodeMap, PendingEdge, DEFAULT_CONNECTION};
-use crate::{context::AudioContext, node::FirewheelNode};
+use super::{ConnectionTarget, Connections, EdgeTarget, NodeMap, PendingEdge, DEFAULT_CONNECTION};
+use crate::{context::AudioContext, graph::GraphChanged, node::FirewheelNode};
 use bevy_ecs::prelude::*;
 use bevy_log::error_once;
 
@@ -121,20 +121,29 @@ impl Disconnect for EntityCommands<'_> {
 }
 
 pub(crate) fn process_disconnections(
-    mut disconnections: Query<(&mut PendingDisconnections, &FirewheelNode)>,
+    mut disconnections: Query<(
+        &mut PendingDisconnections,
+        &FirewheelNode,
+        Option<&mut Connections>,
+    )>,
     targets: Query<&FirewheelNode>,
     node_map: Res<NodeMap>,
+    registry: Res<AppTypeRegistry>,
+    mut graph_changed: ResMut<GraphChanged>,
     mut context: ResMut<AudioContext>,
 ) {
+    let mut disconnected = false;
+    let registry = registry.read();
+
     context.with(|context| {
-        for (mut pending, source_node) in disconnections.iter_mut() {
+        for (mut pending, source_node, mut recorded) in disconnections.iter_mut() {
             pending.0.retain(|disconnections| {
                 let ports = disconnections.ports.as_deref().unwrap_or(DEFAULT_CONNECTION);
 
                 let target_entity = match disconnections.target {
                     EdgeTarget::Entity(entity) => entity,
                     EdgeTarget::Label(label) => {
-                        let Some(entity) = node_map.get(&label) else {
+                        let Some(entity) = node_map.resolve(&label, &registry) else {
                             #[cfg(debug_assertions)]
                             {
                                 let location = disconnections.origin;
@@ -147,11 +156,12 @@ pub(crate) fn process_disconnections(
                             return true;
                         };
 
-                        *entity
+                        entity
                     }
                     EdgeTarget::Node(dest_node) => {
                         // no questions asked, simply disconnect
                         context.disconnect(source_node.0, dest_node, ports);
+                        disconnected = true;
 
                         // if this fails, the target node must have been removed from the graph
                         return false;
@@ -174,11 +184,23 @@ pub(crate) fn process_disconnections(
                 };
 
                 context.disconnect(source_node.0, target.0, ports);
+                disconnected = true;
+
+                if let (Some(recorded), Some(target)) = (
+                    recorded.as_mut(),
+                    ConnectionTarget::from_edge(&disconnections.target, &registry),
+                ) {
+                    recorded.forget(&target, ports);
+                }
 
                 false
             });
         }
     });
+
+    if disconnected {
+        graph_changed.0 = true;
+    }
 }
 
 #[cfg(test)]
@@ -206,6 +228,7 @@ mod test {
             AssetPlugin::default(),
             SeedlingPlugin::<ProfilingBackend> {
                 default_pool_size: None,
+                main_bus_limiter: None,
                 ..SeedlingPlugin::<ProfilingBackend>::new()
             },
         ))
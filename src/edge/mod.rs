@@ -3,24 +3,36 @@
 use crate::node::label::InternedNodeLabel;
 use crate::prelude::{FirewheelNode, MainBus, NodeLabel};
 use bevy_ecs::prelude::*;
+use bevy_reflect::{
+    serde::{ReflectSerializeWithRegistry, SerializeWithRegistry},
+    Reflect, ReflectDeserialize, TypeRegistry,
+};
 use bevy_utils::HashMap;
 use firewheel::node::NodeID;
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 
 #[cfg(debug_assertions)]
 use core::panic::Location;
 
 #[allow(clippy::module_inception)]
 mod connect;
+mod connections;
 mod disconnect;
 
 pub use connect::*;
+pub use connections::*;
 pub use disconnect::*;
 
 /// A target for node connections.
 ///
 /// [`EdgeTarget`] can be constructed manually or
 /// used as a part of the [`Connect`] and [`Disconnect`] APIs.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Labels are serialized as a [`LabelKey`], so their types must be registered.
+/// Since Firewheel node IDs aren't stable across runs, serializing
+/// an [`EdgeTarget::Node`] fails.
+#[derive(Debug, Clone, PartialEq, Eq, Reflect)]
+#[reflect(opaque, Debug, PartialEq, SerializeWithRegistry, Deserialize)]
 pub enum EdgeTarget {
     /// A global label such as [`MainBus`].
     Label(InternedNodeLabel),
@@ -101,6 +113,49 @@ impl From<Entity> for EdgeTarget {
     }
 }
 
+/// [`EdgeTarget`]'s serialized representation.
+#[derive(Serialize, Deserialize)]
+enum SerializedTarget {
+    Entity(u64),
+    Label(LabelKey),
+}
+
+impl SerializeWithRegistry for EdgeTarget {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+        registry: &TypeRegistry,
+    ) -> Result<S::Ok, S::Error> {
+        let target = match ConnectionTarget::from_edge(self, registry) {
+            Some(ConnectionTarget::Entity(entity)) => SerializedTarget::Entity(entity.to_bits()),
+            Some(ConnectionTarget::Label(key)) => SerializedTarget::Label(key),
+            None if matches!(self, Self::Node(_)) => {
+                return Err(S::Error::custom("Firewheel node IDs can't be serialized"))
+            }
+            None => {
+                return Err(S::Error::custom(format_args!(
+                    "the type of `{self:?}` isn't registered"
+                )))
+            }
+        };
+
+        target.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for EdgeTarget {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let target = match SerializedTarget::deserialize(deserializer)? {
+            SerializedTarget::Entity(bits) => {
+                ConnectionTarget::Entity(Entity::try_from_bits(bits).map_err(D::Error::custom)?)
+            }
+            SerializedTarget::Label(key) => ConnectionTarget::Label(key),
+        };
+
+        Ok(target.to_edge())
+    }
+}
+
 const DEFAULT_CONNECTION: &[(u32, u32)] = &[(0, 0), (1, 1)];
 
 /// A map that associates [`NodeLabel`]s with audio
@@ -0,0 +1,505 @@
+//! A cheap, ECS-side view of the audio graph.
+//!
+//! Querying the audio graph directly with [`AudioContext::with`] blocks
+//! on the control thread. Instead, `bevy_seedling` mirrors the graph's
+//! topology into the [`AudioGraphSnapshot`] resource whenever it changes.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_seedling::prelude::*;
+//! fn log_graph(graph: Res<AudioGraphSnapshot>) {
+//!     for node in graph.nodes() {
+//!         let outputs = graph.outgoing(node.id).count();
+//!         info!("{} ({:?}): {outputs} outgoing edges", node.debug_name, node.entity);
+//!     }
+//! }
+//! ```
+//!
+//! Snapshots can also be exported as [Graphviz](https://graphviz.org/) DOT
+//! with [`AudioGraphSnapshot::to_dot`] or as JSON with [`AudioGraphSnapshot::to_json`].
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_seedling::prelude::*;
+//! fn dump_graph(graph: Res<AudioGraphSnapshot>) {
+//!     std::fs::write("audio_graph.dot", graph.to_dot()).unwrap();
+//! }
+//! ```
+//!
+//! With the `dev_tools` feature, `GraphExportPlugin` writes both
+//! formats whenever a key is pressed.
+
+use crate::context::AudioContext;
+use crate::node::{
+    label::{InternedNodeLabel, NodeLabels},
+    FirewheelNode,
+};
+use crate::pool::label::{InternedPoolLabel, PoolLabelContainer};
+use bevy_ecs::prelude::*;
+use bevy_utils::HashMap;
+use firewheel::{channel_config::ChannelConfig, node::NodeID};
+
+/// A snapshot of the audio graph's topology.
+///
+/// This is updated at the end of the [`SeedlingSystems::Flush`][crate::SeedlingSystems::Flush]
+/// set, so it reflects all connections and removals made during the frame.
+/// The snapshot is only rebuilt in frames where nodes are added, removed,
+/// relabelled, or connected through `bevy_seedling`. Changes made directly
+/// with [`AudioContext::with`] appear after the next such frame.
+///
+/// For more details, see the [module docs][self].
+#[derive(Debug, Default, Clone, Resource)]
+pub struct AudioGraphSnapshot {
+    nodes: Vec<NodeSnapshot>,
+    edges: Vec<EdgeSnapshot>,
+    /// Each node's index in `nodes`.
+    ids: HashMap<NodeID, usize>,
+    /// Each owned node's index in `nodes`.
+    entities: HashMap<Entity, usize>,
+}
+
+/// Whether `bevy_seedling` changed the graph's topology since the last snapshot.
+#[derive(Debug, Default, Resource)]
+pub(crate) struct GraphChanged(pub bool);
+
+/// A single node in an [`AudioGraphSnapshot`].
+#[derive(Debug, Clone)]
+pub struct NodeSnapshot {
+    /// The node's ID in the audio graph.
+    pub id: NodeID,
+    /// The entity that owns this node.
+    ///
+    /// This is `None` for nodes not managed by `bevy_seedling`,
+    /// such as the graph's input and output nodes.
+    pub entity: Option<Entity>,
+    /// The node's debug name.
+    pub debug_name: &'static str,
+    /// The node's input and output channel counts.
+    pub channel_config: ChannelConfig,
+    /// The node labels applied to the node's entity.
+    pub labels: Vec<InternedNodeLabel>,
+    /// The sampler pool this node belongs to, if any.
+    pub pool: Option<InternedPoolLabel>,
+}
+
+/// A single edge in an [`AudioGraphSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EdgeSnapshot {
+    /// The source node.
+    pub source: NodeID,
+    /// The output port on the source node.
+    pub source_port: u32,
+    /// The target node.
+    pub target: NodeID,
+    /// The input port on the target node.
+    pub target_port: u32,
+}
+
+impl AudioGraphSnapshot {
+    /// All nodes in the graph.
+    pub fn nodes(&self) -> &[NodeSnapshot] {
+        &self.nodes
+    }
+
+    /// All edges in the graph.
+    pub fn edges(&self) -> &[EdgeSnapshot] {
+        &self.edges
+    }
+
+    /// Get a node by its ID.
+    pub fn node(&self, id: NodeID) -> Option<&NodeSnapshot> {
+        self.index_of(id).map(|i| &self.nodes[i])
+    }
+
+    /// Get the node owned by `entity`.
+    pub fn node_for_entity(&self, entity: Entity) -> Option<&NodeSnapshot> {
+        self.entities.get(&entity).map(|i| &self.nodes[*i])
+    }
+
+    /// Iterate over the edges leaving a node.
+    pub fn outgoing(&self, id: NodeID) -> impl Iterator<Item = &EdgeSnapshot> {
+        self.edges.iter().filter(move |e| e.source == id)
+    }
+
+    /// Iterate over the edges entering a node.
+    pub fn incoming(&self, id: NodeID) -> impl Iterator<Item = &EdgeSnapshot> {
+        self.edges.iter().filter(move |e| e.target == id)
+    }
+
+    /// The position of a node within [`AudioGraphSnapshot::nodes`].
+    fn index_of(&self, id: NodeID) -> Option<usize> {
+        self.ids.get(&id).copied()
+    }
+
+    /// Render the graph in the Graphviz DOT format.
+    ///
+    /// Nodes are annotated with their debug name, entity, node labels,
+    /// and pool label. Edges are annotated with their port pairs,
+    /// written as `source -> target`.
+    pub fn to_dot(&self) -> String {
+        use core::fmt::Write;
+
+        let mut dot =
+            String::from("digraph audio_graph {\n    rankdir=LR;\n    node [shape=box];\n");
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let mut label = node.debug_name.to_string();
+
+            if let Some(entity) = node.entity {
+                write!(label, "\nentity: {entity}").unwrap();
+            }
+            for node_label in &node.labels {
+                write!(label, "\nlabel: {node_label:?}").unwrap();
+            }
+            if let Some(pool) = node.pool {
+                write!(label, "\npool: {pool:?}").unwrap();
+            }
+
+            writeln!(dot, "    n{i} [label=\"{}\"];", escape_dot(&label)).unwrap();
+        }
+
+        // Edges between the same nodes are merged into one.
+        let mut merged: Vec<(usize, usize, Vec<(u32, u32)>)> = Vec::new();
+        let mut merged_index = HashMap::new();
+        for edge in &self.edges {
+            let (Some(source), Some(target)) =
+                (self.index_of(edge.source), self.index_of(edge.target))
+            else {
+                continue;
+            };
+
+            let ports = (edge.source_port, edge.target_port);
+            match merged_index.get(&(source, target)) {
+                Some(i) => merged[*i].2.push(ports),
+                None => {
+                    merged_index.insert((source, target), merged.len());
+                    merged.push((source, target, vec![ports]));
+                }
+            }
+        }
+
+        for (source, target, ports) in merged {
+            let ports: Vec<_> = ports.iter().map(|(s, t)| format!("{s} -> {t}")).collect();
+
+            writeln!(
+                dot,
+                "    n{source} -> n{target} [label=\"{}\"];",
+                escape_dot(&ports.join(", "))
+            )
+            .unwrap();
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Render the graph as pretty-printed JSON.
+    ///
+    /// Edges refer to nodes by their index in the `nodes` array.
+    pub fn to_json(&self) -> String {
+        let nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                serde_json::json!({
+                    "debug_name": node.debug_name,
+                    "entity": node.entity.map(|e| e.to_string()),
+                    "inputs": node.channel_config.num_inputs.get(),
+                    "outputs": node.channel_config.num_outputs.get(),
+                    "labels": node.labels.iter().map(|l| format!("{l:?}")).collect::<Vec<_>>(),
+                    "pool": node.pool.map(|p| format!("{p:?}")),
+                })
+            })
+            .collect();
+
+        let edges: Vec<_> = self
+            .edges
+            .iter()
+            .filter_map(|edge| {
+                Some(serde_json::json!({
+                    "source": self.index_of(edge.source)?,
+                    "source_port": edge.source_port,
+                    "target": self.index_of(edge.target)?,
+                    "target_port": edge.target_port,
+                }))
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&serde_json::json!({
+            "nodes": nodes,
+            "edges": edges,
+        }))
+        .unwrap_or_default()
+    }
+}
+
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Writes the [`AudioGraphSnapshot`] as DOT and JSON when a key is pressed.
+///
+/// The files are named `audio_graph.dot` and `audio_graph.json`
+/// and written to [`GraphExportPlugin::directory`].
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::{graph::GraphExportPlugin, prelude::*};
+/// App::new().add_plugins((
+///     DefaultPlugins,
+///     SeedlingPlugin::default(),
+///     GraphExportPlugin::default(),
+/// ));
+/// ```
+#[cfg(feature = "dev_tools")]
+#[derive(Debug, Clone)]
+pub struct GraphExportPlugin {
+    /// The key that triggers an export.
+    ///
+    /// Defaults to [`KeyCode::F10`][bevy_input::keyboard::KeyCode::F10].
+    pub key: bevy_input::keyboard::KeyCode,
+    /// The directory the files are written to.
+    ///
+    /// Defaults to the working directory.
+    pub directory: std::path::PathBuf,
+}
+
+#[cfg(feature = "dev_tools")]
+impl Default for GraphExportPlugin {
+    fn default() -> Self {
+        Self {
+            key: bevy_input::keyboard::KeyCode::F10,
+            directory: std::path::PathBuf::from("."),
+        }
+    }
+}
+
+#[cfg(feature = "dev_tools")]
+impl bevy_app::Plugin for GraphExportPlugin {
+    fn build(&self, app: &mut bevy_app::App) {
+        let GraphExportPlugin { key, directory } = self.clone();
+
+        app.add_systems(
+            bevy_app::Last,
+            (move |keys: Res<bevy_input::ButtonInput<bevy_input::keyboard::KeyCode>>,
+                   graph: Res<AudioGraphSnapshot>| {
+                if !keys.just_pressed(key) {
+                    return;
+                }
+
+                for (name, contents) in [
+                    ("audio_graph.dot", graph.to_dot()),
+                    ("audio_graph.json", graph.to_json()),
+                ] {
+                    let path = directory.join(name);
+
+                    match std::fs::write(&path, contents) {
+                        Ok(()) => bevy_log::info!("wrote audio graph to {}", path.display()),
+                        Err(e) => {
+                            bevy_log::error!(
+                                "failed to write audio graph to {}: {e}",
+                                path.display()
+                            )
+                        }
+                    }
+                }
+            })
+            .after(crate::SeedlingSystems::Flush),
+        );
+    }
+}
+
+pub(crate) fn update_snapshot(
+    nodes: Query<(
+        Entity,
+        &FirewheelNode,
+        Option<&NodeLabels>,
+        Option<&PoolLabelContainer>,
+    )>,
+    relabelled: Query<
+        (),
+        Or<(
+            Added<FirewheelNode>,
+            Changed<NodeLabels>,
+            Changed<PoolLabelContainer>,
+        )>,
+    >,
+    mut changed: ResMut<GraphChanged>,
+    mut snapshot: ResMut<AudioGraphSnapshot>,
+    mut context: ResMut<AudioContext>,
+) {
+    if !changed.0 && relabelled.is_empty() {
+        return;
+    }
+    changed.0 = false;
+
+    let (graph_nodes, edges) = context.with(|context| {
+        let nodes: Vec<_> = context
+            .nodes()
+            .into_iter()
+            .map(|n| (n.id, n.info.debug_name, n.info.channel_config))
+            .collect();
+
+        let edges: Vec<_> = context
+            .edges()
+            .into_iter()
+            .map(|e| EdgeSnapshot {
+                source: e.src_node,
+                source_port: e.src_port,
+                target: e.dst_node,
+                target_port: e.dst_port,
+            })
+            .collect();
+
+        (nodes, edges)
+    });
+
+    let entities: HashMap<_, _> = nodes
+        .iter()
+        .map(|(entity, node, labels, pool)| (node.0, (entity, labels, pool)))
+        .collect();
+
+    snapshot.nodes = graph_nodes
+        .into_iter()
+        .map(|(id, debug_name, channel_config)| {
+            let owner = entities.get(&id);
+
+            NodeSnapshot {
+                id,
+                entity: owner.map(|(entity, ..)| *entity),
+                debug_name,
+                channel_config,
+                labels: owner
+                    .and_then(|(_, labels, _)| *labels)
+                    .map(|labels| labels.to_vec())
+                    .unwrap_or_default(),
+                pool: owner.and_then(|(.., pool)| pool.map(|p| p.label)),
+            }
+        })
+        .collect();
+    snapshot.edges = edges;
+
+    let snapshot = snapshot.as_mut();
+    snapshot.ids = snapshot
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.id, i))
+        .collect();
+    snapshot.entities = snapshot
+        .nodes
+        .iter()
+        .enumerate()
+        .filter_map(|(i, node)| Some((node.entity?, i)))
+        .collect();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        prelude::{Connect, MainBus, NodeLabel},
+        profiling::ProfilingBackend,
+        SeedlingPlugin,
+    };
+    use bevy::prelude::*;
+    use firewheel::nodes::volume::VolumeNode;
+
+    #[derive(NodeLabel, Debug, Clone, PartialEq, Eq, Hash)]
+    struct Bus;
+
+    fn prepare_app() -> App {
+        let mut app = App::new();
+
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            SeedlingPlugin::<ProfilingBackend> {
+                default_pool_size: None,
+                main_bus_limiter: None,
+                ..SeedlingPlugin::<ProfilingBackend>::new()
+            },
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands.spawn((VolumeNode::default(), Bus));
+            commands.spawn(VolumeNode::default()).connect(Bus);
+        });
+
+        app.finish();
+        app.cleanup();
+        app.update();
+
+        app
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let app = prepare_app();
+        let snapshot = app.world().resource::<AudioGraphSnapshot>();
+
+        // input node, output node, MainBus, Bus, and the source
+        assert_eq!(snapshot.nodes().len(), 5);
+
+        let bus = snapshot
+            .nodes()
+            .iter()
+            .find(|n| n.labels.contains(&Bus.intern()))
+            .unwrap();
+        let main = snapshot
+            .nodes()
+            .iter()
+            .find(|n| n.labels.contains(&MainBus.intern()))
+            .unwrap();
+
+        assert!(bus.entity.is_some());
+        assert_eq!(snapshot.incoming(bus.id).count(), 2);
+        assert!(snapshot.outgoing(bus.id).all(|e| e.target == main.id));
+    }
+
+    #[test]
+    fn test_snapshot_changes() {
+        let mut app = prepare_app();
+        app.update();
+        app.update();
+
+        let last_changed = app
+            .world()
+            .resource_ref::<AudioGraphSnapshot>()
+            .last_changed();
+
+        // Nothing changes, so the snapshot isn't rebuilt.
+        app.update();
+        let snapshot = app.world().resource_ref::<AudioGraphSnapshot>();
+        assert_eq!(snapshot.last_changed(), last_changed);
+
+        let entity = app.world_mut().spawn(VolumeNode::default()).id();
+        app.update();
+
+        let snapshot = app.world().resource_ref::<AudioGraphSnapshot>();
+        assert_ne!(snapshot.last_changed(), last_changed);
+        assert_eq!(snapshot.nodes().len(), 6);
+
+        let node = snapshot.node_for_entity(entity).unwrap();
+        assert_eq!(snapshot.node(node.id).unwrap().entity, Some(entity));
+    }
+
+    #[test]
+    fn test_export() {
+        let app = prepare_app();
+        let snapshot = app.world().resource::<AudioGraphSnapshot>();
+
+        let dot = snapshot.to_dot();
+        assert!(dot.starts_with("digraph audio_graph {"));
+        assert!(dot.contains("label: Bus"));
+        assert!(dot.contains("0 -> 0, 1 -> 1"));
+
+        let json: serde_json::Value = serde_json::from_str(&snapshot.to_json()).unwrap();
+        assert_eq!(json["nodes"].as_array().unwrap().len(), 5);
+        assert_eq!(
+            json["edges"].as_array().unwrap().len(),
+            snapshot.edges().len()
+        );
+    }
+}
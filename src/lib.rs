@@ -91,7 +91,15 @@
 //! ### Playing samples
 //! - [The `SamplePlayer` type][prelude::SamplePlayer]
 //! - [Controlling playback][prelude::PlaybackSettings]
+//! - [Pausing and resuming][prelude::PlaybackState]
+//! - [Seeking][prelude::Seek]
+//! - [Speed and pitch][prelude::PlaybackSpeed]
+//! - [Loop regions][prelude::LoopRegion]
+//! - [Sample variation sets][sample::set]
+//! - [Data-driven sound cues][cue]
+//! - [Streaming and loader settings][sample::SampleLoaderSettings]
 //! - [The sample lifecycle][prelude::SamplePlayer#lifecycle]
+//! - [Lifecycle events][sample::events]
 //! - [Applying effects][prelude::SamplePlayer#applying-effects]
 //!
 //! ### Sampler pools
@@ -100,6 +108,7 @@
 //!   - [Constructing pools][prelude::Pool#constructing-pools]
 //!   - [Playing samples in a pool][prelude::Pool#playing-samples-in-a-pool]
 //!   - [Pool architecture][prelude::Pool#architecture]
+//!   - [Voice stealing][prelude::StealPolicy]
 //! - [The default pool][prelude::DefaultPool]
 //!
 //! ### Routing audio
@@ -112,6 +121,9 @@
 //! - [Creating and registering nodes][prelude::RegisterNode#creating-and-registering-nodes]
 //! - [Synchronizing ECS and audio types][prelude::RegisterNode#synchronizing-ecs-and-audio-types]
 //!
+//! ### Testing and rendering
+//! - [Offline rendering][offline]
+//!
 //! ## Feature flags
 //!
 //! | Flag | Description | Default feature |
@@ -222,11 +234,15 @@ use bevy_ecs::prelude::*;
 use firewheel::{backend::AudioBackend, CpalBackend};
 
 pub mod context;
+pub mod cue;
 pub mod edge;
 pub mod fixed_vec;
+pub mod graph;
 pub mod node;
 pub mod nodes;
+pub mod offline;
 pub mod pool;
+pub mod reflect;
 pub mod sample;
 pub mod spatial;
 pub mod timeline;
@@ -238,25 +254,42 @@ pub mod prelude {
     //! All `bevy_seedlings`'s important types and traits.
 
     pub use crate::context::AudioContext;
-    pub use crate::edge::{Connect, Disconnect, EdgeTarget};
+    pub use crate::cue::{CuePlayer, RegisterCuePool, SoundCue};
+    pub use crate::edge::{Connect, Connection, Connections, Disconnect, EdgeTarget};
+    pub use crate::graph::AudioGraphSnapshot;
     pub use crate::node::{
         label::{MainBus, NodeLabel},
         FirewheelNode, RegisterNode,
     };
     pub use crate::nodes::{
+        biquad::{BiquadConfig, BiquadKind, BiquadNode},
         bpf::{BandPassConfig, BandPassNode},
+        compressor::{CompressorConfig, CompressorNode},
+        eq::{EqBand, ParametricEqConfig, ParametricEqNode},
         freeverb::FreeverbNode,
+        limiter::{LimiterConfig, LimiterNode, LimiterReading, MainBusLimiter},
         lpf::{LowPassConfig, LowPassNode},
+        meter::{MainBusMeter, MeterConfig, MeterNode, MeterReading},
         send::{SendConfig, SendNode},
     };
     pub use crate::pool::{
         builder::{Pool, PoolBuilder},
         label::{DefaultPool, PoolLabel},
+        policy::{QueueTimeout, StealPolicy},
         PoolCommands, PoolDespawn,
     };
-    pub use crate::sample::{OnComplete, PlaybackSettings, SamplePlayer};
+    pub use crate::sample::events::{
+        LoopWrapped, PlaybackCompleted, PlaybackStarted, PlaybackStolen, SampleLoadFailed,
+    };
+    pub use crate::sample::set::{SampleSet, SampleSetPlayer, SelectionMode};
+    pub use crate::sample::{
+        Fade, LoopRegion, OnComplete, PlaybackPosition, PlaybackSettings, PlaybackSpeed,
+        PlaybackState, Playhead, SamplePlayer, SamplePriority, Seek,
+    };
     pub use crate::spatial::{
-        DefaultSpatialScale, SpatialListener2D, SpatialListener3D, SpatialScale,
+        AirAbsorption, AttenuationCurve, DefaultSpatialScale, Doppler, EmitterCone, ListenerMixing,
+        Rolloff, SpatialAttenuation, SpatialListener2D, SpatialListener3D, SpatialScale,
+        SpatialVelocity,
     };
     pub use crate::SeedlingPlugin;
 
@@ -328,6 +361,25 @@ pub struct SeedlingPlugin<B: AudioBackend = CpalBackend> {
     /// maximum size. Setting this field to `None`
     /// will disabled dynamic pools entirely.
     pub dynamic_pool_range: Option<core::ops::RangeInclusive<usize>>,
+
+    /// The limiter inserted between the [`MainBus`][prelude::MainBus]
+    /// and the output, labelled [`MainBusLimiter`][prelude::MainBusLimiter].
+    /// Setting this field to `None` will connect the
+    /// [`MainBus`][prelude::MainBus] directly to the output.
+    ///
+    /// Note that the limiter delays the output by its
+    /// [`lookahead`][prelude::LimiterConfig::lookahead], 5ms by default.
+    /// If your application is sensitive to latency, or you'd like
+    /// to handle mastering yourself, set this field to `None`.
+    pub main_bus_limiter: Option<prelude::LimiterNode>,
+
+    /// The meter inserted after the [`MainBus`][prelude::MainBus],
+    /// labelled [`MainBusMeter`][prelude::MainBusMeter].
+    /// This measures the final mix before the
+    /// [`main_bus_limiter`][Self::main_bus_limiter].
+    ///
+    /// Setting this field to `None`, the default, inserts no meter.
+    pub main_bus_meter: Option<prelude::MeterConfig>,
 }
 
 impl Default for SeedlingPlugin<CpalBackend> {
@@ -347,6 +399,8 @@ where
             stream_config: Default::default(),
             default_pool_size: Some(24),
             dynamic_pool_range: Some(4..=16),
+            main_bus_limiter: Some(Default::default()),
+            main_bus_meter: None,
         }
     }
 }
@@ -367,18 +421,56 @@ where
         app.insert_resource(context)
             .init_resource::<edge::NodeMap>()
             .init_resource::<node::PendingRemovals>()
+            .init_resource::<node::UnreflectedNodes>()
             .init_resource::<spatial::DefaultSpatialScale>()
+            .init_resource::<graph::AudioGraphSnapshot>()
+            .init_resource::<graph::GraphChanged>()
             .insert_resource(pool::dynamic::DynamicPoolRange(
                 self.dynamic_pool_range.clone(),
             ))
             .init_asset::<sample::Sample>()
             .register_asset_loader(sample::SampleLoader { sample_rate })
-            .register_node::<VolumeNode>()
-            .register_node::<VolumePanNode>()
-            .register_node::<SpatialBasicNode>()
+            .init_asset::<sample::set::SampleSet>()
+            .register_asset_loader(sample::set::SampleSetLoader)
+            .init_resource::<sample::set::SampleSetStates>()
+            .init_asset::<cue::SoundCue>()
+            .register_asset_loader(cue::SoundCueLoader)
+            .init_resource::<cue::CuePools>()
+            .register_type::<sample::SamplePlayer>()
+            .register_type::<sample::PlaybackSettings>()
+            .register_type::<sample::Seek>()
+            .register_type::<sample::PlaybackPosition>()
+            .register_type::<sample::SamplePriority>()
+            .register_type::<sample::PlaybackSpeed>()
+            .register_type::<sample::PlaybackState>()
+            .register_type::<sample::QueuedSample>()
+            .register_type::<sample::set::SampleSetPlayer>()
+            .register_type::<cue::CuePlayer>()
+            .register_type::<pool::policy::StealPolicy>()
+            .register_type::<pool::policy::QueueTimeout>()
+            .register_type::<node::ExcludeNode>()
+            .register_type::<node::ParamFollower>()
+            .register_type::<node::UnreflectedNode>()
+            .register_type::<spatial::SpatialScale>()
+            .register_type::<spatial::DefaultSpatialScale>()
+            .register_type::<spatial::SpatialListener2D>()
+            .register_type::<spatial::SpatialListener3D>()
+            .register_type::<spatial::ListenerMixing>()
+            .register_type::<spatial::SpatialAttenuation>()
+            .register_type::<spatial::Doppler>()
+            .register_type::<spatial::EmitterCone>()
+            .register_type::<spatial::SpatialVelocity>()
+            .register_type::<edge::Connections>()
+            .register_type::<node::label::MainBus>()
+            .register_required_components::<node::FirewheelNode, edge::Connections>()
+            .register_required_components::<SpatialBasicNode, spatial::EmitterBase>()
             .register_simple_node::<StereoToMonoNode>()
             .register_simple_node::<SamplerNode>();
 
+        node::register_node_state::<VolumeNode, reflect::VolumeNodeState>(app);
+        node::register_node_state::<VolumePanNode, reflect::VolumePanNodeState>(app);
+        node::register_node_state::<SpatialBasicNode, reflect::SpatialBasicNodeState>(app);
+
         #[cfg(feature = "stream")]
         app.register_simple_node::<StreamReaderNode>()
             .register_simple_node::<StreamWriterNode>();
@@ -396,9 +488,29 @@ where
         .add_systems(
             Last,
             (
-                (spatial::update_2d_emitters, spatial::update_3d_emitters)
+                (
+                    spatial::update_2d_emitters,
+                    spatial::update_3d_emitters,
+                    sample::set::play_sample_sets,
+                )
                     .before(SeedlingSystems::Acquire),
-                edge::auto_connect
+                spatial::track_velocities
+                    .before(spatial::update_2d_emitters)
+                    .before(spatial::update_3d_emitters),
+                (
+                    node::sync_node_state::<VolumeNode, reflect::VolumeNodeState>,
+                    node::sync_node_state::<VolumePanNode, reflect::VolumePanNodeState>,
+                    spatial::sync_emitter_state
+                        .after(spatial::update_2d_emitters)
+                        .after(spatial::update_3d_emitters),
+                )
+                    .before(SeedlingSystems::Acquire),
+                (cue::reload_cues, cue::spawn_cues, cue::finish_cues)
+                    .chain()
+                    .before(sample::set::play_sample_sets),
+                node::restore_unreflected_nodes.before(SeedlingSystems::Acquire),
+                (edge::restore_connections, edge::auto_connect)
+                    .chain()
                     .before(SeedlingSystems::Connect)
                     .after(SeedlingSystems::Acquire),
                 (edge::process_connections, edge::process_disconnections)
@@ -408,6 +520,7 @@ where
                     node::process_removals,
                     node::flush_events,
                     context::update_context,
+                    graph::update_snapshot,
                 )
                     .chain()
                     .in_set(SeedlingSystems::Flush),
@@ -416,7 +529,10 @@ where
         .add_systems(
             PreStartup,
             (
-                node::label::insert_main_bus,
+                node::label::insert_main_bus(
+                    self.main_bus_limiter.clone(),
+                    self.main_bus_meter.clone(),
+                ),
                 move |mut commands: Commands| {
                     if let Some(size) = sample_pool_size {
                         Pool::new(DefaultPool, size).spawn(&mut commands);
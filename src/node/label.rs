@@ -6,9 +6,12 @@
 //!
 //! Any node that doesn't provide an explicit connection when spawned
 //! will be automatically connected to [MainBus].
-use crate::edge::NodeMap;
-use crate::prelude::{AudioContext, Connect};
+use crate::edge::{ConnectCommands, NodeMap};
+use crate::prelude::{
+    AudioContext, Connect, LimiterNode, MainBusLimiter, MainBusMeter, MeterConfig, MeterNode,
+};
 use bevy_ecs::{component::ComponentId, intern::Interned, prelude::*, world::DeferredWorld};
+use bevy_reflect::Reflect;
 use firewheel::{nodes::volume::VolumeNode, Volume};
 use smallvec::SmallVec;
 
@@ -111,23 +114,45 @@ bevy_ecs::define_label!(
 ///     params.volume = Volume::Linear(0.0);
 /// }
 /// ```
-#[derive(NodeLabel, Debug, Clone, PartialEq, Eq, Hash)]
+///
+/// By default, the [`MainBus`] reaches the output through a
+/// [`LimiterNode`] labelled [`MainBusLimiter`]. This can be configured
+/// with [`SeedlingPlugin::main_bus_limiter`][crate::SeedlingPlugin::main_bus_limiter].
+/// To measure the final mix, a [`MeterNode`] labelled [`MainBusMeter`]
+/// can be inserted before the limiter with
+/// [`SeedlingPlugin::main_bus_meter`][crate::SeedlingPlugin::main_bus_meter].
+#[derive(NodeLabel, Reflect, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MainBus;
 
 /// A type-erased node label.
 pub type InternedNodeLabel = Interned<dyn NodeLabel>;
 
-pub(crate) fn insert_main_bus(mut commands: Commands, mut context: ResMut<AudioContext>) {
-    let terminal_node = context.with(|context| context.graph_out_node_id());
+pub(crate) fn insert_main_bus(
+    limiter: Option<LimiterNode>,
+    meter: Option<MeterConfig>,
+) -> impl FnMut(Commands, ResMut<AudioContext>) {
+    move |mut commands: Commands, mut context: ResMut<AudioContext>| {
+        let terminal_node = context.with(|context| context.graph_out_node_id());
 
-    commands
-        .spawn((
+        let main_bus = commands.spawn((
             VolumeNode {
                 volume: Volume::Linear(1.),
             },
             MainBus,
-        ))
-        .connect(terminal_node);
+        ));
+
+        let mut chain = ConnectCommands::new(main_bus);
+
+        if let Some(meter) = meter.clone() {
+            chain = chain.chain_node((MeterNode::default(), meter, MainBusMeter));
+        }
+
+        if let Some(limiter) = limiter.clone() {
+            chain = chain.chain_node((limiter, MainBusLimiter));
+        }
+
+        chain.connect(terminal_node);
+    }
 }
 
 /// A collection of all node labels applied to an entity.
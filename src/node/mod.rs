@@ -1,11 +1,15 @@
 //! Audio node registration and management.
 
 use crate::edge::NodeMap;
+use crate::graph::GraphChanged;
 use crate::pool;
+use crate::reflect::NodeState;
 use crate::{prelude::AudioContext, SeedlingSystems};
 use bevy_app::Last;
 use bevy_ecs::{prelude::*, world::DeferredWorld};
 use bevy_log::error;
+use bevy_reflect::{std_traits::ReflectDefault, GetTypeRegistration, Reflect, TypePath};
+use bevy_utils::HashMap;
 use firewheel::diff::PathBuilder;
 use firewheel::{
     diff::{Diff, Patch},
@@ -112,6 +116,7 @@ fn acquire_id<T>(
 ///
 /// Once you've implemented [`AudioNode`] on a type, there are two ways to register it:
 /// - [`RegisterNode::register_node`] for nodes that also implement [`Diff`] and [`Patch`]
+///   (or [`RegisterNode::register_reflected_node`] if they also implement [`Reflect`])
 /// - [`RegisterNode::register_simple_node`] for nodes that do not implement [`Diff`] and [`Patch`]
 ///
 /// ```ignore
@@ -171,6 +176,42 @@ pub trait RegisterNode {
     where
         T: AudioNode<Configuration: Component + Clone> + Diff + Patch + Component + Clone;
 
+    /// Register a reflected audio node with automatic diffing.
+    ///
+    /// In addition to [`RegisterNode::register_node`], this registers
+    /// the node's type with the [`AppTypeRegistry`][bevy_ecs::reflect::AppTypeRegistry],
+    /// allowing it to appear in inspectors and be saved in scenes.
+    ///
+    /// To reflect Firewheel types in your node's fields,
+    /// see the [`reflect`][crate::reflect] module.
+    fn register_reflected_node<T>(&mut self) -> &mut Self
+    where
+        T: AudioNode<Configuration: Component + Clone>
+            + Diff
+            + Patch
+            + Component
+            + Clone
+            + GetTypeRegistration;
+
+    /// Register an audio node with automatic diffing, but without reflection.
+    ///
+    /// This is useful for nodes that can't implement [`Reflect`],
+    /// like those with Firewheel types in their fields. These nodes are
+    /// saved in scenes as an [`UnreflectedNode`], so they're restored
+    /// with their default parameters.
+    ///
+    /// The node is identified by its [`TypePath`], which can be derived
+    /// even when [`Reflect`] can't.
+    fn register_unreflected_node<T>(&mut self) -> &mut Self
+    where
+        T: AudioNode<Configuration: Component + Clone>
+            + Diff
+            + Patch
+            + Component
+            + Clone
+            + Default
+            + TypePath;
+
     /// Register an audio node without automatic diffing.
     ///
     /// This will allow audio entities to automatically
@@ -185,8 +226,79 @@ impl RegisterNode for bevy_app::App {
     fn register_node<T>(&mut self) -> &mut Self
     where
         T: AudioNode<Configuration: Component + Clone> + Diff + Patch + Component + Clone,
+    {
+        register_diffing::<T>(self)
+    }
+
+    fn register_reflected_node<T>(&mut self) -> &mut Self
+    where
+        T: AudioNode<Configuration: Component + Clone>
+            + Diff
+            + Patch
+            + Component
+            + Clone
+            + GetTypeRegistration,
+    {
+        register_diffing::<T>(self.register_type::<T>())
+    }
+
+    fn register_unreflected_node<T>(&mut self) -> &mut Self
+    where
+        T: AudioNode<Configuration: Component + Clone>
+            + Diff
+            + Patch
+            + Component
+            + Clone
+            + Default
+            + TypePath,
+    {
+        register_unreflected::<T>(self, T::type_path(), restore_default::<T>)
+    }
+
+    fn register_simple_node<T>(&mut self) -> &mut Self
+    where
+        T: AudioNode<Configuration: Component + Clone> + Component + Clone,
     {
         let world = self.world_mut();
+        world.register_required_components::<T, Events>();
+        world.register_required_components::<T, T::Configuration>();
+        world.register_required_components::<T, pool::dynamic::AutoRegister<T>>();
+        world
+            .get_resource_or_init::<pool::PoolTypeRegistry>()
+            .register::<T>();
+
+        self.add_systems(Last, acquire_id::<T>.in_set(SeedlingSystems::Acquire))
+    }
+}
+
+/// Register an unreflected node's [`UnreflectedNode`] marker and its restorer.
+fn register_unreflected<T>(
+    app: &mut bevy_app::App,
+    type_path: &'static str,
+    restore: fn(&mut EntityCommands),
+) -> &mut bevy_app::App
+where
+    T: AudioNode<Configuration: Component + Clone> + Diff + Patch + Component + Clone,
+{
+    let world = app.world_mut();
+    world.register_required_components_with::<T, UnreflectedNode>(|| {
+        UnreflectedNode(type_path.into())
+    });
+    world
+        .get_resource_or_init::<UnreflectedNodes>()
+        .0
+        .insert(type_path, restore);
+
+    register_diffing::<T>(app)
+}
+
+/// Register the components and systems shared by all diffing nodes.
+fn register_diffing<T>(app: &mut bevy_app::App) -> &mut bevy_app::App
+where
+    T: AudioNode<Configuration: Component + Clone> + Diff + Patch + Component + Clone,
+{
+    {
+        let world = app.world_mut();
 
         world.register_component_hooks::<T>().on_insert(
             |mut world: DeferredWorld, entity: Entity, _| {
@@ -197,28 +309,104 @@ impl RegisterNode for bevy_app::App {
         world.register_required_components::<T, Events>();
         world.register_required_components::<T, T::Configuration>();
         world.register_required_components::<T, pool::dynamic::AutoRegister<T>>();
+        world
+            .get_resource_or_init::<pool::PoolTypeRegistry>()
+            .register::<T>();
+    }
 
-        self.add_systems(
-            Last,
-            (
-                acquire_id::<T>.in_set(SeedlingSystems::Acquire),
-                (param_follower::<T>, generate_param_events::<T>)
-                    .chain()
-                    .in_set(SeedlingSystems::Queue),
-            ),
-        )
+    app.add_systems(
+        Last,
+        (
+            acquire_id::<T>.in_set(SeedlingSystems::Acquire),
+            (param_follower::<T>, generate_param_events::<T>)
+                .chain()
+                .in_set(SeedlingSystems::Queue),
+        ),
+    )
+}
+
+/// Records the [`TypePath`] of a node registered with
+/// [`RegisterNode::register_unreflected_node`].
+///
+/// Since unreflected nodes can't be saved in scenes, this marker is
+/// saved in their place. When it's loaded, the node is restored with
+/// its default parameters. Firewheel's volume and spatial nodes instead
+/// save their parameters in [state components][crate::reflect],
+/// which are applied to the restored node. Since Firewheel's
+/// nodes don't implement [`TypePath`], they're recorded by
+/// their state's type path.
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+pub struct UnreflectedNode(String);
+
+impl UnreflectedNode {
+    /// The node's type path.
+    pub fn type_path(&self) -> &str {
+        &self.0
     }
+}
 
-    fn register_simple_node<T>(&mut self) -> &mut Self
-    where
-        T: AudioNode<Configuration: Component + Clone> + Component + Clone,
-    {
-        let world = self.world_mut();
-        world.register_required_components::<T, Events>();
-        world.register_required_components::<T, T::Configuration>();
-        world.register_required_components::<T, pool::dynamic::AutoRegister<T>>();
+/// Restoration functions for each [`UnreflectedNode`], keyed by type path.
+#[derive(Default, Resource)]
+pub(crate) struct UnreflectedNodes(HashMap<&'static str, fn(&mut EntityCommands)>);
 
-        self.add_systems(Last, acquire_id::<T>.in_set(SeedlingSystems::Acquire))
+fn restore_default<T: Component + Default>(commands: &mut EntityCommands) {
+    commands.entry::<T>().or_default();
+}
+
+/// Register an unreflected node whose parameters are saved in scenes through a [`NodeState`].
+///
+/// The state should be kept up to date with a system like [`sync_node_state`].
+pub(crate) fn register_node_state<T, S>(app: &mut bevy_app::App) -> &mut bevy_app::App
+where
+    T: AudioNode<Configuration: Component + Clone> + Diff + Patch + Component + Clone + Default,
+    S: NodeState<T> + GetTypeRegistration + TypePath,
+{
+    app.register_type::<S>()
+        .world_mut()
+        .register_required_components_with::<T, S>(|| S::capture(&T::default()));
+
+    register_unreflected::<T>(app, S::type_path(), restore_state::<T, S>)
+}
+
+fn restore_state<T: Component + Default, S: NodeState<T>>(commands: &mut EntityCommands) {
+    commands.queue(|mut entity: EntityWorldMut| {
+        if entity.contains::<T>() {
+            return;
+        }
+
+        let mut node = T::default();
+        if let Some(state) = entity.get::<S>() {
+            state.apply(&mut node);
+        }
+
+        entity.insert(node);
+    });
+}
+
+/// Capture changes to unreflected nodes in their [`NodeState`].
+pub(crate) fn sync_node_state<T: Component, S: NodeState<T> + PartialEq>(
+    mut nodes: Query<(&T, &mut S), Changed<T>>,
+) {
+    for (node, mut state) in nodes.iter_mut() {
+        state.set_if_neq(S::capture(node));
+    }
+}
+
+/// Insert the nodes of [`UnreflectedNode`]s loaded without them.
+pub(crate) fn restore_unreflected_nodes(
+    nodes: Query<(Entity, &UnreflectedNode), Added<UnreflectedNode>>,
+    restorers: Res<UnreflectedNodes>,
+    mut commands: Commands,
+) {
+    for (entity, node) in nodes.iter() {
+        match restorers.0.get(node.type_path()) {
+            Some(restore) => restore(&mut commands.entity(entity)),
+            None => error!(
+                "failed to restore node `{}`: its type isn't registered",
+                node.type_path()
+            ),
+        }
     }
 }
 
@@ -263,8 +451,14 @@ impl PendingRemovals {
 
 pub(crate) fn process_removals(
     mut removals: ResMut<PendingRemovals>,
+    mut graph_changed: ResMut<GraphChanged>,
     mut context: ResMut<AudioContext>,
 ) {
+    if removals.0.is_empty() {
+        return;
+    }
+    graph_changed.0 = true;
+
     context.with(|context| {
         for node in removals.0.drain(..) {
             if context.remove_node(node).is_err() {
@@ -314,7 +508,8 @@ pub(crate) fn flush_events(
 /// Nodes inserted into an entity with [`ExcludeNode`] can be
 /// thought of as *remote nodes* that other graph-connected
 /// nodes can track.
-#[derive(Debug, Default, Component)]
+#[derive(Debug, Default, Component, Reflect)]
+#[reflect(Component, Default)]
 pub struct ExcludeNode;
 
 /// A component that allows one entity's parameters to track another's.
@@ -347,7 +542,8 @@ pub struct ExcludeNode;
 /// // SamplePlayer: (SamplePlayer, SpatialBasicNode, ExcludeNode)
 /// # }
 /// ```
-#[derive(Debug, Component)]
+#[derive(Debug, Component, Reflect)]
+#[reflect(Component)]
 pub struct ParamFollower(pub Entity);
 
 /// Apply diffing and patching between two sets of parameters
@@ -0,0 +1,412 @@
+//! Second-order filters.
+
+use crate::timeline::Timeline;
+use bevy_ecs::prelude::*;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use firewheel::{
+    channel_config::{ChannelConfig, NonZeroChannelCount},
+    clock::ClockSeconds,
+    diff::{Diff, EventQueue, Patch, PatchError, PathBuilder},
+    event::{NodeEventList, ParamData},
+    node::{
+        AudioNode, AudioNodeInfo, AudioNodeProcessor, ConstructProcessorContext, ProcBuffers,
+        ProcInfo, ProcessStatus,
+    },
+};
+
+/// The response of a [`BiquadNode`] or [`EqBand`][super::eq::EqBand].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+pub enum BiquadKind {
+    /// Attenuates frequencies above the cutoff.
+    #[default]
+    LowPass,
+    /// Attenuates frequencies below the cutoff.
+    HighPass,
+    /// Passes frequencies around the center, attenuating the rest.
+    BandPass,
+    /// Boosts or cuts frequencies below the corner by the gain.
+    LowShelf,
+    /// Boosts or cuts frequencies above the corner by the gain.
+    HighShelf,
+    /// Boosts or cuts frequencies around the center by the gain.
+    Peaking,
+    /// Removes frequencies around the center.
+    Notch,
+    /// Passes all frequencies, shifting their phase around the center.
+    AllPass,
+}
+
+impl Diff for BiquadKind {
+    fn diff<E: EventQueue>(&self, baseline: &Self, path: PathBuilder, event_queue: &mut E) {
+        if self != baseline {
+            event_queue.push_param(ParamData::any(*self), path);
+        }
+    }
+}
+
+impl Patch for BiquadKind {
+    fn patch(&mut self, data: &ParamData, _: &[u32]) -> Result<(), PatchError> {
+        *self = *data.downcast_ref().ok_or(PatchError::InvalidData)?;
+
+        Ok(())
+    }
+}
+
+/// A second-order filter.
+///
+/// The filter's response is selected with [`BiquadNode::kind`], and
+/// may be changed at any time. [`BiquadNode::gain`] only affects the
+/// shelving and peaking responses.
+///
+/// Parameter changes are interpolated at audio rate using a
+/// topology that remains stable under fast modulation, so the
+/// frequency can be swept freely, such as for occlusion.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// # fn system(mut commands: Commands) {
+/// // A thin, radio-like band.
+/// commands
+///     .spawn(BiquadNode::new(BiquadKind::HighPass, 400.0, 0.9))
+///     .chain_node(BiquadNode::new(BiquadKind::LowPass, 3000.0, 0.9));
+///
+/// // A presence boost.
+/// commands.spawn(BiquadNode::new(BiquadKind::Peaking, 4000.0, 1.0).with_gain(4.0));
+/// # }
+/// ```
+#[derive(Diff, Patch, Debug, Clone, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct BiquadNode {
+    /// The filter's response.
+    pub kind: BiquadKind,
+    /// The cutoff, corner, or center frequency in hertz.
+    pub frequency: Timeline<f32>,
+    /// The shelving or peaking gain in decibels.
+    pub gain: Timeline<f32>,
+    /// The filter's *quality*, or resonance.
+    pub q: Timeline<f32>,
+}
+
+impl Default for BiquadNode {
+    fn default() -> Self {
+        Self::new(
+            BiquadKind::LowPass,
+            1000.0,
+            core::f32::consts::FRAC_1_SQRT_2,
+        )
+    }
+}
+
+impl BiquadNode {
+    /// Create a new [`BiquadNode`] with an initial frequency and quality.
+    ///
+    /// ```
+    /// # use bevy_seedling::prelude::*;
+    /// # use bevy::prelude::*;
+    /// # fn system(mut commands: Commands) {
+    /// commands.spawn(BiquadNode::new(BiquadKind::Notch, 60.0, 10.0));
+    /// # }
+    /// ```
+    pub fn new(kind: BiquadKind, frequency: f32, q: f32) -> Self {
+        Self {
+            kind,
+            frequency: Timeline::new(frequency),
+            gain: Timeline::new(0.0),
+            q: Timeline::new(q),
+        }
+    }
+
+    /// Set the initial shelving or peaking gain in decibels.
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = Timeline::new(gain);
+        self
+    }
+}
+
+/// [`BiquadNode`]'s configuration.
+#[derive(Debug, Component, Clone)]
+pub struct BiquadConfig {
+    /// The number of input and output channels.
+    pub channels: NonZeroChannelCount,
+}
+
+impl Default for BiquadConfig {
+    fn default() -> Self {
+        Self {
+            channels: NonZeroChannelCount::STEREO,
+        }
+    }
+}
+
+impl AudioNode for BiquadNode {
+    type Configuration = BiquadConfig;
+
+    fn info(&self, config: &Self::Configuration) -> AudioNodeInfo {
+        AudioNodeInfo::new()
+            .debug_name("biquad filter")
+            .channel_config(ChannelConfig {
+                num_inputs: config.channels.get(),
+                num_outputs: config.channels.get(),
+            })
+            .uses_events(true)
+    }
+
+    fn construct_processor(
+        &self,
+        config: &Self::Configuration,
+        cx: ConstructProcessorContext,
+    ) -> impl AudioNodeProcessor {
+        let sample_rate = cx.stream_info.sample_rate.get() as f32;
+        let coefficients = Coefficients::new(
+            self.kind,
+            self.frequency.get(),
+            self.gain.get(),
+            self.q.get(),
+            sample_rate,
+        );
+
+        BiquadProcessor {
+            params: self.clone(),
+            sample_rate,
+            filter: Filter::new(coefficients, config.channels.get().get() as usize),
+        }
+    }
+}
+
+/// The coefficients of a trapezoidal state-variable filter.
+///
+/// Unlike direct-form biquad coefficients, these can be
+/// interpolated linearly without the filter becoming unstable.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Coefficients {
+    a1: f32,
+    a2: f32,
+    a3: f32,
+    m0: f32,
+    m1: f32,
+    m2: f32,
+}
+
+impl Coefficients {
+    pub fn new(kind: BiquadKind, frequency: f32, gain: f32, q: f32, sample_rate: f32) -> Self {
+        let frequency = frequency.clamp(10.0, sample_rate * 0.49);
+        let q = q.max(0.025);
+        let g = (core::f32::consts::PI * frequency / sample_rate).tan();
+        let k = 1.0 / q;
+        let a = 10f32.powf(gain / 40.0);
+
+        let (g, k, m0, m1, m2) = match kind {
+            BiquadKind::LowPass => (g, k, 0.0, 0.0, 1.0),
+            BiquadKind::HighPass => (g, k, 1.0, -k, -1.0),
+            BiquadKind::BandPass => (g, k, 0.0, k, 0.0),
+            BiquadKind::LowShelf => (g / a.sqrt(), k, 1.0, k * (a - 1.0), a * a - 1.0),
+            BiquadKind::HighShelf => (g * a.sqrt(), k, a * a, k * (1.0 - a) * a, 1.0 - a * a),
+            BiquadKind::Peaking => {
+                let k = 1.0 / (q * a);
+                (g, k, 1.0, k * (a * a - 1.0), 0.0)
+            }
+            BiquadKind::Notch => (g, k, 1.0, -k, 0.0),
+            BiquadKind::AllPass => (g, k, 1.0, -2.0 * k, 0.0),
+        };
+
+        let a1 = 1.0 / (1.0 + g * (g + k));
+        let a2 = g * a1;
+        let a3 = g * a2;
+
+        Self {
+            a1,
+            a2,
+            a3,
+            m0,
+            m1,
+            m2,
+        }
+    }
+
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+
+        Self {
+            a1: mix(self.a1, other.a1),
+            a2: mix(self.a2, other.a2),
+            a3: mix(self.a3, other.a3),
+            m0: mix(self.m0, other.m0),
+            m1: mix(self.m1, other.m1),
+            m2: mix(self.m2, other.m2),
+        }
+    }
+}
+
+/// A multichannel state-variable filter whose coefficients
+/// glide towards their target over each block of frames.
+#[derive(Debug, Clone)]
+pub(crate) struct Filter {
+    current: Coefficients,
+    start: Coefficients,
+    target: Coefficients,
+    /// The integrator states of each channel.
+    state: Vec<(f32, f32)>,
+}
+
+impl Filter {
+    pub fn new(coefficients: Coefficients, channels: usize) -> Self {
+        Self {
+            current: coefficients,
+            start: coefficients,
+            target: coefficients,
+            state: vec![(0.0, 0.0); channels],
+        }
+    }
+
+    /// Begin gliding towards new coefficients.
+    pub fn set_target(&mut self, target: Coefficients) {
+        self.start = self.current;
+        self.target = target;
+    }
+
+    /// Advance the glide, where `t` runs from zero to one.
+    pub fn interpolate(&mut self, t: f32) {
+        self.current = if self.start == self.target {
+            self.target
+        } else {
+            self.start.lerp(&self.target, t)
+        };
+    }
+
+    /// Process a single sample for a channel.
+    pub fn process(&mut self, channel: usize, input: f32) -> f32 {
+        let Coefficients {
+            a1,
+            a2,
+            a3,
+            m0,
+            m1,
+            m2,
+        } = self.current;
+        let (ic1eq, ic2eq) = &mut self.state[channel];
+
+        let v3 = input - *ic2eq;
+        let v1 = a1 * *ic1eq + a2 * v3;
+        let v2 = *ic2eq + a2 * *ic1eq + a3 * v3;
+        *ic1eq = 2.0 * v1 - *ic1eq;
+        *ic2eq = 2.0 * v2 - *ic2eq;
+
+        m0 * input + m1 * v1 + m2 * v2
+    }
+
+    /// Clear the filter's memory.
+    pub fn reset(&mut self) {
+        self.state.fill((0.0, 0.0));
+        self.current = self.target;
+        self.start = self.target;
+    }
+}
+
+struct BiquadProcessor {
+    params: BiquadNode,
+    sample_rate: f32,
+    filter: Filter,
+}
+
+impl AudioNodeProcessor for BiquadProcessor {
+    fn process(
+        &mut self,
+        ProcBuffers {
+            inputs, outputs, ..
+        }: ProcBuffers,
+        proc_info: &ProcInfo,
+        events: NodeEventList,
+    ) -> ProcessStatus {
+        self.params.patch_list(events);
+
+        if proc_info.in_silence_mask.all_channels_silent(inputs.len()) {
+            self.filter.reset();
+            return ProcessStatus::ClearAllOutputs;
+        }
+
+        let seconds = proc_info.clock_seconds.start;
+        let frame_time = (proc_info.clock_seconds.end.0 - proc_info.clock_seconds.start.0)
+            / proc_info.frames as f64;
+
+        for frame in 0..proc_info.frames {
+            if frame % 32 == 0 {
+                let seconds = seconds + ClockSeconds(frame as f64 * frame_time);
+                let params = &mut self.params;
+
+                params.frequency.tick(seconds);
+                params.gain.tick(seconds);
+                params.q.tick(seconds);
+
+                self.filter.set_target(Coefficients::new(
+                    params.kind,
+                    params.frequency.get(),
+                    params.gain.get(),
+                    params.q.get(),
+                    self.sample_rate,
+                ));
+            }
+
+            self.filter.interpolate((frame % 32 + 1) as f32 / 32.0);
+
+            for (channel, (input, output)) in inputs.iter().zip(outputs.iter_mut()).enumerate() {
+                output[frame] = self.filter.process(channel, input[frame]);
+            }
+        }
+
+        ProcessStatus::outputs_not_silent()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// The RMS amplitude of a filtered sine after the filter settles.
+    fn response(coefficients: Coefficients, frequency: f32) -> f32 {
+        let rate = 48000.0;
+        let mut filter = Filter::new(coefficients, 1);
+
+        let output: Vec<f32> = (0..48000)
+            .map(|i| {
+                let input = (i as f32 * frequency * core::f32::consts::TAU / rate).sin();
+                filter.process(0, input)
+            })
+            .skip(24000)
+            .collect();
+
+        let squares: f32 = output.iter().map(|s| s * s).sum();
+        (squares / output.len() as f32).sqrt() * core::f32::consts::SQRT_2
+    }
+
+    #[test]
+    fn test_responses() {
+        let rate = 48000.0;
+        let q = core::f32::consts::FRAC_1_SQRT_2;
+        let db = |amp: f32| 20.0 * amp.log10();
+
+        let low_pass = Coefficients::new(BiquadKind::LowPass, 1000.0, 0.0, q, rate);
+        assert!(db(response(low_pass, 100.0)).abs() < 0.1);
+        assert!((db(response(low_pass, 1000.0)) + 3.0).abs() < 0.1);
+        assert!(db(response(low_pass, 10000.0)) < -35.0);
+
+        let high_pass = Coefficients::new(BiquadKind::HighPass, 1000.0, 0.0, q, rate);
+        assert!(db(response(high_pass, 100.0)) < -35.0);
+        assert!(db(response(high_pass, 10000.0)).abs() < 0.1);
+
+        let peaking = Coefficients::new(BiquadKind::Peaking, 1000.0, 6.0, 1.0, rate);
+        assert!((db(response(peaking, 1000.0)) - 6.0).abs() < 0.1);
+        assert!(db(response(peaking, 10000.0)).abs() < 0.5);
+
+        let shelf = Coefficients::new(BiquadKind::LowShelf, 1000.0, -12.0, q, rate);
+        assert!((db(response(shelf, 50.0)) + 12.0).abs() < 0.2);
+        assert!(db(response(shelf, 15000.0)).abs() < 0.2);
+
+        let notch = Coefficients::new(BiquadKind::Notch, 1000.0, 0.0, 1.0, rate);
+        assert!(db(response(notch, 1000.0)) < -40.0);
+
+        let all_pass = Coefficients::new(BiquadKind::AllPass, 1000.0, 0.0, 1.0, rate);
+        assert!(db(response(all_pass, 1000.0)).abs() < 0.1);
+    }
+}
@@ -0,0 +1,362 @@
+//! A feed-forward compressor with an optional sidechain.
+
+use crate::timeline::Timeline;
+use bevy_ecs::prelude::*;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use firewheel::{
+    channel_config::{ChannelConfig, ChannelCount, NonZeroChannelCount},
+    clock::ClockSeconds,
+    diff::{Diff, Patch},
+    event::NodeEventList,
+    node::{
+        AudioNode, AudioNodeInfo, AudioNodeProcessor, ConstructProcessorContext, ProcBuffers,
+        ProcInfo, ProcessStatus,
+    },
+};
+
+/// A feed-forward compressor.
+///
+/// As a bus insert, the compressor responds to its own input.
+/// With [`CompressorConfig::sidechain`], the node gains a second set of
+/// inputs that drive the gain reduction instead, allowing one
+/// signal to duck another.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// # use bevy_seedling::timeline::Timeline;
+/// #[derive(NodeLabel, Debug, Clone, PartialEq, Eq, Hash)]
+/// struct MusicBus;
+///
+/// #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+/// struct DialoguePool;
+///
+/// fn duck_music(mut commands: Commands) {
+///     // Music passes through the compressor's main inputs.
+///     let ducker = commands
+///         .spawn((
+///             CompressorNode {
+///                 threshold: Timeline::new(-30.0),
+///                 ratio: Timeline::new(8.0),
+///                 ..Default::default()
+///             },
+///             CompressorConfig {
+///                 sidechain: true,
+///                 ..Default::default()
+///             },
+///         ))
+///         .id();
+///
+///     commands.spawn((VolumeNode::default(), MusicBus)).connect(ducker);
+///
+///     // Dialogue feeds the sidechain, which begins at input 2.
+///     Pool::new(DialoguePool, 4)
+///         .effect(SendNode::new(Volume::UNITY_GAIN, ducker).with_target_input(2))
+///         .spawn(&mut commands);
+/// }
+/// ```
+#[derive(Diff, Patch, Debug, Clone, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct CompressorNode {
+    /// The level in dBFS above which gain reduction begins.
+    pub threshold: Timeline<f32>,
+    /// The input-to-output ratio above the threshold.
+    pub ratio: Timeline<f32>,
+    /// The time in seconds for gain reduction to engage.
+    pub attack: Timeline<f32>,
+    /// The time in seconds for gain reduction to recover.
+    pub release: Timeline<f32>,
+    /// The width of the soft knee in decibels, centered on the threshold.
+    pub knee: Timeline<f32>,
+    /// The gain in decibels applied after compression.
+    pub makeup: Timeline<f32>,
+}
+
+impl Default for CompressorNode {
+    fn default() -> Self {
+        Self {
+            threshold: Timeline::new(-18.0),
+            ratio: Timeline::new(4.0),
+            attack: Timeline::new(0.01),
+            release: Timeline::new(0.2),
+            knee: Timeline::new(6.0),
+            makeup: Timeline::new(0.0),
+        }
+    }
+}
+
+/// [`CompressorNode`]'s configuration.
+#[derive(Debug, Component, Clone)]
+pub struct CompressorConfig {
+    /// The number of main input and output channels.
+    pub channels: NonZeroChannelCount,
+    /// Whether the node has sidechain inputs.
+    ///
+    /// Sidechain inputs follow the main inputs, so the
+    /// first sidechain input's index is the channel count.
+    pub sidechain: bool,
+}
+
+impl Default for CompressorConfig {
+    fn default() -> Self {
+        Self {
+            channels: NonZeroChannelCount::STEREO,
+            sidechain: false,
+        }
+    }
+}
+
+impl AudioNode for CompressorNode {
+    type Configuration = CompressorConfig;
+
+    fn info(&self, config: &Self::Configuration) -> AudioNodeInfo {
+        let channels = config.channels.get();
+        let inputs = if config.sidechain {
+            ChannelCount::new(channels.get() * 2)
+                .expect("compressor channel count must not exceed 32")
+        } else {
+            channels
+        };
+
+        AudioNodeInfo::new()
+            .debug_name("compressor")
+            .channel_config(ChannelConfig {
+                num_inputs: inputs,
+                num_outputs: channels,
+            })
+            .uses_events(true)
+    }
+
+    fn construct_processor(
+        &self,
+        config: &Self::Configuration,
+        cx: ConstructProcessorContext,
+    ) -> impl AudioNodeProcessor {
+        CompressorProcessor {
+            params: self.clone(),
+            sample_rate: cx.stream_info.sample_rate.get() as f32,
+            channels: config.channels.get().get() as usize,
+            sidechain: config.sidechain,
+            envelope: 0.0,
+        }
+    }
+}
+
+/// Compute the gain change in decibels for an input level.
+///
+/// The result is never positive.
+fn gain_computer(level: f32, threshold: f32, ratio: f32, knee: f32) -> f32 {
+    let over = level - threshold;
+    let slope = 1.0 / ratio.max(1.0) - 1.0;
+
+    if knee > 0.0 && over.abs() * 2.0 <= knee {
+        let x = over + knee * 0.5;
+        slope * x * x / (2.0 * knee)
+    } else if over > 0.0 {
+        slope * over
+    } else {
+        0.0
+    }
+}
+
+/// The one-pole smoothing coefficient for a time constant in seconds.
+fn smoothing(seconds: f32, sample_rate: f32) -> f32 {
+    if seconds <= 0.0 {
+        0.0
+    } else {
+        (-1.0 / (seconds * sample_rate)).exp()
+    }
+}
+
+struct CompressorProcessor {
+    params: CompressorNode,
+    sample_rate: f32,
+    channels: usize,
+    sidechain: bool,
+    /// The smoothed gain change in decibels.
+    envelope: f32,
+}
+
+impl AudioNodeProcessor for CompressorProcessor {
+    fn process(
+        &mut self,
+        ProcBuffers {
+            inputs, outputs, ..
+        }: ProcBuffers,
+        proc_info: &ProcInfo,
+        events: NodeEventList,
+    ) -> ProcessStatus {
+        self.params.patch_list(events);
+
+        if proc_info.in_silence_mask.all_channels_silent(self.channels) {
+            self.envelope = 0.0;
+            return ProcessStatus::ClearAllOutputs;
+        }
+
+        let frame_time = (proc_info.clock_seconds.end.0 - proc_info.clock_seconds.start.0)
+            / proc_info.frames as f64;
+
+        self.compress(
+            inputs,
+            outputs,
+            proc_info.frames,
+            proc_info.clock_seconds.start,
+            frame_time,
+        );
+
+        ProcessStatus::outputs_not_silent()
+    }
+}
+
+impl CompressorProcessor {
+    /// Compress the main inputs into the outputs, detecting levels
+    /// on the sidechain inputs if enabled.
+    fn compress(
+        &mut self,
+        inputs: &[&[f32]],
+        outputs: &mut [&mut [f32]],
+        frames: usize,
+        seconds: ClockSeconds,
+        frame_time: f64,
+    ) {
+        let (main, detector) = if self.sidechain {
+            inputs.split_at(self.channels)
+        } else {
+            (inputs, inputs)
+        };
+
+        let mut threshold = 0.0;
+        let mut ratio = 1.0;
+        let mut knee = 0.0;
+        let mut makeup = 0.0;
+        let mut attack = 0.0;
+        let mut release = 0.0;
+
+        for frame in 0..frames {
+            if frame % 32 == 0 {
+                let seconds = seconds + ClockSeconds(frame as f64 * frame_time);
+                let params = &mut self.params;
+
+                params.threshold.tick(seconds);
+                params.ratio.tick(seconds);
+                params.attack.tick(seconds);
+                params.release.tick(seconds);
+                params.knee.tick(seconds);
+                params.makeup.tick(seconds);
+
+                threshold = params.threshold.get();
+                ratio = params.ratio.get();
+                knee = params.knee.get();
+                makeup = params.makeup.get();
+                attack = smoothing(params.attack.get(), self.sample_rate);
+                release = smoothing(params.release.get(), self.sample_rate);
+            }
+
+            let peak = detector
+                .iter()
+                .fold(0.0f32, |peak, channel| peak.max(channel[frame].abs()));
+            let level = 20.0 * peak.max(1e-9).log10();
+            let target = gain_computer(level, threshold, ratio, knee);
+
+            // Gain reduction increases during the attack and recovers during the release.
+            let coeff = if target < self.envelope {
+                attack
+            } else {
+                release
+            };
+            self.envelope = coeff * self.envelope + (1.0 - coeff) * target;
+
+            let gain = 10f32.powf((self.envelope + makeup) / 20.0);
+            for (input, output) in main.iter().zip(outputs.iter_mut()) {
+                output[frame] = input[frame] * gain;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_gain_computer() {
+        // Below the knee, no gain change is applied.
+        assert_eq!(gain_computer(-30.0, -20.0, 4.0, 6.0), 0.0);
+
+        // Well above the knee, the ratio applies.
+        let change = gain_computer(-8.0, -20.0, 4.0, 6.0);
+        assert!((change + 9.0).abs() < 1e-4, "{change}");
+
+        // The knee is continuous at its edges.
+        let edge = gain_computer(-17.0, -20.0, 4.0, 6.0);
+        assert!((edge - gain_computer(-17.0, -20.0, 4.0, 0.0)).abs() < 1e-4);
+    }
+
+    fn processor(sidechain: bool) -> CompressorProcessor {
+        CompressorProcessor {
+            params: CompressorNode {
+                threshold: Timeline::new(-20.0),
+                ratio: Timeline::new(8.0),
+                attack: Timeline::new(0.0),
+                knee: Timeline::new(0.0),
+                ..Default::default()
+            },
+            sample_rate: 48000.0,
+            channels: 2,
+            sidechain,
+            envelope: 0.0,
+        }
+    }
+
+    /// Compress a block, returning the last output frame of each channel.
+    fn compress(processor: &mut CompressorProcessor, inputs: &[&[f32]]) -> [f32; 2] {
+        let frames = inputs[0].len();
+        let mut left = vec![0.0; frames];
+        let mut right = vec![0.0; frames];
+
+        processor.compress(
+            inputs,
+            &mut [left.as_mut_slice(), right.as_mut_slice()],
+            frames,
+            ClockSeconds(0.0),
+            1.0 / 48000.0,
+        );
+
+        [left[frames - 1], right[frames - 1]]
+    }
+
+    #[test]
+    fn test_sidechain_reduces_main() {
+        let quiet: &[f32] = &[0.01; 256];
+        let loud: &[f32] = &[1.0; 256];
+        let silent: &[f32] = &[0.0; 256];
+
+        // A quiet main signal passes through when the sidechain is silent.
+        let mut compressor = processor(true);
+        let output = compress(&mut compressor, &[quiet, quiet, silent, silent]);
+        assert!(output.iter().all(|o| (o - 0.01).abs() < 1e-6), "{output:?}");
+
+        // A loud sidechain reduces the main signal's gain, even
+        // though the main signal is below the threshold.
+        let output = compress(&mut compressor, &[quiet, quiet, loud, loud]);
+        assert!(output.iter().all(|o| *o < 0.01 * 0.5), "{output:?}");
+    }
+
+    #[test]
+    fn test_main_drives_gain() {
+        let quiet: &[f32] = &[0.01; 256];
+        let loud: &[f32] = &[1.0; 256];
+
+        let mut compressor = processor(false);
+        let output = compress(&mut compressor, &[quiet, quiet]);
+        assert!(output.iter().all(|o| (o - 0.01).abs() < 1e-6), "{output:?}");
+
+        // 0 dBFS is 20 dB above the threshold, so at 8:1 it's reduced by 17.5 dB.
+        let output = compress(&mut compressor, &[loud, loud]);
+        let expected = 10f32.powf(-17.5 / 20.0);
+        assert!(
+            output.iter().all(|o| (o - expected).abs() < 1e-3),
+            "{output:?}"
+        );
+    }
+}
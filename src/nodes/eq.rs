@@ -0,0 +1,221 @@
+//! A multi-band parametric equalizer.
+
+use super::biquad::{BiquadKind, Coefficients, Filter};
+use crate::timeline::Timeline;
+use bevy_ecs::prelude::*;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use firewheel::{
+    channel_config::{ChannelConfig, NonZeroChannelCount},
+    clock::ClockSeconds,
+    diff::{Diff, EventQueue, Patch, PatchError, PathBuilder},
+    event::{NodeEventList, ParamData},
+    node::{
+        AudioNode, AudioNodeInfo, AudioNodeProcessor, ConstructProcessorContext, ProcBuffers,
+        ProcInfo, ProcessStatus,
+    },
+};
+
+/// A single band of a [`ParametricEqNode`].
+///
+/// Each band behaves like a [`BiquadNode`][super::biquad::BiquadNode].
+#[derive(Diff, Patch, Debug, Clone, Reflect)]
+#[reflect(Default)]
+pub struct EqBand {
+    /// The band's response.
+    pub kind: BiquadKind,
+    /// The cutoff, corner, or center frequency in hertz.
+    pub frequency: Timeline<f32>,
+    /// The shelving or peaking gain in decibels.
+    pub gain: Timeline<f32>,
+    /// The band's *quality*, or resonance.
+    pub q: Timeline<f32>,
+}
+
+impl Default for EqBand {
+    fn default() -> Self {
+        Self::new(BiquadKind::Peaking, 1000.0, 0.0, 1.0)
+    }
+}
+
+impl EqBand {
+    /// Create a new [`EqBand`] with an initial frequency, gain, and quality.
+    pub fn new(kind: BiquadKind, frequency: f32, gain: f32, q: f32) -> Self {
+        Self {
+            kind,
+            frequency: Timeline::new(frequency),
+            gain: Timeline::new(gain),
+            q: Timeline::new(q),
+        }
+    }
+
+    fn coefficients(&self, sample_rate: f32) -> Coefficients {
+        Coefficients::new(
+            self.kind,
+            self.frequency.get(),
+            self.gain.get(),
+            self.q.get(),
+            sample_rate,
+        )
+    }
+}
+
+/// A multi-band parametric equalizer.
+///
+/// Each [`EqBand`] is applied in series. The number of bands is fixed
+/// when the node is spawned, so bands added or removed afterwards
+/// are ignored. Any band's parameters may be changed freely.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// # fn system(mut commands: Commands) {
+/// commands.spawn(ParametricEqNode::new([
+///     EqBand::new(BiquadKind::HighPass, 80.0, 0.0, 0.7),
+///     EqBand::new(BiquadKind::Peaking, 300.0, -3.0, 1.2),
+///     EqBand::new(BiquadKind::HighShelf, 8000.0, 2.0, 0.7),
+/// ]));
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct ParametricEqNode {
+    /// The equalizer's bands.
+    pub bands: Vec<EqBand>,
+}
+
+impl ParametricEqNode {
+    /// Create a new [`ParametricEqNode`] from a set of bands.
+    pub fn new(bands: impl IntoIterator<Item = EqBand>) -> Self {
+        Self {
+            bands: bands.into_iter().collect(),
+        }
+    }
+}
+
+impl Diff for ParametricEqNode {
+    fn diff<E: EventQueue>(&self, baseline: &Self, path: PathBuilder, event_queue: &mut E) {
+        for (i, (band, baseline)) in self.bands.iter().zip(&baseline.bands).enumerate() {
+            band.diff(baseline, path.with(i as u32), event_queue);
+        }
+    }
+}
+
+impl Patch for ParametricEqNode {
+    fn patch(&mut self, data: &ParamData, path: &[u32]) -> Result<(), PatchError> {
+        let (index, path) = path.split_first().ok_or(PatchError::InvalidPath)?;
+        let band = self
+            .bands
+            .get_mut(*index as usize)
+            .ok_or(PatchError::InvalidPath)?;
+
+        band.patch(data, path)
+    }
+}
+
+/// [`ParametricEqNode`]'s configuration.
+#[derive(Debug, Component, Clone)]
+pub struct ParametricEqConfig {
+    /// The number of input and output channels.
+    pub channels: NonZeroChannelCount,
+}
+
+impl Default for ParametricEqConfig {
+    fn default() -> Self {
+        Self {
+            channels: NonZeroChannelCount::STEREO,
+        }
+    }
+}
+
+impl AudioNode for ParametricEqNode {
+    type Configuration = ParametricEqConfig;
+
+    fn info(&self, config: &Self::Configuration) -> AudioNodeInfo {
+        AudioNodeInfo::new()
+            .debug_name("parametric equalizer")
+            .channel_config(ChannelConfig {
+                num_inputs: config.channels.get(),
+                num_outputs: config.channels.get(),
+            })
+            .uses_events(true)
+    }
+
+    fn construct_processor(
+        &self,
+        config: &Self::Configuration,
+        cx: ConstructProcessorContext,
+    ) -> impl AudioNodeProcessor {
+        let sample_rate = cx.stream_info.sample_rate.get() as f32;
+        let channels = config.channels.get().get() as usize;
+
+        ParametricEqProcessor {
+            params: self.clone(),
+            sample_rate,
+            filters: self
+                .bands
+                .iter()
+                .map(|band| Filter::new(band.coefficients(sample_rate), channels))
+                .collect(),
+        }
+    }
+}
+
+struct ParametricEqProcessor {
+    params: ParametricEqNode,
+    sample_rate: f32,
+    filters: Vec<Filter>,
+}
+
+impl AudioNodeProcessor for ParametricEqProcessor {
+    fn process(
+        &mut self,
+        ProcBuffers {
+            inputs, outputs, ..
+        }: ProcBuffers,
+        proc_info: &ProcInfo,
+        events: NodeEventList,
+    ) -> ProcessStatus {
+        self.params.patch_list(events);
+
+        if proc_info.in_silence_mask.all_channels_silent(inputs.len()) {
+            for filter in &mut self.filters {
+                filter.reset();
+            }
+            return ProcessStatus::ClearAllOutputs;
+        }
+
+        let seconds = proc_info.clock_seconds.start;
+        let frame_time = (proc_info.clock_seconds.end.0 - proc_info.clock_seconds.start.0)
+            / proc_info.frames as f64;
+
+        for frame in 0..proc_info.frames {
+            if frame % 32 == 0 {
+                let seconds = seconds + ClockSeconds(frame as f64 * frame_time);
+
+                for (band, filter) in self.params.bands.iter_mut().zip(&mut self.filters) {
+                    band.frequency.tick(seconds);
+                    band.gain.tick(seconds);
+                    band.q.tick(seconds);
+
+                    filter.set_target(band.coefficients(self.sample_rate));
+                }
+            }
+
+            let t = (frame % 32 + 1) as f32 / 32.0;
+            for filter in &mut self.filters {
+                filter.interpolate(t);
+            }
+
+            for (channel, (input, output)) in inputs.iter().zip(outputs.iter_mut()).enumerate() {
+                output[frame] = self
+                    .filters
+                    .iter_mut()
+                    .fold(input[frame], |sample, filter| {
+                        filter.process(channel, sample)
+                    });
+            }
+        }
+
+        ProcessStatus::outputs_not_silent()
+    }
+}
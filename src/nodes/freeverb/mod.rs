@@ -4,7 +4,8 @@
 #![allow(missing_docs)]
 #![allow(clippy::module_inception)]
 
-use bevy_ecs::component::Component;
+use bevy_ecs::{component::Component, reflect::ReflectComponent};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
 use firewheel::{
     channel_config::{ChannelConfig, ChannelCount},
     core::node::ProcInfo,
@@ -22,7 +23,8 @@ mod delay_line;
 mod freeverb;
 
 /// A simple, relatively cheap stereo reverb.
-#[derive(Diff, Patch, Clone, Debug, Component)]
+#[derive(Diff, Patch, Clone, Debug, Component, Reflect)]
+#[reflect(Component, Default)]
 pub struct FreeverbNode {
     /// Set the size of the emulated room, expressed from 0 to 1.
     pub room_size: f32,
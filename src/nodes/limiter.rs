@@ -0,0 +1,464 @@
+//! A lookahead true-peak limiter.
+
+use crate::{node::FirewheelNode, prelude::AudioContext, timeline::Timeline};
+use bevy_ecs::prelude::*;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use firewheel::{
+    channel_config::{ChannelConfig, NonZeroChannelCount},
+    clock::ClockSeconds,
+    diff::{Diff, Patch},
+    event::NodeEventList,
+    node::{
+        AudioNode, AudioNodeInfo, AudioNodeProcessor, ConstructProcessorContext, ProcBuffers,
+        ProcInfo, ProcessStatus,
+    },
+};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+/// A lookahead true-peak limiter.
+///
+/// The limiter keeps the inter-sample peaks of its output at or below
+/// [`LimiterNode::ceiling`]. Since it looks ahead by
+/// [`LimiterConfig::lookahead`], gain reduction engages smoothly before
+/// a peak arrives rather than clipping it, at the cost of delaying the
+/// signal by roughly the lookahead time.
+///
+/// By default, [`SeedlingPlugin`][crate::SeedlingPlugin] inserts a limiter
+/// labelled [`MainBusLimiter`] between the [`MainBus`][crate::prelude::MainBus]
+/// and the output, which adds its lookahead to the output latency. This can
+/// be disabled with [`SeedlingPlugin::main_bus_limiter`][crate::SeedlingPlugin::main_bus_limiter].
+/// The amount of gain reduction is published each frame to
+/// the entity's [`LimiterReading`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// # use bevy_seedling::timeline::Timeline;
+/// fn lower_ceiling(mut limiter: Single<&mut LimiterNode, With<MainBusLimiter>>) {
+///     limiter.ceiling = Timeline::new(-3.0);
+/// }
+///
+/// fn read_limiter(limiter: Single<&LimiterReading, With<MainBusLimiter>>) {
+///     info!("limiting by {:.1} dB", limiter.gain_reduction);
+/// }
+/// ```
+#[derive(Diff, Patch, Debug, Clone, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct LimiterNode {
+    /// The maximum true-peak level of the output in dBTP.
+    pub ceiling: Timeline<f32>,
+    /// The time in seconds for gain reduction to recover.
+    pub release: Timeline<f32>,
+}
+
+impl Default for LimiterNode {
+    fn default() -> Self {
+        Self {
+            ceiling: Timeline::new(-1.0),
+            release: Timeline::new(0.1),
+        }
+    }
+}
+
+/// [`LimiterNode`]'s configuration.
+#[derive(Debug, Component, Clone)]
+pub struct LimiterConfig {
+    /// The number of input and output channels.
+    ///
+    /// Gain reduction is linked across all channels.
+    pub channels: NonZeroChannelCount,
+    /// The lookahead time in seconds.
+    pub lookahead: f32,
+}
+
+impl Default for LimiterConfig {
+    fn default() -> Self {
+        Self {
+            channels: NonZeroChannelCount::STEREO,
+            lookahead: 0.005,
+        }
+    }
+}
+
+/// The most recent gain reduction of a [`LimiterNode`].
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct LimiterReading {
+    /// The largest gain reduction over the last processed block, in decibels.
+    ///
+    /// This is `0.0` when the limiter is inactive and positive otherwise.
+    pub gain_reduction: f32,
+}
+
+/// The label of the limiter inserted after the [`MainBus`][crate::prelude::MainBus].
+///
+/// For more details, see [`LimiterNode`].
+#[derive(crate::prelude::NodeLabel, Reflect, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MainBusLimiter;
+
+/// [`LimiterNode`]'s custom node state.
+#[derive(Debug, Clone)]
+struct LimiterState {
+    gain_reduction: Arc<AtomicU32>,
+}
+
+impl AudioNode for LimiterNode {
+    type Configuration = LimiterConfig;
+
+    fn info(&self, config: &Self::Configuration) -> AudioNodeInfo {
+        AudioNodeInfo::new()
+            .debug_name("limiter")
+            .channel_config(ChannelConfig {
+                num_inputs: config.channels.get(),
+                num_outputs: config.channels.get(),
+            })
+            .uses_events(true)
+            .custom_state(LimiterState {
+                gain_reduction: Arc::new(AtomicU32::new(0f32.to_bits())),
+            })
+    }
+
+    fn construct_processor(
+        &self,
+        config: &Self::Configuration,
+        cx: ConstructProcessorContext,
+    ) -> impl AudioNodeProcessor {
+        let sample_rate = cx.stream_info.sample_rate.get() as f32;
+        let channels = config.channels.get().get() as usize;
+        let lookahead = (config.lookahead * sample_rate).round().max(1.0) as usize;
+        let gain_reduction = cx
+            .custom_state::<LimiterState>()
+            .map(|s| s.gain_reduction.clone())
+            .unwrap_or_default();
+
+        LimiterProcessor {
+            params: self.clone(),
+            sample_rate,
+            limiter: Limiter::new(channels, lookahead),
+            frame: vec![0.0; channels],
+            gain_reduction,
+        }
+    }
+}
+
+/// The number of input samples each interpolation phase spans.
+const TAPS: usize = 8;
+
+/// The oversampling factor of the true-peak detector.
+const OVERSAMPLING: usize = 4;
+
+/// Polyphase coefficients for the interpolated points between samples.
+///
+/// Each phase estimates the signal `phase / OVERSAMPLING` of the way
+/// between the middle two samples of the detector's history using a
+/// Hann-windowed sinc.
+fn interpolation_phases() -> [[f32; TAPS]; OVERSAMPLING - 1] {
+    let mut phases = [[0.0; TAPS]; OVERSAMPLING - 1];
+    let half = TAPS as f32 / 2.0;
+
+    for (phase, coefficients) in phases.iter_mut().enumerate() {
+        let offset = (phase + 1) as f32 / OVERSAMPLING as f32;
+
+        for (tap, coefficient) in coefficients.iter_mut().enumerate() {
+            let distance = half - 1.0 + offset - tap as f32;
+            let x = core::f32::consts::PI * distance;
+            let sinc = if x == 0.0 { 1.0 } else { x.sin() / x };
+            let window = 0.5 + 0.5 * (core::f32::consts::PI * distance / half).cos();
+
+            *coefficient = sinc * window;
+        }
+    }
+
+    phases
+}
+
+/// The sample-accurate limiting core, independent of parameter automation.
+struct Limiter {
+    phases: [[f32; TAPS]; OVERSAMPLING - 1],
+    /// The last [`TAPS`] input samples of each channel.
+    history: Vec<[f32; TAPS]>,
+    /// The delayed input of each channel.
+    delay: Vec<Vec<f32>>,
+    delay_index: usize,
+    lookahead: usize,
+    /// Candidates for the minimum required gain within the lookahead window.
+    minimum: VecDeque<(usize, f32)>,
+    frame: usize,
+    /// The release-smoothed gain.
+    release: f32,
+    /// The last `lookahead` smoothed gains, averaged to shape the attack.
+    window: Vec<f32>,
+    window_index: usize,
+    window_sum: f64,
+    /// The number of consecutive silent frames processed.
+    silent_frames: usize,
+}
+
+impl Limiter {
+    fn new(channels: usize, lookahead: usize) -> Self {
+        Self {
+            phases: interpolation_phases(),
+            history: vec![[0.0; TAPS]; channels],
+            delay: vec![vec![0.0; Self::latency_for(lookahead)]; channels],
+            delay_index: 0,
+            lookahead,
+            minimum: VecDeque::with_capacity(lookahead + 1),
+            frame: 0,
+            release: 1.0,
+            window: vec![1.0; lookahead],
+            window_index: 0,
+            window_sum: lookahead as f64,
+            silent_frames: 0,
+        }
+    }
+
+    fn latency_for(lookahead: usize) -> usize {
+        TAPS / 2 + lookahead - 1
+    }
+
+    /// The total delay in frames from input to output.
+    fn latency(&self) -> usize {
+        Self::latency_for(self.lookahead)
+    }
+
+    /// Whether the processor has fully flushed its delayed audio.
+    fn is_drained(&self) -> bool {
+        self.silent_frames > self.latency() + TAPS
+    }
+
+    fn reset(&mut self) {
+        for history in &mut self.history {
+            *history = [0.0; TAPS];
+        }
+        for delay in &mut self.delay {
+            delay.fill(0.0);
+        }
+
+        self.minimum.clear();
+        self.release = 1.0;
+        self.window.fill(1.0);
+        self.window_sum = self.lookahead as f64;
+    }
+
+    /// Push one frame through the detector, returning the
+    /// true peak of the frame [`TAPS`]` / 2` samples ago.
+    fn detect(&mut self, input: impl Iterator<Item = f32>) -> f32 {
+        let mut peak = 0.0f32;
+
+        for (history, sample) in self.history.iter_mut().zip(input) {
+            history.copy_within(1.., 0);
+            history[TAPS - 1] = sample;
+
+            peak = peak.max(history[TAPS / 2 - 1].abs());
+            for phase in &self.phases {
+                let interpolated: f32 = phase.iter().zip(history.iter()).map(|(c, x)| c * x).sum();
+                peak = peak.max(interpolated.abs());
+            }
+        }
+
+        peak
+    }
+
+    /// Process a single frame in place, returning the applied gain.
+    fn process_frame(&mut self, frame: &mut [f32], ceiling: f32, release: f32) -> f32 {
+        let silent = frame.iter().all(|s| *s == 0.0);
+        self.silent_frames = if silent { self.silent_frames + 1 } else { 0 };
+
+        let peak = self.detect(frame.iter().copied());
+        let required = if peak > ceiling { ceiling / peak } else { 1.0 };
+
+        // The minimum required gain over the lookahead window.
+        while self.minimum.back().is_some_and(|(_, g)| *g >= required) {
+            self.minimum.pop_back();
+        }
+        self.minimum.push_back((self.frame, required));
+        while self
+            .minimum
+            .front()
+            .is_some_and(|(i, _)| *i + self.lookahead <= self.frame)
+        {
+            self.minimum.pop_front();
+        }
+        let hold = self.minimum.front().map(|(_, g)| *g).unwrap_or(1.0);
+        self.frame += 1;
+
+        // Reduction is immediate, while recovery is smoothed.
+        self.release = if hold < self.release {
+            hold
+        } else {
+            hold + release * (self.release - hold)
+        };
+
+        // Averaging over the lookahead window ramps the gain down before
+        // each peak reaches the output without ever exceeding its requirement.
+        self.window_sum += (self.release - self.window[self.window_index]) as f64;
+        self.window[self.window_index] = self.release;
+        self.window_index = (self.window_index + 1) % self.lookahead;
+        let gain = ((self.window_sum / self.lookahead as f64) as f32).min(1.0);
+
+        for (sample, delay) in frame.iter_mut().zip(&mut self.delay) {
+            let delayed = core::mem::replace(&mut delay[self.delay_index], *sample);
+            *sample = delayed * gain;
+        }
+        self.delay_index = (self.delay_index + 1) % self.latency();
+
+        gain
+    }
+}
+
+struct LimiterProcessor {
+    params: LimiterNode,
+    sample_rate: f32,
+    limiter: Limiter,
+    /// Scratch space for a single frame.
+    frame: Vec<f32>,
+    gain_reduction: Arc<AtomicU32>,
+}
+
+impl AudioNodeProcessor for LimiterProcessor {
+    fn process(
+        &mut self,
+        ProcBuffers {
+            inputs, outputs, ..
+        }: ProcBuffers,
+        proc_info: &ProcInfo,
+        events: NodeEventList,
+    ) -> ProcessStatus {
+        self.params.patch_list(events);
+
+        if proc_info.in_silence_mask.all_channels_silent(inputs.len()) && self.limiter.is_drained()
+        {
+            self.limiter.reset();
+            self.gain_reduction.store(0f32.to_bits(), Ordering::Relaxed);
+            return ProcessStatus::ClearAllOutputs;
+        }
+
+        let seconds = proc_info.clock_seconds.start;
+        let frame_time = (proc_info.clock_seconds.end.0 - proc_info.clock_seconds.start.0)
+            / proc_info.frames as f64;
+
+        let mut ceiling = 1.0;
+        let mut release = 0.0;
+        let mut minimum_gain = 1.0f32;
+
+        for frame in 0..proc_info.frames {
+            if frame % 32 == 0 {
+                let seconds = seconds + ClockSeconds(frame as f64 * frame_time);
+
+                self.params.ceiling.tick(seconds);
+                self.params.release.tick(seconds);
+
+                ceiling = 10f32.powf(self.params.ceiling.get() / 20.0);
+                release = smoothing(self.params.release.get(), self.sample_rate);
+            }
+
+            for (sample, input) in self.frame.iter_mut().zip(inputs.iter()) {
+                *sample = input[frame];
+            }
+
+            let gain = self
+                .limiter
+                .process_frame(&mut self.frame, ceiling, release);
+            minimum_gain = minimum_gain.min(gain);
+
+            for (sample, output) in self.frame.iter().zip(outputs.iter_mut()) {
+                output[frame] = *sample;
+            }
+        }
+
+        let reduction = -20.0 * minimum_gain.log10();
+        self.gain_reduction
+            .store(reduction.max(0.0).to_bits(), Ordering::Relaxed);
+
+        ProcessStatus::outputs_not_silent()
+    }
+}
+
+/// The one-pole smoothing coefficient for a time constant in seconds.
+fn smoothing(seconds: f32, sample_rate: f32) -> f32 {
+    if seconds <= 0.0 {
+        0.0
+    } else {
+        (-1.0 / (seconds * sample_rate)).exp()
+    }
+}
+
+/// Publish limiter gain reduction to [`LimiterReading`].
+pub(crate) fn update_limiters(
+    mut limiters: Query<(&FirewheelNode, &mut LimiterReading), With<LimiterNode>>,
+    mut context: ResMut<AudioContext>,
+) {
+    if limiters.is_empty() {
+        return;
+    }
+
+    let nodes: Vec<_> = limiters.iter().map(|(node, _)| node.0).collect();
+    let states = context.with(move |context| {
+        nodes
+            .into_iter()
+            .map(|node| context.node_state::<LimiterState>(node).cloned())
+            .collect::<Vec<_>>()
+    });
+
+    for ((_, mut reading), state) in limiters.iter_mut().zip(states) {
+        let Some(state) = state else {
+            continue;
+        };
+
+        reading.set_if_neq(LimiterReading {
+            gain_reduction: f32::from_bits(state.gain_reduction.load(Ordering::Relaxed)),
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_true_peak_ceiling() {
+        let rate = 48000.0;
+        let lookahead = 240;
+        let ceiling = 10f32.powf(-1.0 / 20.0);
+
+        // A loud sine near a quarter of the sample rate
+        // has inter-sample peaks well above its samples.
+        let sine: Vec<f32> = (0..4800)
+            .map(|i| 2.0 * (i as f32 * 11025.0 * core::f32::consts::TAU / rate + 0.7).sin())
+            .collect();
+
+        let mut limiter = Limiter::new(1, lookahead);
+        let mut output = Vec::with_capacity(sine.len());
+        for sample in &sine {
+            let mut frame = [*sample];
+            limiter.process_frame(&mut frame, ceiling, smoothing(0.1, rate));
+            output.push(frame[0]);
+        }
+
+        // Measure the output's true peak with a fresh detector.
+        let mut detector = Limiter::new(1, 1);
+        let peak = output
+            .iter()
+            .map(|s| detector.detect(core::iter::once(*s)))
+            .fold(0.0f32, f32::max);
+
+        assert!(peak <= ceiling * 1.01, "{peak}");
+        assert!(peak > ceiling * 0.9, "{peak}");
+
+        // The signal is delayed by the limiter's latency.
+        let mut limiter = Limiter::new(1, lookahead);
+        let mut impulse = Vec::new();
+        for i in 0..limiter.latency() + 1 {
+            let mut frame = [if i == 0 { 0.5 } else { 0.0 }];
+            limiter.process_frame(&mut frame, ceiling, 0.0);
+            impulse.push(frame[0]);
+        }
+        assert_eq!(impulse.last(), Some(&0.5));
+    }
+}
@@ -2,6 +2,7 @@
 
 use crate::timeline::Timeline;
 use bevy_ecs::prelude::*;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
 use firewheel::{
     channel_config::{ChannelConfig, NonZeroChannelCount},
     clock::ClockSeconds,
@@ -14,7 +15,8 @@ use firewheel::{
 };
 
 /// A one-pole, low-pass filter.
-#[derive(Diff, Patch, Debug, Clone, Component)]
+#[derive(Diff, Patch, Debug, Clone, Component, Reflect)]
+#[reflect(Component, Default)]
 pub struct LowPassNode {
     /// The cutoff frequency in hertz.
     pub frequency: Timeline<f32>,
@@ -0,0 +1,547 @@
+//! Level metering.
+
+use crate::{node::FirewheelNode, prelude::AudioContext, sample::process::KWeighting};
+use bevy_ecs::prelude::*;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use firewheel::{
+    channel_config::{ChannelConfig, NonZeroChannelCount},
+    diff::{Diff, Patch},
+    event::NodeEventList,
+    node::{
+        AudioNode, AudioNodeInfo, AudioNodeProcessor, ConstructProcessorContext, NodeID,
+        ProcBuffers, ProcInfo, ProcessStatus,
+    },
+};
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+/// A level meter.
+///
+/// [`MeterNode`] measures the per-channel peak and RMS level of its input
+/// over [`MeterNode::window`] and publishes them to the entity's
+/// [`MeterReading`] each frame. The momentary loudness, following
+/// ITU-R BS.1770, can be enabled with [`MeterConfig::loudness`].
+///
+/// The meter passes its input through unchanged, so it can be placed
+/// inline after the node you'd like to measure. Like any other node,
+/// it's connected to the [`MainBus`][crate::prelude::MainBus] if it's
+/// not given an explicit connection.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// #[derive(Component)]
+/// struct MusicMeter;
+///
+/// fn meter_music(mut commands: Commands) {
+///     commands
+///         .spawn(VolumeNode::default())
+///         .chain_node((MeterNode::default(), MusicMeter));
+/// }
+///
+/// fn read_meter(meter: Single<&MeterReading, With<MusicMeter>>) {
+///     info!("music peak: {:.1} dBFS", meter.peak_db(0));
+/// }
+/// ```
+///
+/// To meter the [`MainBus`][crate::prelude::MainBus] itself, set
+/// [`SeedlingPlugin::main_bus_meter`][crate::SeedlingPlugin::main_bus_meter].
+/// This inserts a meter labelled [`MainBusMeter`] between the
+/// [`MainBus`][crate::prelude::MainBus] and its limiter. Since an unconnected
+/// meter is routed to the [`MainBus`][crate::prelude::MainBus], chaining
+/// a meter after the [`MainBus`][crate::prelude::MainBus] yourself would form a cycle.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// fn read_main_meter(meter: Single<&MeterReading, With<MainBusMeter>>) {
+///     info!("main peak: {:.1} dBFS", meter.peak_db(0));
+/// }
+/// ```
+#[derive(Diff, Patch, Debug, Clone, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct MeterNode {
+    /// The measurement window in seconds.
+    pub window: f32,
+}
+
+impl Default for MeterNode {
+    fn default() -> Self {
+        Self { window: 0.1 }
+    }
+}
+
+/// The label of the meter inserted after the [`MainBus`][crate::prelude::MainBus].
+///
+/// For more details, see [`SeedlingPlugin::main_bus_meter`][crate::SeedlingPlugin::main_bus_meter].
+#[derive(crate::prelude::NodeLabel, Reflect, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MainBusMeter;
+
+/// [`MeterNode`]'s configuration.
+#[derive(Debug, Component, Clone)]
+pub struct MeterConfig {
+    /// The number of input and output channels.
+    pub channels: NonZeroChannelCount,
+    /// Whether to measure momentary loudness.
+    pub loudness: bool,
+}
+
+impl Default for MeterConfig {
+    fn default() -> Self {
+        Self {
+            channels: NonZeroChannelCount::STEREO,
+            loudness: false,
+        }
+    }
+}
+
+/// The most recent measurements of a [`MeterNode`].
+///
+/// Levels are expressed as linear amplitudes, where `1.0` is full scale.
+#[derive(Debug, Component, Clone, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct MeterReading {
+    /// The peak amplitude of each channel.
+    pub peak: Vec<f32>,
+    /// The RMS amplitude of each channel.
+    pub rms: Vec<f32>,
+    /// The momentary loudness in LUFS, if enabled with [`MeterConfig::loudness`].
+    pub loudness: Option<f32>,
+}
+
+impl MeterReading {
+    /// The peak level of a channel in dBFS.
+    ///
+    /// Returns negative infinity for silent or missing channels.
+    pub fn peak_db(&self, channel: usize) -> f32 {
+        amp_to_db(self.peak.get(channel).copied().unwrap_or(0.0))
+    }
+
+    /// The RMS level of a channel in dBFS.
+    ///
+    /// Returns negative infinity for silent or missing channels.
+    pub fn rms_db(&self, channel: usize) -> f32 {
+        amp_to_db(self.rms.get(channel).copied().unwrap_or(0.0))
+    }
+}
+
+fn amp_to_db(amp: f32) -> f32 {
+    20.0 * amp.log10()
+}
+
+/// Measurements shared between the processor and the ECS.
+#[derive(Debug)]
+struct MeterShared {
+    peak: Vec<AtomicU32>,
+    rms: Vec<AtomicU32>,
+    loudness: AtomicU32,
+}
+
+impl MeterShared {
+    fn new(channels: usize) -> Self {
+        let silent = || AtomicU32::new(0f32.to_bits());
+
+        Self {
+            peak: (0..channels).map(|_| silent()).collect(),
+            rms: (0..channels).map(|_| silent()).collect(),
+            loudness: AtomicU32::new(f32::NEG_INFINITY.to_bits()),
+        }
+    }
+
+    fn load(values: &[AtomicU32]) -> impl Iterator<Item = f32> + '_ {
+        values
+            .iter()
+            .map(|v| f32::from_bits(v.load(Ordering::Relaxed)))
+    }
+}
+
+/// [`MeterNode`]'s custom node state.
+#[derive(Debug, Clone)]
+pub(crate) struct MeterState {
+    shared: Arc<MeterShared>,
+    loudness: bool,
+}
+
+impl AudioNode for MeterNode {
+    type Configuration = MeterConfig;
+
+    fn info(&self, config: &Self::Configuration) -> AudioNodeInfo {
+        AudioNodeInfo::new()
+            .debug_name("meter")
+            .channel_config(ChannelConfig {
+                num_inputs: config.channels.get(),
+                num_outputs: config.channels.get(),
+            })
+            .uses_events(true)
+            .custom_state(MeterState {
+                shared: Arc::new(MeterShared::new(config.channels.get().get() as usize)),
+                loudness: config.loudness,
+            })
+    }
+
+    fn construct_processor(
+        &self,
+        config: &Self::Configuration,
+        cx: ConstructProcessorContext,
+    ) -> impl AudioNodeProcessor {
+        let sample_rate = cx.stream_info.sample_rate.get() as f32;
+        let channels = config.channels.get().get() as usize;
+        let shared = cx
+            .custom_state::<MeterState>()
+            .map(|s| s.shared.clone())
+            .unwrap_or_else(|| Arc::new(MeterShared::new(channels)));
+
+        MeterProcessor {
+            params: self.clone(),
+            analyzer: Analyzer::new(sample_rate, channels, config.loudness),
+            shared,
+        }
+    }
+}
+
+/// Accumulates levels over windows and loudness over 400ms blocks.
+struct Analyzer {
+    sample_rate: f32,
+    frames: usize,
+    peak: Vec<f32>,
+    squares: Vec<f64>,
+    loudness: Option<Loudness>,
+}
+
+struct Loudness {
+    filters: Vec<KWeighting>,
+    /// The number of frames in a 100ms block.
+    block_frames: usize,
+    frames: usize,
+    power: f64,
+    /// The mean power of the last four blocks.
+    blocks: [f64; 4],
+    next: usize,
+}
+
+impl Analyzer {
+    fn new(sample_rate: f32, channels: usize, loudness: bool) -> Self {
+        Self {
+            sample_rate,
+            frames: 0,
+            peak: vec![0.0; channels],
+            squares: vec![0.0; channels],
+            loudness: loudness.then(|| Loudness {
+                filters: vec![KWeighting::new(sample_rate); channels],
+                block_frames: (sample_rate * 0.1).max(1.0) as usize,
+                frames: 0,
+                power: 0.0,
+                blocks: [0.0; 4],
+                next: 0,
+            }),
+        }
+    }
+
+    fn process(&mut self, inputs: &[&[f32]], frames: usize, window: f32, shared: &MeterShared) {
+        let window_frames = (window * self.sample_rate).max(1.0) as usize;
+
+        for frame in 0..frames {
+            for (channel, input) in inputs.iter().enumerate() {
+                let sample = input[frame];
+                self.peak[channel] = self.peak[channel].max(sample.abs());
+                self.squares[channel] += (sample * sample) as f64;
+            }
+
+            self.frames += 1;
+            if self.frames >= window_frames {
+                for (channel, (peak, squares)) in
+                    self.peak.iter_mut().zip(&mut self.squares).enumerate()
+                {
+                    let rms = (*squares / self.frames as f64).sqrt() as f32;
+
+                    shared.peak[channel].store(peak.to_bits(), Ordering::Relaxed);
+                    shared.rms[channel].store(rms.to_bits(), Ordering::Relaxed);
+
+                    *peak = 0.0;
+                    *squares = 0.0;
+                }
+
+                self.frames = 0;
+            }
+
+            if let Some(loudness) = &mut self.loudness {
+                for (input, filter) in inputs.iter().zip(&mut loudness.filters) {
+                    let weighted = filter.process(input[frame]) as f64;
+                    loudness.power += weighted * weighted;
+                }
+
+                loudness.frames += 1;
+                if loudness.frames >= loudness.block_frames {
+                    loudness.blocks[loudness.next] = loudness.power / loudness.frames as f64;
+                    loudness.next = (loudness.next + 1) % loudness.blocks.len();
+                    loudness.frames = 0;
+                    loudness.power = 0.0;
+
+                    let mean = loudness.blocks.iter().sum::<f64>() / loudness.blocks.len() as f64;
+                    let lufs = (-0.691 + 10.0 * mean.log10()) as f32;
+                    shared.loudness.store(lufs.to_bits(), Ordering::Relaxed);
+                }
+            }
+        }
+    }
+}
+
+struct MeterProcessor {
+    params: MeterNode,
+    analyzer: Analyzer,
+    shared: Arc<MeterShared>,
+}
+
+impl AudioNodeProcessor for MeterProcessor {
+    fn process(
+        &mut self,
+        ProcBuffers {
+            inputs, outputs, ..
+        }: ProcBuffers,
+        proc_info: &ProcInfo,
+        events: NodeEventList,
+    ) -> ProcessStatus {
+        self.params.patch_list(events);
+
+        self.analyzer
+            .process(inputs, proc_info.frames, self.params.window, &self.shared);
+
+        if proc_info.in_silence_mask.all_channels_silent(inputs.len()) {
+            return ProcessStatus::ClearAllOutputs;
+        }
+
+        for (input, output) in inputs.iter().zip(outputs.iter_mut()) {
+            output[..proc_info.frames].copy_from_slice(&input[..proc_info.frames]);
+        }
+
+        ProcessStatus::outputs_not_silent()
+    }
+}
+
+/// Publish meter measurements to [`MeterReading`].
+pub(crate) fn update_meters(
+    mut meters: Query<(&FirewheelNode, &mut MeterReading), With<MeterNode>>,
+    mut nodes: Local<Vec<NodeID>>,
+    mut states: Local<Vec<Option<MeterState>>>,
+    mut context: ResMut<AudioContext>,
+) {
+    if meters.is_empty() {
+        return;
+    }
+
+    nodes.clear();
+    nodes.extend(meters.iter().map(|(node, _)| node.0));
+
+    let (nodes, states) = (&*nodes, &mut *states);
+    states.clear();
+    context.with(|context| {
+        states.extend(
+            nodes
+                .iter()
+                .map(|node| context.node_state::<MeterState>(*node).cloned()),
+        );
+    });
+
+    for ((_, mut reading), state) in meters.iter_mut().zip(states.drain(..)) {
+        let Some(state) = state else {
+            continue;
+        };
+
+        let shared = &state.shared;
+        let loudness = state
+            .loudness
+            .then(|| f32::from_bits(shared.loudness.load(Ordering::Relaxed)));
+
+        if reading.loudness == loudness
+            && reading
+                .peak
+                .iter()
+                .copied()
+                .eq(MeterShared::load(&shared.peak))
+            && reading
+                .rms
+                .iter()
+                .copied()
+                .eq(MeterShared::load(&shared.rms))
+        {
+            continue;
+        }
+
+        let reading = reading.into_inner();
+        reading.peak.clear();
+        reading.peak.extend(MeterShared::load(&shared.peak));
+        reading.rms.clear();
+        reading.rms.extend(MeterShared::load(&shared.rms));
+        reading.loudness = loudness;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{prelude::MainBus, profiling::ProfilingBackend, SeedlingPlugin};
+    use bevy::prelude::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use firewheel::nodes::volume::VolumeNode;
+
+    #[derive(Component)]
+    struct Bus;
+
+    #[test]
+    fn test_metered_bus_reaches_main() {
+        let mut app = App::new();
+
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            SeedlingPlugin::<ProfilingBackend> {
+                default_pool_size: None,
+                main_bus_limiter: None,
+                ..SeedlingPlugin::<ProfilingBackend>::new()
+            },
+        ))
+        .add_systems(Startup, |mut commands: Commands| {
+            commands
+                .spawn((VolumeNode::default(), Bus))
+                .chain_node(MeterNode::default());
+        });
+
+        app.finish();
+        app.cleanup();
+        app.update();
+
+        app.world_mut()
+            .run_system_once(
+                |mut context: ResMut<AudioContext>,
+                 bus: Single<&FirewheelNode, With<Bus>>,
+                 meter: Single<&FirewheelNode, With<MeterNode>>,
+                 main: Single<&FirewheelNode, With<MainBus>>| {
+                    let (bus, meter, main) = (bus.0, meter.0, main.0);
+
+                    context.with(|context| {
+                        let edges = context.edges();
+
+                        assert!(edges
+                            .iter()
+                            .filter(|e| e.src_node == bus)
+                            .all(|e| e.dst_node == meter));
+
+                        let meter_edges: Vec<_> =
+                            edges.iter().filter(|e| e.src_node == meter).collect();
+
+                        assert_eq!(meter_edges.len(), 2);
+                        assert!(meter_edges.iter().all(|e| e.dst_node == main));
+                    });
+                },
+            )
+            .unwrap();
+    }
+
+    #[test]
+    fn test_main_bus_meter() {
+        use crate::{
+            offline::{OfflineBackend, OfflineClock, OfflineConfig, OfflineMode, OfflinePlugin},
+            prelude::{PlaybackSettings, SamplePlayer},
+        };
+
+        let clock = OfflineClock::new(512);
+        let mut app = App::new();
+
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            SeedlingPlugin::<OfflineBackend> {
+                stream_config: OfflineConfig {
+                    mode: OfflineMode::Lockstep(clock.clone()),
+                    ..Default::default()
+                },
+                default_pool_size: None,
+                main_bus_limiter: None,
+                main_bus_meter: Some(MeterConfig::default()),
+                ..SeedlingPlugin::<OfflineBackend>::new()
+            },
+            OfflinePlugin::new(clock),
+            HierarchyPlugin,
+        ))
+        .add_systems(
+            Startup,
+            |mut commands: Commands, server: Res<AssetServer>| {
+                commands.spawn((
+                    SamplePlayer::new(server.load("caw.ogg")),
+                    PlaybackSettings::LOOP,
+                ));
+            },
+        );
+
+        app.finish();
+        app.cleanup();
+        app.update();
+
+        app.world_mut()
+            .run_system_once(
+                |mut context: ResMut<AudioContext>,
+                 meter: Single<&FirewheelNode, With<MainBusMeter>>,
+                 main: Single<&FirewheelNode, With<MainBus>>| {
+                    let (meter, main) = (meter.0, main.0);
+
+                    context.with(|context| {
+                        let output = context.graph_out_node_id();
+                        let edges = context.edges();
+
+                        assert!(edges
+                            .iter()
+                            .filter(|e| e.src_node == main)
+                            .all(|e| e.dst_node == meter));
+                        assert!(edges
+                            .iter()
+                            .filter(|e| e.src_node == meter)
+                            .all(|e| e.dst_node == output));
+                    });
+                },
+            )
+            .unwrap();
+
+        // Assets load on other threads, so the sample may take a few updates to start.
+        let metered = (0..512).any(|_| {
+            app.update();
+            std::thread::sleep(std::time::Duration::from_millis(1));
+
+            app.world_mut()
+                .run_system_once(|meter: Single<&MeterReading, With<MainBusMeter>>| {
+                    meter.peak.iter().any(|peak| *peak > 0.0)
+                })
+                .unwrap()
+        });
+
+        assert!(metered);
+    }
+
+    #[test]
+    fn test_sine_levels() {
+        let rate = 48000;
+        let sine: Vec<f32> = (0..rate)
+            .map(|i| (i as f32 * 1000.0 * std::f32::consts::TAU / rate as f32).sin())
+            .collect();
+
+        let shared = MeterShared::new(1);
+        let mut analyzer = Analyzer::new(rate as f32, 1, true);
+
+        for block in sine.chunks(512) {
+            analyzer.process(&[block], block.len(), 0.1, &shared);
+        }
+
+        let peak = MeterShared::load(&shared.peak).next().unwrap();
+        let rms = MeterShared::load(&shared.rms).next().unwrap();
+        let loudness = f32::from_bits(shared.loudness.load(Ordering::Relaxed));
+
+        assert!((peak - 1.0).abs() < 0.01, "{peak}");
+        assert!(
+            (rms - core::f32::consts::FRAC_1_SQRT_2).abs() < 0.01,
+            "{rms}"
+        );
+        // A full-scale 1kHz sine reads about -3 LUFS per channel.
+        assert!((loudness + 3.0).abs() < 0.2, "{loudness}");
+    }
+}
@@ -3,9 +3,14 @@
 use crate::{prelude::RegisterNode, SeedlingSystems};
 use bevy_ecs::prelude::*;
 
+pub mod biquad;
 pub mod bpf;
+pub mod compressor;
+pub mod eq;
 pub mod freeverb;
+pub mod limiter;
 pub mod lpf;
+pub mod meter;
 pub mod send;
 
 /// Registration and logic for `bevy_seedling`'s audio nodes.
@@ -13,13 +18,28 @@ pub(crate) struct SeedlingNodesPlugin;
 
 impl bevy_app::Plugin for SeedlingNodesPlugin {
     fn build(&self, app: &mut bevy_app::App) {
-        app.register_node::<bpf::BandPassNode>()
-            .register_node::<lpf::LowPassNode>()
-            .register_node::<send::SendNode>()
-            .register_node::<freeverb::FreeverbNode>()
+        app.register_reflected_node::<biquad::BiquadNode>()
+            .register_reflected_node::<bpf::BandPassNode>()
+            .register_reflected_node::<compressor::CompressorNode>()
+            .register_reflected_node::<eq::ParametricEqNode>()
+            .register_reflected_node::<lpf::LowPassNode>()
+            .register_reflected_node::<send::SendNode>()
+            .register_reflected_node::<freeverb::FreeverbNode>()
+            .register_reflected_node::<limiter::LimiterNode>()
+            .register_type::<limiter::LimiterReading>()
+            .register_type::<limiter::MainBusLimiter>()
+            .register_required_components::<limiter::LimiterNode, limiter::LimiterReading>()
+            .register_reflected_node::<meter::MeterNode>()
+            .register_type::<meter::MeterReading>()
+            .register_type::<meter::MainBusMeter>()
+            .register_required_components::<meter::MeterNode, meter::MeterReading>()
             .add_systems(
                 bevy_app::Last,
-                (send::connect_sends, send::update_remote_sends).before(SeedlingSystems::Acquire),
+                (
+                    (send::connect_sends, send::update_remote_sends)
+                        .before(SeedlingSystems::Acquire),
+                    (meter::update_meters, limiter::update_limiters).after(SeedlingSystems::Flush),
+                ),
             );
     }
 }
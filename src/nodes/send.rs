@@ -1,11 +1,16 @@
 //! A convenient node for routing to sends.
 
 use crate::{
-    edge::{Disconnect, EdgeTarget, PendingConnections, PendingEdge},
+    edge::{Connections, Disconnect, EdgeTarget, PendingConnections, PendingEdge},
     node::ParamFollower,
     prelude::MainBus,
 };
-use bevy_ecs::prelude::*;
+use bevy_ecs::{
+    entity::{EntityMapper, MapEntities},
+    prelude::*,
+    reflect::ReflectMapEntities,
+};
+use bevy_reflect::Reflect;
 use firewheel::{
     channel_config::{ChannelConfig, ChannelCount, NonZeroChannelCount},
     diff::{Diff, Patch},
@@ -40,15 +45,32 @@ use firewheel::{
 ///
 /// The signal simply passing through [`SendNode`] is untouched, while the
 /// send output has [`SendNode::send_volume`] applied.
-#[derive(Diff, Patch, Debug, Clone, Component)]
+///
+/// [`SendNode`] can be saved in scenes as long as its target is
+/// an entity or a label, not a Firewheel [`NodeID`][firewheel::node::NodeID].
+#[derive(Diff, Patch, Debug, Clone, Component, Reflect)]
+#[reflect(Component, MapEntities)]
 pub struct SendNode {
     /// The send volume.
     ///
     /// This affects only the send outputs.
+    #[reflect(remote = crate::reflect::VolumeReflect)]
     pub send_volume: Volume,
 
     #[diff(skip)]
     pub(crate) target: EdgeTarget,
+
+    /// The first input on the target that the send connects to.
+    #[diff(skip)]
+    pub(crate) target_input: u32,
+}
+
+impl MapEntities for SendNode {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        if let EdgeTarget::Entity(entity) = &mut self.target {
+            *entity = entity_mapper.map_entity(*entity);
+        }
+    }
 }
 
 pub(crate) fn connect_sends(
@@ -58,17 +80,23 @@ pub(crate) fn connect_sends(
             &SendNode,
             &SendConfig,
             Option<&mut PendingConnections>,
+            Option<&Connections>,
         ),
         Added<SendNode>,
     >,
     mut commands: Commands,
 ) {
-    for (entity, send_node, send_config, pending) in sends.iter_mut() {
+    for (entity, send_node, send_config, pending, recorded) in sends.iter_mut() {
+        // Sends loaded from a scene restore their recorded connections instead.
+        if recorded.is_some_and(|r| r.iter().next().is_some()) {
+            continue;
+        }
+
         let target = send_node.target.clone();
 
         let total_channels = send_config.channels.get().get();
         let ports = (0..total_channels)
-            .map(|c| (c + total_channels, c))
+            .map(|c| (c + total_channels, c + send_node.target_input))
             .collect();
 
         let pending_connection = PendingEdge::new(target, Some(ports));
@@ -120,7 +148,7 @@ pub(crate) fn update_remote_sends(
 
         let total_channels = send_config.channels.get().get();
         let ports = (0..total_channels)
-            .map(|c| (c + total_channels, c))
+            .map(|c| (c + total_channels, c + remote_node.target_input))
             .collect();
 
         let pending_connection = PendingEdge::new(new_target, Some(ports));
@@ -136,8 +164,27 @@ impl SendNode {
         Self {
             send_volume,
             target: send_target.into(),
+            target_input: 0,
         }
     }
+
+    /// Connect the send output to the target's inputs beginning at `input`.
+    ///
+    /// This is useful for feeding auxiliary inputs, such as the sidechain
+    /// of a [`CompressorNode`][crate::prelude::CompressorNode].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_seedling::prelude::*;
+    /// # fn system(mut commands: Commands, compressor: Entity) {
+    /// // A stereo compressor's sidechain begins at input 2.
+    /// commands.spawn(SendNode::new(Volume::UNITY_GAIN, compressor).with_target_input(2));
+    /// # }
+    /// ```
+    pub fn with_target_input(mut self, input: u32) -> Self {
+        self.target_input = input;
+        self
+    }
 }
 
 /// [`SendNode`]'s configuration.
@@ -0,0 +1,704 @@
+//! Offline, device-free audio rendering.
+//!
+//! [`OfflineBackend`] drives the audio graph without a sound card,
+//! capturing the interleaved output to a WAV file or an in-memory buffer.
+//! This makes it well-suited for CI, golden-audio regression tests,
+//! and rendering audio for trailers.
+//!
+//! The backend can render in two modes.
+//!
+//! - [`OfflineMode::Lockstep`] renders a fixed number of frames each time
+//!   its [`OfflineClock`] is advanced. With the [`OfflinePlugin`], this
+//!   happens once per Bevy frame, making renders fully deterministic.
+//! - [`OfflineMode::FreeRunning`] renders as fast as possible on a
+//!   background thread.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_seedling::{
+//!     offline::{OfflineBackend, OfflineClock, OfflineConfig, OfflineMode, OfflineOutput, OfflinePlugin},
+//!     prelude::*,
+//! };
+//!
+//! fn main() {
+//!     // Render 800 frames (1/60th of a second at 48kHz) per update.
+//!     let clock = OfflineClock::new(800);
+//!
+//!     App::new()
+//!         .add_plugins((
+//!             MinimalPlugins,
+//!             AssetPlugin::default(),
+//!             SeedlingPlugin::<OfflineBackend> {
+//!                 stream_config: OfflineConfig {
+//!                     mode: OfflineMode::Lockstep(clock.clone()),
+//!                     output: OfflineOutput::Wav("render.wav".into()),
+//!                     ..Default::default()
+//!                 },
+//!                 ..SeedlingPlugin::<OfflineBackend>::new()
+//!             },
+//!             OfflinePlugin::new(clock),
+//!         ))
+//!         .run();
+//! }
+//! ```
+
+use crate::SeedlingSystems;
+use bevy_app::{Last, Plugin};
+use bevy_ecs::prelude::*;
+use firewheel::{
+    backend::{AudioBackend, DeviceInfo},
+    clock::ClockSeconds,
+    node::StreamStatus,
+    processor::FirewheelProcessor,
+    StreamInfo,
+};
+use std::{
+    fs::File,
+    io::BufWriter,
+    num::NonZeroU32,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread::JoinHandle,
+};
+
+mod wav;
+
+use wav::WavWriter;
+
+const DEVICE_NAME: &str = "offline output";
+
+/// An audio backend that renders without an audio device.
+///
+/// For more information, see [the module docs][self].
+pub struct OfflineBackend {
+    channels: NonZeroU32,
+    sender: mpsc::Sender<Message>,
+    thread: Option<JoinHandle<()>>,
+    error: Arc<Mutex<Option<OfflineError>>>,
+}
+
+impl core::fmt::Debug for OfflineBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OfflineBackend")
+            .field("channels", &self.channels)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OfflineBackend {
+    /// The number of interleaved output channels this stream renders.
+    ///
+    /// This is also reported through the stream's
+    /// [`StreamInfo::num_stream_out_channels`].
+    pub fn channels(&self) -> NonZeroU32 {
+        self.channels
+    }
+}
+
+/// [`OfflineBackend`]'s configuration.
+#[derive(Debug, Clone)]
+pub struct OfflineConfig {
+    /// The sample rate of the rendered audio.
+    ///
+    /// Defaults to 48kHz.
+    pub sample_rate: NonZeroU32,
+
+    /// The maximum number of frames processed at once.
+    ///
+    /// Defaults to 128.
+    pub block_frames: NonZeroU32,
+
+    /// The number of interleaved output channels.
+    ///
+    /// Defaults to 2.
+    pub channels: NonZeroU32,
+
+    /// Determines when audio is rendered.
+    pub mode: OfflineMode,
+
+    /// Where the rendered audio is written.
+    pub output: OfflineOutput,
+}
+
+impl Default for OfflineConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: NonZeroU32::new(48000).unwrap(),
+            block_frames: NonZeroU32::new(128).unwrap(),
+            channels: NonZeroU32::new(2).unwrap(),
+            mode: OfflineMode::default(),
+            output: OfflineOutput::default(),
+        }
+    }
+}
+
+/// Determines when [`OfflineBackend`] renders audio.
+#[derive(Debug, Clone)]
+pub enum OfflineMode {
+    /// Render as fast as possible on a background thread.
+    ///
+    /// If `max_frames` is provided, rendering stops once
+    /// that many frames have been produced.
+    FreeRunning {
+        /// The total number of frames to render.
+        max_frames: Option<u64>,
+    },
+    /// Render only when the [`OfflineClock`] is advanced.
+    Lockstep(OfflineClock),
+}
+
+impl Default for OfflineMode {
+    fn default() -> Self {
+        Self::FreeRunning { max_frames: None }
+    }
+}
+
+/// The destination for rendered audio.
+#[derive(Debug, Clone, Default)]
+pub enum OfflineOutput {
+    /// Discard the rendered audio.
+    #[default]
+    Discard,
+    /// Append interleaved samples to an in-memory buffer.
+    Buffer(OfflineBuffer),
+    /// Write a 32-bit float WAV file.
+    ///
+    /// The file's header is finalized when the backend is dropped.
+    Wav(PathBuf),
+}
+
+/// A shared, in-memory buffer of interleaved samples.
+///
+/// Cloning this buffer produces another handle to the same samples.
+#[derive(Debug, Clone, Default)]
+pub struct OfflineBuffer(Arc<Mutex<Vec<f32>>>);
+
+impl OfflineBuffer {
+    /// Construct a new, empty [`OfflineBuffer`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy out all samples rendered so far.
+    pub fn samples(&self) -> Vec<f32> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// Take all samples rendered so far, leaving the buffer empty.
+    pub fn take(&self) -> Vec<f32> {
+        core::mem::take(&mut *self.0.lock().unwrap())
+    }
+
+    /// Write the samples rendered so far to a 32-bit float WAV file.
+    pub fn write_wav(
+        &self,
+        path: impl AsRef<Path>,
+        sample_rate: NonZeroU32,
+        channels: NonZeroU32,
+    ) -> std::io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut writer = WavWriter::new(file, sample_rate.get(), channels.get() as u16)?;
+
+        writer.write_samples(&self.0.lock().unwrap())?;
+        writer.finalize()
+    }
+
+    fn extend(&self, samples: &[f32]) {
+        self.0.lock().unwrap().extend_from_slice(samples);
+    }
+}
+
+/// A handle for driving [`OfflineMode::Lockstep`] rendering.
+///
+/// Cloning the clock produces another handle to the same stream.
+#[derive(Debug, Clone, Resource)]
+pub struct OfflineClock(Arc<ClockInner>);
+
+#[derive(Debug)]
+struct ClockInner {
+    frames_per_update: u64,
+    sender: Mutex<Option<mpsc::Sender<Message>>>,
+}
+
+impl OfflineClock {
+    /// Construct a new [`OfflineClock`].
+    ///
+    /// `frames_per_update` determines how many frames are
+    /// rendered in [`OfflineClock::update`].
+    pub fn new(frames_per_update: u64) -> Self {
+        Self(Arc::new(ClockInner {
+            frames_per_update,
+            sender: Mutex::new(None),
+        }))
+    }
+
+    /// The number of frames rendered in [`OfflineClock::update`].
+    pub fn frames_per_update(&self) -> u64 {
+        self.0.frames_per_update
+    }
+
+    /// Render [`OfflineClock::frames_per_update`] frames.
+    ///
+    /// For more information, see [`OfflineClock::advance`].
+    pub fn update(&self) -> bool {
+        self.advance(self.0.frames_per_update)
+    }
+
+    /// Render `frames` frames, blocking until they've been processed.
+    ///
+    /// Returns `false` if the stream isn't running.
+    pub fn advance(&self, frames: u64) -> bool {
+        let Some(sender) = self.0.sender.lock().unwrap().clone() else {
+            return false;
+        };
+
+        let (done, receive) = mpsc::sync_channel(1);
+
+        if sender.send(Message::Render { frames, done }).is_err() {
+            return false;
+        }
+
+        receive.recv().is_ok()
+    }
+}
+
+/// Advances an [`OfflineClock`] once per frame.
+///
+/// The clock is advanced in the [`Last`] schedule after
+/// the audio context is flushed, so all events queued
+/// during a frame are processed before its audio is rendered.
+#[derive(Debug)]
+pub struct OfflinePlugin {
+    clock: OfflineClock,
+}
+
+impl OfflinePlugin {
+    /// Construct a new [`OfflinePlugin`].
+    ///
+    /// This should be the same clock provided to [`OfflineMode::Lockstep`].
+    pub fn new(clock: OfflineClock) -> Self {
+        Self { clock }
+    }
+}
+
+impl Plugin for OfflinePlugin {
+    fn build(&self, app: &mut bevy_app::App) {
+        app.insert_resource(self.clock.clone())
+            .add_systems(Last, advance_clock.after(SeedlingSystems::Flush));
+    }
+}
+
+fn advance_clock(clock: Res<OfflineClock>) {
+    clock.update();
+}
+
+/// Errors produced by [`OfflineBackend`].
+#[derive(Debug)]
+pub enum OfflineError {
+    /// An I/O error, such as a failure to write the output file.
+    StdIo(std::io::Error),
+}
+
+impl From<std::io::Error> for OfflineError {
+    fn from(value: std::io::Error) -> Self {
+        Self::StdIo(value)
+    }
+}
+
+impl std::error::Error for OfflineError {}
+
+impl core::fmt::Display for OfflineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StdIo(stdio) => stdio.fmt(f),
+        }
+    }
+}
+
+enum Message {
+    Processor(FirewheelProcessor),
+    Render {
+        frames: u64,
+        done: mpsc::SyncSender<()>,
+    },
+    Shutdown,
+}
+
+enum Sink {
+    Discard,
+    Buffer(OfflineBuffer),
+    Wav(WavWriter<BufWriter<File>>),
+}
+
+impl Sink {
+    fn write(&mut self, samples: &[f32]) -> std::io::Result<()> {
+        match self {
+            Self::Discard => Ok(()),
+            Self::Buffer(buffer) => {
+                buffer.extend(samples);
+                Ok(())
+            }
+            Self::Wav(writer) => writer.write_samples(samples),
+        }
+    }
+
+    fn finalize(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Wav(writer) => writer.finalize(),
+            _ => Ok(()),
+        }
+    }
+}
+
+struct Renderer {
+    processor: Option<FirewheelProcessor>,
+    sink: Sink,
+    error: Arc<Mutex<Option<OfflineError>>>,
+    sample_rate: f64,
+    block_frames: usize,
+    channels: usize,
+    frames_rendered: u64,
+    input: Vec<f32>,
+    output: Vec<f32>,
+}
+
+impl Renderer {
+    /// Render `frames` frames in blocks of at most `block_frames`.
+    fn render(&mut self, mut frames: u64) {
+        while frames > 0 {
+            let block = (frames as usize).min(self.block_frames);
+            let len = block * self.channels;
+            let seconds = ClockSeconds(self.frames_rendered as f64 / self.sample_rate);
+
+            match &mut self.processor {
+                Some(processor) => processor.process_interleaved(
+                    &self.input[..len],
+                    &mut self.output[..len],
+                    self.channels,
+                    self.channels,
+                    block,
+                    seconds,
+                    StreamStatus::empty(),
+                ),
+                // Until a processor arrives, we simply render silence
+                // to keep the timeline consistent.
+                None => self.output[..len].fill(0.0),
+            }
+
+            if let Err(e) = self.sink.write(&self.output[..len]) {
+                self.report(e);
+            }
+
+            self.frames_rendered += block as u64;
+            frames -= block as u64;
+        }
+    }
+
+    fn report(&mut self, error: std::io::Error) {
+        // Once the sink fails, there's no point in continuing to write to it.
+        self.sink = Sink::Discard;
+        *self.error.lock().unwrap() = Some(error.into());
+    }
+
+    fn finish(mut self) {
+        if let Err(e) = self.sink.finalize() {
+            self.report(e);
+        }
+    }
+}
+
+fn run_lockstep(mut renderer: Renderer, receiver: mpsc::Receiver<Message>) {
+    while let Ok(message) = receiver.recv() {
+        match message {
+            Message::Processor(processor) => renderer.processor = Some(processor),
+            Message::Render { frames, done } => {
+                renderer.render(frames);
+                let _ = done.send(());
+            }
+            Message::Shutdown => break,
+        }
+    }
+
+    renderer.finish();
+}
+
+fn run_free(mut renderer: Renderer, receiver: mpsc::Receiver<Message>, max_frames: Option<u64>) {
+    loop {
+        // Wait for a processor before rendering anything.
+        let message = if renderer.processor.is_none()
+            || max_frames.is_some_and(|max| renderer.frames_rendered >= max)
+        {
+            match receiver.recv() {
+                Ok(message) => Some(message),
+                Err(_) => break,
+            }
+        } else {
+            match receiver.try_recv() {
+                Ok(message) => Some(message),
+                Err(mpsc::TryRecvError::Empty) => None,
+                Err(mpsc::TryRecvError::Disconnected) => break,
+            }
+        };
+
+        match message {
+            Some(Message::Processor(processor)) => renderer.processor = Some(processor),
+            // Free-running streams ignore clock updates.
+            Some(Message::Render { done, .. }) => {
+                let _ = done.send(());
+            }
+            Some(Message::Shutdown) => break,
+            None => {
+                let remaining = max_frames
+                    .map(|max| max - renderer.frames_rendered)
+                    .unwrap_or(u64::MAX);
+                let block = remaining.min(renderer.block_frames as u64);
+
+                renderer.render(block);
+            }
+        }
+    }
+
+    renderer.finish();
+}
+
+impl AudioBackend for OfflineBackend {
+    type Config = OfflineConfig;
+
+    type StartStreamError = OfflineError;
+    type StreamError = OfflineError;
+
+    fn available_input_devices() -> Vec<DeviceInfo> {
+        vec![]
+    }
+
+    /// The virtual device renders any channel count, so it
+    /// lists [`OfflineConfig`]'s default. Each stream's configured
+    /// count is reported through its [`StreamInfo::num_stream_out_channels`].
+    fn available_output_devices() -> Vec<DeviceInfo> {
+        vec![DeviceInfo {
+            name: DEVICE_NAME.into(),
+            num_channels: OfflineConfig::default().channels.get() as u16,
+            is_default: true,
+        }]
+    }
+
+    fn start_stream(config: Self::Config) -> Result<(Self, StreamInfo), Self::StartStreamError> {
+        let OfflineConfig {
+            sample_rate,
+            block_frames,
+            channels,
+            mode,
+            output,
+        } = config;
+
+        let sink = match output {
+            OfflineOutput::Discard => Sink::Discard,
+            OfflineOutput::Buffer(buffer) => Sink::Buffer(buffer),
+            OfflineOutput::Wav(path) => {
+                let file = BufWriter::new(File::create(path)?);
+                Sink::Wav(WavWriter::new(
+                    file,
+                    sample_rate.get(),
+                    channels.get() as u16,
+                )?)
+            }
+        };
+
+        let error = Arc::new(Mutex::new(None));
+        let block_len = block_frames.get() as usize * channels.get() as usize;
+
+        let renderer = Renderer {
+            processor: None,
+            sink,
+            error: error.clone(),
+            sample_rate: sample_rate.get() as f64,
+            block_frames: block_frames.get() as usize,
+            channels: channels.get() as usize,
+            frames_rendered: 0,
+            input: vec![0.0; block_len],
+            output: vec![0.0; block_len],
+        };
+
+        let (sender, receiver) = mpsc::channel();
+
+        let thread = match mode {
+            OfflineMode::Lockstep(clock) => {
+                *clock.0.sender.lock().unwrap() = Some(sender.clone());
+                std::thread::spawn(move || run_lockstep(renderer, receiver))
+            }
+            OfflineMode::FreeRunning { max_frames } => {
+                std::thread::spawn(move || run_free(renderer, receiver, max_frames))
+            }
+        };
+
+        Ok((
+            Self {
+                channels,
+                sender,
+                thread: Some(thread),
+                error,
+            },
+            StreamInfo {
+                sample_rate,
+                sample_rate_recip: 1.0 / sample_rate.get() as f64,
+                max_block_frames: block_frames,
+                num_stream_in_channels: 0,
+                num_stream_out_channels: channels.get(),
+                declick_frames: NonZeroU32::new(16).unwrap(),
+                input_device_name: None,
+                output_device_name: Some(DEVICE_NAME.into()),
+                input_to_output_latency_seconds: 0.0,
+            },
+        ))
+    }
+
+    fn set_processor(&mut self, processor: FirewheelProcessor) {
+        // If the render thread has exited, there's nothing left to process.
+        let _ = self.sender.send(Message::Processor(processor));
+    }
+
+    fn poll_status(&mut self) -> Result<(), Self::StreamError> {
+        match self.error.lock().unwrap().take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for OfflineBackend {
+    fn drop(&mut self) {
+        let _ = self.sender.send(Message::Shutdown);
+
+        // Joining ensures the output is finalized
+        // before the backend goes away.
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::prelude::*;
+    use bevy::prelude::*;
+    use bevy_ecs::system::RunSystemOnce;
+
+    #[test]
+    fn test_lockstep_render() {
+        const FRAMES: u64 = 512;
+        // The 1ms sample needs only a handful of updates,
+        // so anything beyond this is a regression.
+        const MAX_UPDATES: u64 = 64;
+
+        let buffer = OfflineBuffer::new();
+        let clock = OfflineClock::new(FRAMES);
+
+        let mut app = App::new();
+
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            SeedlingPlugin::<OfflineBackend> {
+                stream_config: OfflineConfig {
+                    mode: OfflineMode::Lockstep(clock.clone()),
+                    output: OfflineOutput::Buffer(buffer.clone()),
+                    ..Default::default()
+                },
+                default_pool_size: Some(4),
+                main_bus_limiter: None,
+                ..SeedlingPlugin::<OfflineBackend>::new()
+            },
+            OfflinePlugin::new(clock),
+            HierarchyPlugin,
+        ))
+        .add_systems(
+            Startup,
+            |mut commands: Commands, server: Res<AssetServer>| {
+                commands.spawn(SamplePlayer::new(server.load("sine_440hz_1ms.wav")));
+            },
+        );
+
+        app.finish();
+        app.cleanup();
+
+        // Render until the sample has finished playing.
+        let mut updates = 0;
+        let mut finished = false;
+        while updates < MAX_UPDATES {
+            app.update();
+            updates += 1;
+
+            let players = app
+                .world_mut()
+                .run_system_once(|q: Query<&SamplePlayer>| q.iter().len())
+                .unwrap();
+
+            if players == 0 {
+                finished = true;
+                break;
+            }
+        }
+
+        assert!(finished, "sample still playing after {MAX_UPDATES} updates");
+
+        let samples = buffer.samples();
+
+        // Exactly one clock update per frame.
+        assert_eq!(samples.len() as u64, updates * FRAMES * 2);
+        assert!(samples.iter().any(|s| *s != 0.0));
+
+        let devices = OfflineBackend::available_output_devices();
+        assert_eq!(devices[0].num_channels, 2);
+    }
+
+    #[test]
+    fn test_stream_channels() {
+        let prepare_app = |channels: u32| {
+            let clock = OfflineClock::new(128);
+            let mut app = App::new();
+
+            app.add_plugins((
+                MinimalPlugins,
+                AssetPlugin::default(),
+                SeedlingPlugin::<OfflineBackend> {
+                    stream_config: OfflineConfig {
+                        mode: OfflineMode::Lockstep(clock.clone()),
+                        channels: NonZeroU32::new(channels).unwrap(),
+                        ..Default::default()
+                    },
+                    default_pool_size: None,
+                    main_bus_limiter: None,
+                    ..SeedlingPlugin::<OfflineBackend>::new()
+                },
+                OfflinePlugin::new(clock),
+                HierarchyPlugin,
+            ));
+
+            app.finish();
+            app.cleanup();
+            app.update();
+
+            app
+        };
+
+        let stream_channels = |app: &mut App| {
+            app.world_mut()
+                .run_system_once(|mut context: ResMut<AudioContext>| {
+                    context.with(|context| {
+                        context
+                            .stream_info()
+                            .map(|info| info.num_stream_out_channels)
+                    })
+                })
+                .unwrap()
+        };
+
+        // Streams in the same process keep their own configuration.
+        let mut quad = prepare_app(4);
+        let mut stereo = prepare_app(2);
+
+        assert_eq!(stream_channels(&mut quad), Some(4));
+        assert_eq!(stream_channels(&mut stereo), Some(2));
+    }
+}
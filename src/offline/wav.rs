@@ -0,0 +1,130 @@
+//! A minimal WAV writer for offline renders.
+
+use std::io::{self, Seek, SeekFrom, Write};
+
+const FORMAT_IEEE_FLOAT: u16 = 3;
+const BYTES_PER_SAMPLE: u16 = 4;
+
+// Byte offsets of the fields we need to patch once the total length is known.
+const RIFF_SIZE_OFFSET: u64 = 4;
+const FACT_FRAMES_OFFSET: u64 = 46;
+const DATA_SIZE_OFFSET: u64 = 54;
+
+/// The size of the header written by [`WavWriter::new`].
+pub(crate) const HEADER_LEN: u64 = 58;
+
+/// Writes interleaved, 32-bit float samples to a WAV stream.
+///
+/// The header is written with placeholder lengths, which are
+/// patched in [`WavWriter::finalize`].
+#[derive(Debug)]
+pub(crate) struct WavWriter<W: Write + Seek> {
+    inner: W,
+    channels: u16,
+    samples_written: u64,
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// Write the WAV header, returning a writer ready for samples.
+    pub fn new(mut inner: W, sample_rate: u32, channels: u16) -> io::Result<Self> {
+        let block_align = channels * BYTES_PER_SAMPLE;
+
+        inner.write_all(b"RIFF")?;
+        inner.write_all(&0u32.to_le_bytes())?;
+        inner.write_all(b"WAVE")?;
+
+        inner.write_all(b"fmt ")?;
+        inner.write_all(&18u32.to_le_bytes())?;
+        inner.write_all(&FORMAT_IEEE_FLOAT.to_le_bytes())?;
+        inner.write_all(&channels.to_le_bytes())?;
+        inner.write_all(&sample_rate.to_le_bytes())?;
+        inner.write_all(&(sample_rate * block_align as u32).to_le_bytes())?;
+        inner.write_all(&block_align.to_le_bytes())?;
+        inner.write_all(&(BYTES_PER_SAMPLE * 8).to_le_bytes())?;
+        // no extension
+        inner.write_all(&0u16.to_le_bytes())?;
+
+        // Non-PCM formats require a `fact` chunk.
+        inner.write_all(b"fact")?;
+        inner.write_all(&4u32.to_le_bytes())?;
+        inner.write_all(&0u32.to_le_bytes())?;
+
+        inner.write_all(b"data")?;
+        inner.write_all(&0u32.to_le_bytes())?;
+
+        Ok(Self {
+            inner,
+            channels,
+            samples_written: 0,
+        })
+    }
+
+    /// Append interleaved samples.
+    pub fn write_samples(&mut self, samples: &[f32]) -> io::Result<()> {
+        for sample in samples {
+            self.inner.write_all(&sample.to_le_bytes())?;
+        }
+
+        self.samples_written += samples.len() as u64;
+
+        Ok(())
+    }
+
+    /// Patch the header lengths and flush the stream.
+    ///
+    /// The writer can continue to accept samples after this call,
+    /// though it will need to be finalized again.
+    pub fn finalize(&mut self) -> io::Result<()> {
+        let data_len = self.samples_written * BYTES_PER_SAMPLE as u64;
+        let frames = self.samples_written / self.channels.max(1) as u64;
+
+        // WAV can't address more than 4GiB, so we simply saturate.
+        let riff_len = u32::try_from(HEADER_LEN - 8 + data_len).unwrap_or(u32::MAX);
+        let data_len = u32::try_from(data_len).unwrap_or(u32::MAX);
+        let frames = u32::try_from(frames).unwrap_or(u32::MAX);
+
+        let end = self.inner.stream_position()?;
+
+        self.inner.seek(SeekFrom::Start(RIFF_SIZE_OFFSET))?;
+        self.inner.write_all(&riff_len.to_le_bytes())?;
+        self.inner.seek(SeekFrom::Start(FACT_FRAMES_OFFSET))?;
+        self.inner.write_all(&frames.to_le_bytes())?;
+        self.inner.seek(SeekFrom::Start(DATA_SIZE_OFFSET))?;
+        self.inner.write_all(&data_len.to_le_bytes())?;
+
+        self.inner.seek(SeekFrom::Start(end))?;
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Cursor;
+
+    fn read_u32(bytes: &[u8], offset: u64) -> u32 {
+        let offset = offset as usize;
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_header() {
+        let mut writer = WavWriter::new(Cursor::new(Vec::new()), 48000, 2).unwrap();
+        writer.write_samples(&[0.0, 0.5, -0.5, 1.0]).unwrap();
+        writer.finalize().unwrap();
+
+        let bytes = writer.inner.into_inner();
+
+        assert_eq!(bytes.len() as u64, HEADER_LEN + 16);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(&bytes[8..12], b"WAVE");
+        assert_eq!(read_u32(&bytes, RIFF_SIZE_OFFSET), bytes.len() as u32 - 8);
+        assert_eq!(read_u32(&bytes, 24), 48000);
+        assert_eq!(read_u32(&bytes, FACT_FRAMES_OFFSET), 2);
+        assert_eq!(&bytes[50..54], b"data");
+        assert_eq!(read_u32(&bytes, DATA_SIZE_OFFSET), 16);
+
+        let last = f32::from_le_bytes(bytes[bytes.len() - 4..].try_into().unwrap());
+        assert_eq!(last, 1.0);
+    }
+}
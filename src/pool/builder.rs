@@ -1,5 +1,6 @@
 //! Pool builder trait and struct.
 
+use super::policy::{QueueTimeout, StealPolicy};
 use super::SamplePoolTypes;
 use crate::prelude::PoolLabel;
 use bevy_ecs::prelude::*;
@@ -162,8 +163,9 @@ pub trait PoolBuilder {
 /// sampler nodes, each of which can play a single sample at a time.
 /// When samples are queued up for playback, `bevy_seedling` will
 /// look for the best sampler in the corresponding pool. If a suitable
-/// sampler is found, the sample will begin playback, otherwise
-/// waiting until a slot opens up.
+/// sampler is found, the sample will begin playback. Otherwise, a busy
+/// sampler is stolen according to the pool's [`StealPolicy`], or the
+/// sample waits until a slot opens up.
 ///
 /// Each sampler node is routed to a final volume node. For a simple pool:
 ///
@@ -229,6 +231,8 @@ pub struct Pool<L> {
     label: L,
     size: usize,
     defaults: SamplePoolTypes,
+    steal_policy: StealPolicy,
+    queue_timeout: QueueTimeout,
 }
 
 impl<L: PoolLabel + Component + Clone> Pool<L> {
@@ -247,8 +251,32 @@ impl<L: PoolLabel + Component + Clone> Pool<L> {
             label,
             size,
             defaults: Default::default(),
+            steal_policy: Default::default(),
+            queue_timeout: Default::default(),
         }
     }
+
+    /// Set the pool's [`StealPolicy`].
+    ///
+    /// Defaults to [`StealPolicy::Oldest`].
+    #[inline(always)]
+    #[must_use]
+    pub fn steal_policy(mut self, policy: StealPolicy) -> Self {
+        self.steal_policy = policy;
+
+        self
+    }
+
+    /// Set the pool's [`QueueTimeout`].
+    ///
+    /// Defaults to [`QueueTimeout::Never`].
+    #[inline(always)]
+    #[must_use]
+    pub fn queue_timeout(mut self, timeout: QueueTimeout) -> Self {
+        self.queue_timeout = timeout;
+
+        self
+    }
 }
 
 impl<L: PoolLabel + Component + Clone> Pool<L> {
@@ -259,9 +287,18 @@ impl<L: PoolLabel + Component + Clone> Pool<L> {
             label,
             size,
             defaults,
+            steal_policy,
+            queue_timeout,
         } = self;
 
-        super::spawn_pool(label, size..=size, defaults, commands)
+        super::spawn_pool(
+            label,
+            size..=size,
+            defaults,
+            steal_policy,
+            queue_timeout,
+            commands,
+        )
     }
 }
 
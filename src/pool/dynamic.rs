@@ -132,6 +132,8 @@ pub(super) fn update_auto_pools(
                     label,
                     dynamic_range.clone(),
                     defaults.clone(),
+                    Default::default(),
+                    Default::default(),
                     &mut commands,
                 );
 
@@ -244,6 +246,7 @@ mod test {
             AssetPlugin::default(),
             SeedlingPlugin::<ProfilingBackend> {
                 default_pool_size: None,
+                main_bus_limiter: None,
                 dynamic_pool_range: Some(4..=16),
                 ..SeedlingPlugin::<ProfilingBackend>::new()
             },
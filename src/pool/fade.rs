@@ -0,0 +1,149 @@
+//! Per-voice fades for sampler pools.
+
+use crate::timeline::Timeline;
+use bevy_ecs::prelude::*;
+use bevy_reflect::Reflect;
+use firewheel::{
+    channel_config::{ChannelConfig, NonZeroChannelCount},
+    clock::ClockSeconds,
+    diff::{Diff, Patch},
+    event::NodeEventList,
+    node::{
+        AudioNode, AudioNodeInfo, AudioNodeProcessor, ConstructProcessorContext, ProcBuffers,
+        ProcInfo, ProcessStatus,
+    },
+};
+
+/// The number of frames between timeline evaluations.
+const TICK_FRAMES: usize = 32;
+
+/// A gain stage placed directly after each pool sampler.
+///
+/// Since the gain is a [`Timeline`], fades are
+/// sample-accurate on the audio clock.
+#[derive(Diff, Patch, Debug, Clone, Component, Reflect)]
+#[reflect(Component)]
+pub(crate) struct FadeNode {
+    /// The linear gain.
+    pub gain: Timeline<f32>,
+}
+
+impl Default for FadeNode {
+    fn default() -> Self {
+        Self {
+            gain: Timeline::new(1.),
+        }
+    }
+}
+
+/// [`FadeNode`]'s configuration.
+#[derive(Debug, Component, Clone)]
+pub(crate) struct FadeConfig {
+    /// The number of input and output channels.
+    pub channels: NonZeroChannelCount,
+}
+
+impl Default for FadeConfig {
+    fn default() -> Self {
+        Self {
+            channels: NonZeroChannelCount::STEREO,
+        }
+    }
+}
+
+impl AudioNode for FadeNode {
+    type Configuration = FadeConfig;
+
+    fn info(&self, config: &Self::Configuration) -> AudioNodeInfo {
+        AudioNodeInfo::new()
+            .debug_name("fade")
+            .channel_config(ChannelConfig {
+                num_inputs: config.channels.get(),
+                num_outputs: config.channels.get(),
+            })
+            .uses_events(true)
+    }
+
+    fn construct_processor(
+        &self,
+        _: &Self::Configuration,
+        _: ConstructProcessorContext,
+    ) -> impl AudioNodeProcessor {
+        FadeProcessor {
+            params: self.clone(),
+        }
+    }
+}
+
+struct FadeProcessor {
+    params: FadeNode,
+}
+
+impl AudioNodeProcessor for FadeProcessor {
+    fn process(
+        &mut self,
+        ProcBuffers {
+            inputs, outputs, ..
+        }: ProcBuffers,
+        proc_info: &ProcInfo,
+        events: NodeEventList,
+    ) -> ProcessStatus {
+        self.params.patch_list(events);
+
+        let start = proc_info.clock_seconds.start;
+        let end = proc_info.clock_seconds.end;
+
+        if proc_info.in_silence_mask.all_channels_silent(inputs.len()) {
+            // Keep the timeline up to date while silent.
+            self.params.gain.tick(end);
+            return ProcessStatus::ClearAllOutputs;
+        }
+
+        if !self.params.gain.active_within(start, end) && !self.params.gain.is_active(start) {
+            self.params.gain.tick(start);
+            let gain = self.params.gain.get();
+
+            if gain == 0. {
+                return ProcessStatus::ClearAllOutputs;
+            }
+
+            for (input, output) in inputs.iter().zip(outputs.iter_mut()) {
+                for (i, o) in input.iter().zip(output.iter_mut()) {
+                    *o = *i * gain;
+                }
+            }
+
+            return ProcessStatus::outputs_not_silent();
+        }
+
+        // Linearly interpolate between timeline evaluations.
+        let frame_time = (end.0 - start.0) / proc_info.frames as f64;
+        let mut segment_start = 0;
+        let mut gain = self.params.gain.value_at(start);
+
+        while segment_start < proc_info.frames {
+            let segment_end = (segment_start + TICK_FRAMES).min(proc_info.frames);
+            let next_gain = self
+                .params
+                .gain
+                .value_at(start + ClockSeconds(segment_end as f64 * frame_time));
+            let step = (next_gain - gain) / (segment_end - segment_start) as f32;
+
+            for (input, output) in inputs.iter().zip(outputs.iter_mut()) {
+                let mut gain = gain;
+
+                for frame in segment_start..segment_end {
+                    output[frame] = input[frame] * gain;
+                    gain += step;
+                }
+            }
+
+            gain = next_gain;
+            segment_start = segment_end;
+        }
+
+        self.params.gain.tick(end);
+
+        ProcessStatus::outputs_not_silent()
+    }
+}
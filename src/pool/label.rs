@@ -46,6 +46,21 @@ bevy_ecs::define_label!(
     /// #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
     /// struct MyPool;
     /// ```
+    ///
+    /// To save a pool in scenes, derive and register [`Reflect`][bevy_reflect::Reflect]
+    /// for its label as well.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_seedling::prelude::*;
+    /// #[derive(PoolLabel, Reflect, Debug, Clone, PartialEq, Eq, Hash)]
+    /// #[reflect(Component)]
+    /// struct MyPool;
+    ///
+    /// # fn plugin(app: &mut App) {
+    /// app.register_type::<MyPool>();
+    /// # }
+    /// ```
     PoolLabel,
     POOL_LABEL_INTERNER
 );
@@ -1,41 +1,85 @@
 //! Sampler pools, which represent primary sampler player mechanism.
 
 use crate::node::ParamFollower;
-use crate::prelude::{AudioContext, Connect, DefaultPool, FirewheelNode, PoolLabel, VolumeNode};
-use crate::sample::{OnComplete, PlaybackSettings, QueuedSample, Sample, SamplePlayer};
+use crate::prelude::{
+    AudioContext, Connect, DefaultPool, FirewheelNode, PoolLabel, RegisterNode, VolumeNode,
+};
+use crate::sample::{
+    events::{LoopWrapped, PlaybackCompleted, PlaybackStarted, PlaybackStolen, SampleLoadFailed},
+    looping::{LoopMap, RegionSample},
+    resample::{ResampledSample, SpeedControl},
+    Fade, OnComplete, PlaybackPosition, PlaybackSettings, PlaybackSpeed, PlaybackState, Playhead,
+    QueuedSample, Sample, SamplePlayer, SamplePriority, Seek,
+};
+use crate::spatial::{DopplerFactor, SpatialListener2D, SpatialListener3D};
 use crate::{node::Events, SeedlingSystems};
 use bevy_app::{Last, Plugin, PostUpdate};
-use bevy_asset::Assets;
-use bevy_ecs::{component::ComponentId, prelude::*, world::DeferredWorld};
+use bevy_asset::{AssetServer, Assets, Handle, LoadState};
+use bevy_ecs::{
+    component::ComponentId,
+    entity::{EntityMapper, MapEntities},
+    prelude::*,
+    reflect::ReflectMapEntities,
+    world::DeferredWorld,
+};
 use bevy_hierarchy::DespawnRecursiveExt;
+use bevy_log::warn_once;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect, ReflectDeserialize, ReflectSerialize};
+use bevy_transform::components::GlobalTransform;
 use bevy_utils::HashSet;
 use dynamic::DynamicPoolRegistry;
 use firewheel::{
+    clock::ClockSeconds,
     event::{NodeEventType, SequenceCommand},
-    nodes::sampler::{SamplerNode, SamplerState},
+    nodes::{
+        sampler::{RepeatMode, SamplerNode, SamplerState},
+        spatial_basic::SpatialBasicNode,
+    },
     Volume,
 };
+use serde::{Deserialize, Serialize};
 use std::any::TypeId;
+use std::num::NonZeroU32;
 use std::sync::Arc;
 
 pub mod builder;
 pub mod dynamic;
+mod fade;
 pub mod label;
+pub mod policy;
 
+use fade::FadeNode;
 use label::PoolLabelContainer;
+use policy::{QueueTimeout, StealPolicy};
 
 pub(crate) struct SamplePoolPlugin;
 
 impl Plugin for SamplePoolPlugin {
     fn build(&self, app: &mut bevy_app::App) {
-        app.init_resource::<dynamic::Registries>()
+        app.register_reflected_node::<FadeNode>()
+            .register_type::<PoolRoot>()
+            .register_type::<SamplerNodes>()
+            .register_type::<SamplePoolNode>()
+            .register_type::<EffectsChain>()
+            .register_type::<VoiceFade>()
+            .register_type::<PoolRange>()
+            .register_type::<NodeRank>()
+            .init_resource::<dynamic::Registries>()
             .add_systems(
                 Last,
                 (
-                    (remove_finished, assign_default)
+                    restore_pools
+                        .after(crate::node::restore_unreflected_nodes)
+                        .before(SeedlingSystems::Acquire),
+                    (
+                        remove_finished,
+                        (update_playback_state, update_playback_speed, apply_seek)
+                            .after(remove_finished),
+                        assign_default,
+                    )
                         .before(SeedlingSystems::Queue)
                         .after(SeedlingSystems::Acquire),
-                    monitor_active
+                    (monitor_active, finish_fades, update_playback_position)
                         .before(SeedlingSystems::Flush)
                         .after(SeedlingSystems::Queue),
                 ),
@@ -46,6 +90,9 @@ impl Plugin for SamplePoolPlugin {
 
 /// Spawn an effects chain, connecting all nodes and
 /// returning the root sampler node.
+///
+/// Every sampler is followed by a [`FadeNode`], since any
+/// sample it plays may request a fade.
 #[cfg_attr(debug_assertions, track_caller)]
 fn spawn_chain<L: Component + Clone>(
     bus: Entity,
@@ -55,12 +102,17 @@ fn spawn_chain<L: Component + Clone>(
 ) -> Entity {
     let chain = defaults.spawn_nodes(label.clone(), commands);
 
+    let fade = commands
+        .spawn((FadeNode::default(), SamplePoolNode, label.clone()))
+        .id();
+
     let source = commands
         .spawn((
             SamplerNode::default(),
             SamplePoolNode,
             label,
             EffectsChain(chain.clone()),
+            VoiceFade(fade),
             PoolRoot(bus),
         ))
         .id();
@@ -68,7 +120,8 @@ fn spawn_chain<L: Component + Clone>(
     let mut chain = chain;
     chain.push(bus);
 
-    commands.entity(source).connect(chain[0]);
+    commands.entity(source).connect(fade);
+    commands.entity(fade).connect(chain[0]);
 
     for pair in chain.windows(2) {
         commands.entity(pair[0]).connect(pair[1]);
@@ -82,27 +135,48 @@ fn spawn_chain<L: Component + Clone>(
 #[derive(Resource, Default)]
 pub(crate) struct RegisteredPools(HashSet<TypeId>);
 
+/// Add the systems that drive pools labelled `L`.
+///
+/// This is called whenever a derived [`PoolLabel`] is inserted,
+/// so pools loaded from scenes are driven like spawned ones.
+/// Since [`Last`] can't be modified while it runs, insertions
+/// during [`Last`] are skipped until a later insertion.
+#[doc(hidden)]
+pub fn register_pool_systems<L: PoolLabel + Component + Clone>(world: &mut World) {
+    let id = TypeId::of::<L>();
+
+    if world
+        .get_resource_or_init::<RegisteredPools>()
+        .0
+        .contains(&id)
+    {
+        return;
+    }
+
+    let added = world.try_schedule_scope(Last, |_, schedule| {
+        schedule.add_systems(
+            (rank_nodes::<L>, assign_work::<L>)
+                .chain()
+                .in_set(SeedlingSystems::Queue),
+        );
+    });
+
+    if added.is_ok() {
+        world.resource_mut::<RegisteredPools>().0.insert(id);
+    }
+}
+
 /// Spawn a sampler pool with an initial size.
 #[cfg_attr(debug_assertions, track_caller)]
 fn spawn_pool<'a, L: PoolLabel + Component + Clone>(
     label: L,
     size: core::ops::RangeInclusive<usize>,
     defaults: SamplePoolTypes,
+    steal_policy: StealPolicy,
+    queue_timeout: QueueTimeout,
     commands: &'a mut Commands,
 ) -> EntityCommands<'a> {
-    commands.queue(|world: &mut World| {
-        let mut resource = world.get_resource_or_init::<RegisteredPools>();
-
-        if resource.0.insert(TypeId::of::<L>()) {
-            world.schedule_scope(Last, |_, schedule| {
-                schedule.add_systems(
-                    (rank_nodes::<L>, assign_work::<L>)
-                        .chain()
-                        .in_set(SeedlingSystems::Queue),
-                );
-            });
-        }
-    });
+    commands.queue(register_pool_systems::<L>);
 
     commands.despawn_pool(label.clone());
 
@@ -115,6 +189,8 @@ fn spawn_pool<'a, L: PoolLabel + Component + Clone>(
             label.clone(),
             NodeRank::default(),
             PoolRange(size.clone()),
+            steal_policy,
+            queue_timeout,
         ))
         .id();
 
@@ -132,14 +208,30 @@ fn spawn_pool<'a, L: PoolLabel + Component + Clone>(
 }
 
 /// The root pool node, analogous to `Parent`.
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component, MapEntities)]
 struct PoolRoot(Entity);
 
+impl MapEntities for PoolRoot {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
 /// A collection of each sampler node in the pool.
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component, MapEntities)]
 #[component(on_remove = on_remove_sampler_nodes)]
 struct SamplerNodes(Vec<Entity>);
 
+impl MapEntities for SamplerNodes {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        for node in &mut self.0 {
+            *node = entity_mapper.map_entity(*node);
+        }
+    }
+}
+
 impl core::ops::Deref for SamplerNodes {
     type Target = [Entity];
 
@@ -161,13 +253,23 @@ fn on_remove_sampler_nodes(mut world: DeferredWorld, entity: Entity, _: Componen
     }
 }
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component)]
 struct SamplePoolNode;
 
-#[derive(Component)]
+#[derive(Component, Reflect)]
+#[reflect(Component, MapEntities)]
 #[component(on_remove = on_remove_effects_chain)]
 struct EffectsChain(Vec<Entity>);
 
+impl MapEntities for EffectsChain {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        for node in &mut self.0 {
+            *node = entity_mapper.map_entity(*node);
+        }
+    }
+}
+
 fn on_remove_effects_chain(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
     let Some(mut nodes) = world.get_mut::<EffectsChain>(entity) else {
         return;
@@ -181,6 +283,43 @@ fn on_remove_effects_chain(mut world: DeferredWorld, entity: Entity, _: Componen
     }
 }
 
+/// The [`FadeNode`] directly following a sampler.
+#[derive(Component, Reflect)]
+#[reflect(Component, MapEntities)]
+#[component(on_remove = on_remove_voice_fade)]
+struct VoiceFade(Entity);
+
+impl MapEntities for VoiceFade {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.0 = entity_mapper.map_entity(self.0);
+    }
+}
+
+fn on_remove_voice_fade(mut world: DeferredWorld, entity: Entity, _: ComponentId) {
+    let Some(fade) = world.get::<VoiceFade>(entity).map(|fade| fade.0) else {
+        return;
+    };
+
+    world.commands().entity(fade).try_despawn();
+}
+
+/// Marks a sampler whose sample is fading out before it's stopped.
+#[derive(Component, Clone, Copy)]
+struct FadingOut(ClockSeconds);
+
+/// Begin fading a sampler out, returning the time the fade completes.
+fn begin_fade_out(fade_node: &mut FadeNode, fade: Fade, now: ClockSeconds) -> ClockSeconds {
+    let end = now + ClockSeconds(fade.duration.as_secs_f64());
+
+    // Interrupt any fade in progress.
+    let current = fade_node.gain.value_at(now);
+    fade_node.gain.set(current);
+    // This can't fail since the timeline was just cleared.
+    let _ = fade_node.gain.push_curve(0., now, end, fade.ease);
+
+    end
+}
+
 trait SamplePoolType {
     /// Insert the pool's default value if the component isn't already present.
     fn insert_default(&self, commands: &mut EntityCommands);
@@ -242,49 +381,391 @@ impl SamplePoolTypes {
     }
 }
 
+/// Per-type functions that recover a pool's [`SamplePoolTypes`]
+/// from one of its effect nodes.
+#[derive(Resource, Default)]
+pub(crate) struct PoolTypeRegistry(
+    Vec<fn(EntityRef) -> Option<Arc<dyn SamplePoolType + Send + Sync + 'static>>>,
+);
+
+impl PoolTypeRegistry {
+    /// Register a node type that may appear in effects chains.
+    pub fn register<T: Component + Clone>(&mut self) {
+        self.0.push(|entity| {
+            entity
+                .get::<T>()
+                .map(|node| Arc::new(node.clone()) as Arc<dyn SamplePoolType + Send + Sync>)
+        });
+    }
+}
+
+/// Rebuild the state that pools loaded from scenes don't save.
+///
+/// A pool's [`SamplePoolTypes`] is recovered from its first sampler's
+/// effects chain, and each sampler receives a default [`SamplerNode`].
+fn restore_pools(world: &mut World) {
+    let mut pools = world.query_filtered::<(Entity, &SamplerNodes), (
+        With<PoolRange>,
+        With<PoolLabelContainer>,
+        Without<SamplePoolTypes>,
+    )>();
+    let pools: Vec<_> = pools
+        .iter(world)
+        .map(|(pool, samplers)| (pool, samplers.first().copied()))
+        .collect();
+
+    let registry = world.resource::<PoolTypeRegistry>();
+    let restored: Vec<_> = pools
+        .into_iter()
+        .map(|(pool, sampler)| {
+            let mut types = SamplePoolTypes::default();
+            let chain = sampler.and_then(|sampler| world.get::<EffectsChain>(sampler));
+
+            for effect in chain.iter().flat_map(|chain| chain.0.iter()) {
+                let Ok(effect) = world.get_entity(*effect) else {
+                    continue;
+                };
+
+                if let Some(ty) = registry.0.iter().find_map(|get| get(effect)) {
+                    types.0.push(ty);
+                }
+            }
+
+            (pool, types)
+        })
+        .collect();
+
+    for (pool, types) in restored {
+        world.entity_mut(pool).insert(types);
+    }
+
+    let mut samplers = world.query_filtered::<Entity, (
+        With<VoiceFade>,
+        With<PoolLabelContainer>,
+        Without<SamplerNode>,
+    )>();
+    let samplers: Vec<_> = samplers.iter(world).collect();
+
+    for sampler in samplers {
+        world.entity_mut(sampler).insert(SamplerNode::default());
+    }
+}
+
 /// Sets the range for the number of pool sampler nodes.
-#[derive(Component, Clone, Debug)]
+#[derive(Component, Clone, Debug, Reflect, Serialize, Deserialize)]
+#[reflect(opaque)]
+#[reflect(Component, Debug, Serialize, Deserialize)]
 struct PoolRange(pub core::ops::RangeInclusive<usize>);
 
 /// Sampler node ranking for playback.
-#[derive(Default, Component)]
-struct NodeRank(Vec<(Entity, u64)>);
+///
+/// This is rebuilt every frame, so it's never saved.
+#[derive(Default, Component, Reflect)]
+#[reflect(Component, Default)]
+struct NodeRank {
+    /// Idle samplers, best first.
+    #[reflect(ignore)]
+    idle: Vec<(Entity, u64)>,
+    /// Busy samplers along with their sample's priority,
+    /// ordered by the pool's [`StealPolicy`].
+    #[reflect(ignore)]
+    stealable: Vec<(Entity, i32)>,
+}
+
+/// A sampler that may be stolen.
+struct StealCandidate {
+    node: Entity,
+    priority: i32,
+    started: ClockSeconds,
+    /// The sample's effective gain.
+    gain: f32,
+    distance: f32,
+}
 
 fn rank_nodes<T: Component>(
     q: Query<
-        (Entity, &SamplerNode, &FirewheelNode, &PoolLabelContainer),
+        (
+            Entity,
+            &SamplerNode,
+            &FirewheelNode,
+            &PoolLabelContainer,
+            Option<&ActiveSample>,
+            Has<FadingOut>,
+            &VoiceFade,
+        ),
         (With<SamplePoolNode>, With<T>),
     >,
-    mut rank: Query<(&mut NodeRank, &PoolLabelContainer), With<T>>,
+    samples: Query<(
+        &PlaybackState,
+        &PlaybackSettings,
+        Option<&SamplePriority>,
+        Option<&GlobalTransform>,
+        Option<&SpatialBasicNode>,
+    )>,
+    fades: Query<&FadeNode>,
+    listeners: Query<&GlobalTransform, Or<(With<SpatialListener2D>, With<SpatialListener3D>)>>,
+    mut rank: Query<(&mut NodeRank, Option<&StealPolicy>, &PoolLabelContainer), With<T>>,
     mut context: ResMut<AudioContext>,
 ) {
-    for (mut rank, label) in rank.iter_mut() {
-        rank.0.clear();
+    let mut candidates = Vec::new();
+
+    for (mut rank, policy, label) in rank.iter_mut() {
+        let policy = policy.copied().unwrap_or_default();
+        rank.idle.clear();
+        rank.stealable.clear();
+        candidates.clear();
 
         context.with(|c| {
-            for (e, params, node, node_label) in q.iter() {
-                if node_label.label != label.label {
+            let now = c.clock_now();
+
+            for (e, params, node, node_label, active, fading, voice_fade) in q.iter() {
+                // Fading samplers are released once the fade completes.
+                if node_label.label != label.label || fading {
                     continue;
                 }
 
-                let Some(state) = c.node_state::<SamplerState>(node.0) else {
+                let Some(active) = active else {
+                    let Some(state) = c.node_state::<SamplerState>(node.0) else {
+                        continue;
+                    };
+
+                    rank.idle.push((e, state.worker_score(params)));
+                    continue;
+                };
+
+                let Ok((state, settings, priority, transform, spatial)) =
+                    samples.get(active.sample_entity)
+                else {
                     continue;
                 };
 
-                let score = state.worker_score(params);
+                // Paused samples should never be stolen.
+                if *state == PlaybackState::Paused {
+                    continue;
+                }
 
-                rank.0.push((e, score));
+                let distance = transform
+                    .and_then(|transform| {
+                        listeners
+                            .iter()
+                            .map(|listener| {
+                                listener.translation().distance(transform.translation())
+                            })
+                            .min_by(f32::total_cmp)
+                    })
+                    .unwrap_or_default();
+
+                let fade = fades
+                    .get(voice_fade.0)
+                    .map(|fade| fade.gain.value_at(now))
+                    .unwrap_or(1.0);
+                let spatial = spatial
+                    .map(|spatial| {
+                        let values = spatial.compute_values(0.0);
+                        values.gain_l.max(values.gain_r)
+                    })
+                    .unwrap_or(1.0);
+
+                candidates.push(StealCandidate {
+                    node: e,
+                    priority: priority.map(|p| p.0).unwrap_or_default(),
+                    started: active.started,
+                    gain: settings.volume.amp() * fade * spatial,
+                    distance,
+                });
             }
         });
 
-        rank.0
+        rank.idle
             .sort_unstable_by_key(|pair| std::cmp::Reverse(pair.1));
+
+        match policy {
+            StealPolicy::Oldest => {
+                candidates.sort_unstable_by(|a, b| a.started.0.total_cmp(&b.started.0))
+            }
+            StealPolicy::Quietest => candidates.sort_unstable_by(|a, b| a.gain.total_cmp(&b.gain)),
+            StealPolicy::LowestPriority => candidates.sort_unstable_by(|a, b| {
+                a.priority
+                    .cmp(&b.priority)
+                    .then(a.started.0.total_cmp(&b.started.0))
+            }),
+            StealPolicy::FurthestFromListener => {
+                candidates.sort_unstable_by(|a, b| b.distance.total_cmp(&a.distance))
+            }
+            StealPolicy::RejectNew => candidates.clear(),
+        }
+
+        rank.stealable
+            .extend(candidates.iter().map(|c| (c.node, c.priority)));
     }
 }
 
-#[derive(Component, Clone, Copy)]
+#[derive(Component, Clone)]
 struct ActiveSample {
     sample_entity: Entity,
+    handle: Handle<Sample>,
+    /// The frame playback started from.
+    ///
+    /// With a loop region, this is a virtual frame within the region's layout.
+    start_frame: u64,
+    /// The length of the full sample in frames.
+    len_frames: u64,
+    looping: bool,
+    /// Present when playing through a loop region.
+    region: Option<LoopMap>,
+    /// Present for variable-speed playback.
+    speed: Option<Arc<SpeedControl>>,
+    /// The time this sample was assigned to the sampler.
+    started: ClockSeconds,
+    /// The most recently observed playhead, used to detect loops.
+    last_frames: u64,
+    loops: u64,
+    /// Retained so the sample can fade out after it's despawned.
+    fade_out: Option<Fade>,
+}
+
+/// Everything needed to begin playing a sample on a sampler node.
+struct SampleStart<'a> {
+    sample_entity: Entity,
+    player: &'a SamplePlayer,
+    asset: &'a Sample,
+    settings: &'a PlaybackSettings,
+    state: PlaybackState,
+    start_frame: u64,
+    /// Providing a speed plays the sample through a [`ResampledSample`].
+    speed: Option<f32>,
+    started: ClockSeconds,
+    sample_rate: NonZeroU32,
+}
+
+/// Assign a sample to a sampler node.
+fn start_sample(
+    start: SampleStart,
+    params: &mut SamplerNode,
+    sampler_state: &SamplerState,
+    events: &mut Events,
+) -> ActiveSample {
+    let SampleStart {
+        sample_entity,
+        player,
+        asset,
+        settings,
+        state,
+        start_frame,
+        speed,
+        started,
+        sample_rate,
+    } = start;
+
+    let looping = !matches!(settings.repeat_mode, RepeatMode::PlayOnce);
+    let len_frames = asset.len_frames();
+    let start_frame = if looping {
+        start_frame % len_frames.max(1)
+    } else {
+        start_frame.min(len_frames)
+    };
+
+    let region = settings
+        .loop_region
+        .or(asset.loop_region())
+        .and_then(|region| {
+            LoopMap::new(
+                region.start.as_frames(sample_rate),
+                region.end.as_frames(sample_rate),
+                len_frames,
+                settings.repeat_mode,
+            )
+        });
+
+    // Loop regions are played through a virtual layout of the sample.
+    let start_frame = match &region {
+        Some(region) => region.to_virtual(start_frame),
+        None => start_frame,
+    };
+
+    // Streamed samples can only be read sequentially.
+    let speed = speed.filter(|_| {
+        if asset.is_streamed() {
+            warn_once!(
+                "`PlaybackSpeed` and Doppler shifts are not supported for streamed samples; playing {:?} and other streams at normal speed",
+                player.0.path(),
+            );
+        }
+
+        !asset.is_streamed()
+    });
+
+    let speed = match speed {
+        Some(speed) => Some(set_resampled(
+            params,
+            asset,
+            settings,
+            region,
+            start_frame,
+            speed,
+        )),
+        None => {
+            match region {
+                Some(region) => params.set_sample(
+                    RegionSample::new(asset.get(), region, start_frame),
+                    settings.volume,
+                    RepeatMode::PlayOnce,
+                ),
+                None => params.set_sample(
+                    asset.get_from(start_frame, looping),
+                    settings.volume,
+                    settings.repeat_mode,
+                ),
+            }
+
+            None
+        }
+    };
+
+    let event = sampler_state.sync_params_event(params, true);
+    events.push(event);
+
+    if state == PlaybackState::Paused {
+        events.push(NodeEventType::SequenceCommand(SequenceCommand::Pause));
+    }
+
+    ActiveSample {
+        sample_entity,
+        handle: player.0.clone(),
+        start_frame,
+        len_frames,
+        looping,
+        region,
+        speed,
+        started,
+        last_frames: region.map_or(start_frame, |region| region.map(start_frame)),
+        loops: 0,
+        fade_out: settings.fade_out,
+    }
+}
+
+/// Play `asset` through a [`ResampledSample`] from `start_frame`,
+/// which may count previous repetitions.
+fn set_resampled(
+    params: &mut SamplerNode,
+    asset: &Sample,
+    settings: &PlaybackSettings,
+    region: Option<LoopMap>,
+    start_frame: u64,
+    speed: f32,
+) -> Arc<SpeedControl> {
+    let (inner, repeat_mode) = match region {
+        Some(region) => (
+            RegionSample::new(asset.get(), region, 0),
+            RepeatMode::PlayOnce,
+        ),
+        None => (asset.get(), settings.repeat_mode),
+    };
+
+    let (resource, control) = ResampledSample::new(inner, start_frame, speed, repeat_mode);
+    params.set_sample(resource, settings.volume, RepeatMode::PlayOnce);
+
+    control
 }
 
 /// Automatically remove or despawn sample players when their
@@ -297,25 +778,37 @@ fn remove_finished(
             &FirewheelNode,
             &ActiveSample,
             &PoolRoot,
+            &PoolLabelContainer,
         ),
         With<SamplerNode>,
     >,
     samples: Query<&PlaybackSettings>,
+    states: Query<Ref<PlaybackState>>,
     roots: Query<&SamplePoolTypes>,
     mut commands: Commands,
     mut context: ResMut<AudioContext>,
 ) {
     context.with(|context| {
-        for (entity, effects_chain, node, active, pool_root) in nodes.iter() {
+        for (entity, effects_chain, node, active, pool_root, label) in nodes.iter() {
             let Some(state) = context.node_state::<SamplerState>(node.0) else {
                 continue;
             };
 
+            // Paused samples aren't finished, and a recently changed
+            // state may not have reached the audio thread yet.
+            if states
+                .get(active.sample_entity)
+                .is_ok_and(|s| *s == PlaybackState::Paused || s.is_changed())
+            {
+                continue;
+            }
+
             let state = state.playback_state();
 
-            // TODO: this will remove samples when paused
             if !state.is_playing() {
-                commands.entity(entity).remove::<ActiveSample>();
+                commands
+                    .entity(entity)
+                    .remove::<(ActiveSample, FadingOut)>();
 
                 for effect in effects_chain.0.iter() {
                     commands.entity(*effect).remove::<ParamFollower>();
@@ -325,31 +818,60 @@ fn remove_finished(
                     continue;
                 };
 
-                match settings.on_complete {
-                    OnComplete::Preserve => {}
-                    OnComplete::Remove => {
-                        let Ok(root) = roots.get(pool_root.0) else {
-                            continue;
-                        };
-
-                        let mut entity_commands = commands.entity(active.sample_entity);
-                        root.remove_nodes(&mut entity_commands);
-                        entity_commands.remove_with_requires::<(
-                            SamplePoolTypes,
-                            SamplePlayer,
-                            PoolLabelContainer,
-                            DynamicPoolRegistry,
-                        )>();
-                    }
-                    OnComplete::Despawn => {
-                        commands.entity(active.sample_entity).despawn_recursive();
-                    }
-                }
+                commands.trigger_targets(
+                    PlaybackCompleted {
+                        sample: active.sample_entity,
+                        pool: label.label,
+                        handle: active.handle.clone(),
+                    },
+                    active.sample_entity,
+                );
+
+                let Ok(root) = roots.get(pool_root.0) else {
+                    continue;
+                };
+
+                complete_sample(
+                    active.sample_entity,
+                    settings.on_complete,
+                    root,
+                    &mut commands,
+                );
             }
         }
     });
 }
 
+/// Apply a sample's [`OnComplete`] behavior.
+fn complete_sample(
+    sample: Entity,
+    on_complete: OnComplete,
+    pool_types: &SamplePoolTypes,
+    commands: &mut Commands,
+) {
+    match on_complete {
+        OnComplete::Preserve => {}
+        OnComplete::Remove => {
+            let mut entity_commands = commands.entity(sample);
+            pool_types.remove_nodes(&mut entity_commands);
+            entity_commands.remove_with_requires::<(
+                SamplePoolTypes,
+                SamplePlayer,
+                PoolLabelContainer,
+                DynamicPoolRegistry,
+            )>();
+        }
+        OnComplete::Despawn => {
+            commands.entity(sample).despawn_recursive();
+        }
+    }
+}
+
+/// Records when a loaded sample began waiting for a sampler.
+#[derive(Component, Clone, Copy)]
+#[component(storage = "SparseSet")]
+struct QueuedAt(ClockSeconds);
+
 /// Scan through the set of pending sample players
 /// and assign work to the most appropriate sampler node.
 fn assign_work<T: Component + Clone>(
@@ -360,14 +882,22 @@ fn assign_work<T: Component + Clone>(
             &mut Events,
             &EffectsChain,
             &FirewheelNode,
+            Option<&ActiveSample>,
+            &VoiceFade,
         ),
         (With<SamplePoolNode>, With<T>),
     >,
+    mut fades: Query<&mut FadeNode>,
     queued_samples: Query<
         (
             Entity,
             &SamplePlayer,
             &PlaybackSettings,
+            &PlaybackState,
+            Option<&Seek>,
+            Option<&PlaybackSpeed>,
+            Option<&SamplePriority>,
+            Option<&QueuedAt>,
             &PoolLabelContainer,
         ),
         (With<QueuedSample>, With<T>),
@@ -380,45 +910,119 @@ fn assign_work<T: Component + Clone>(
         &PoolLabelContainer,
         &PoolRange,
         &mut SamplerNodes,
+        Option<&QueueTimeout>,
     )>,
+    stolen: Query<&PlaybackSettings>,
     assets: Res<Assets<Sample>>,
+    server: Res<AssetServer>,
     mut commands: Commands,
     mut context: ResMut<AudioContext>,
 ) {
     context.with(|context| {
-        for (sample, player, settings, label) in queued_samples.iter() {
-            let Some(asset) = assets.get(&player.0) else {
+        let Some(sample_rate) = context.stream_info().map(|info| info.sample_rate) else {
+            return;
+        };
+        let now = context.clock_now();
+
+        for (sample, player, settings, state, seek, speed, priority, queued_at, label) in
+            queued_samples.iter()
+        {
+            let Some((
+                pool_entity,
+                mut rank,
+                defaults,
+                pool_label,
+                _,
+                pool_range,
+                mut pool_nodes,
+                timeout,
+            )) = pools.iter_mut().find(|pool| pool.4.label == label.label)
+            else {
                 continue;
             };
 
-            let Some((pool_entity, mut rank, defaults, pool_label, _, pool_range, mut pool_nodes)) =
-                pools.iter_mut().find(|pool| pool.4.label == label.label)
-            else {
+            // Stopped samples never start, so they complete immediately.
+            if *state == PlaybackState::Stopped {
+                commands
+                    .entity(sample)
+                    .remove::<(QueuedSample, QueuedAt, Seek)>();
+                complete_sample(sample, settings.on_complete, defaults, &mut commands);
+                continue;
+            }
+
+            let Some(asset) = assets.get(&player.0) else {
+                if matches!(server.load_state(player.0.id()), LoadState::Failed(_)) {
+                    commands.trigger_targets(
+                        SampleLoadFailed {
+                            sample,
+                            pool: label.label,
+                            handle: player.0.clone(),
+                        },
+                        sample,
+                    );
+                    commands.entity(sample).remove::<(QueuedSample, Seek)>();
+                    complete_sample(sample, settings.on_complete, defaults, &mut commands);
+                }
+
                 continue;
             };
 
-            // get the best candidate
-            let Some((node_entity, _)) = rank.0.first() else {
+            let queued_at = match queued_at {
+                Some(queued_at) => queued_at.0,
+                None => {
+                    commands.entity(sample).insert(QueuedAt(now));
+                    now
+                }
+            };
+
+            // get the best candidate, preferring idle samplers
+            let node_entity = if !rank.idle.is_empty() {
+                Some(rank.idle.remove(0).0)
+            } else if pool_nodes.len() < *pool_range.0.end() {
                 // Try to grow the pool if it's reached max capacity.
                 // TODO: find a decent way to do this eagerly.
                 let current_size = pool_nodes.len();
-                let max_size = *pool_range.0.end();
+                let new_size = (current_size * 2).clamp(1, *pool_range.0.end());
+
+                for _ in 0..new_size - current_size {
+                    let new_sampler =
+                        spawn_chain(pool_entity, defaults, pool_label.clone(), &mut commands);
+                    pool_nodes.0.push(new_sampler);
+                }
 
-                if current_size < max_size {
-                    let new_size = (current_size * 2).min(max_size);
+                None
+            } else {
+                // Samples can only steal from equal or lower priorities.
+                let priority = priority.map(|p| p.0).unwrap_or_default();
 
-                    for _ in 0..new_size - current_size {
-                        let new_sampler =
-                            spawn_chain(pool_entity, defaults, pool_label.clone(), &mut commands);
-                        pool_nodes.0.push(new_sampler);
+                rank.stealable
+                    .iter()
+                    .position(|(_, victim)| *victim <= priority)
+                    .map(|index| rank.stealable.remove(index).0)
+            };
+
+            let Some(node_entity) = node_entity else {
+                if let Some(QueueTimeout::After(duration)) = timeout {
+                    if now.0 - queued_at.0 >= duration.as_secs_f64() {
+                        commands
+                            .entity(sample)
+                            .remove::<(QueuedSample, QueuedAt, Seek)>();
+                        complete_sample(sample, settings.on_complete, defaults, &mut commands);
                     }
                 }
 
                 continue;
             };
 
-            let Ok((node_entity, mut params, mut events, effects_chain, sampler_id)) =
-                nodes.get_mut(*node_entity)
+            let Ok((
+                node_entity,
+                mut params,
+                mut events,
+                effects_chain,
+                sampler_id,
+                victim,
+                voice_fade,
+            )) = nodes.get_mut(node_entity)
             else {
                 continue;
             };
@@ -427,9 +1031,70 @@ fn assign_work<T: Component + Clone>(
                 continue;
             };
 
-            params.set_sample(asset.get(), settings.volume, settings.repeat_mode);
-            let event = sampler_state.sync_params_event(&params, true);
-            events.push(event);
+            let start = seek.map(|seek| seek.0).unwrap_or(settings.start);
+            let active = start_sample(
+                SampleStart {
+                    sample_entity: sample,
+                    player,
+                    asset,
+                    settings,
+                    state: *state,
+                    start_frame: start.as_frames(sample_rate),
+                    speed: speed.map(|speed| speed.speed.value_at(now)),
+                    started: now,
+                    sample_rate,
+                },
+                &mut params,
+                sampler_state,
+                &mut events,
+            );
+
+            if let Ok(mut fade) = fades.get_mut(voice_fade.0) {
+                match settings.fade_in {
+                    Some(fade_in) => {
+                        fade.gain.set(0.);
+                        // This can't fail since the timeline was just cleared.
+                        let _ = fade.gain.push_curve(
+                            1.,
+                            now,
+                            now + ClockSeconds(fade_in.duration.as_secs_f64()),
+                            fade_in.ease,
+                        );
+                    }
+                    None => fade.gain.set(1.),
+                }
+            }
+
+            commands.trigger_targets(
+                PlaybackStarted {
+                    sample,
+                    pool: label.label,
+                    handle: player.0.clone(),
+                },
+                sample,
+            );
+
+            // The interrupted sample is treated as though it completed.
+            if let Some(victim) = victim {
+                commands.trigger_targets(
+                    PlaybackStolen {
+                        sample: victim.sample_entity,
+                        pool: label.label,
+                        handle: victim.handle.clone(),
+                        stolen_by: sample,
+                    },
+                    victim.sample_entity,
+                );
+
+                if let Ok(victim_settings) = stolen.get(victim.sample_entity) {
+                    complete_sample(
+                        victim.sample_entity,
+                        victim_settings.on_complete,
+                        defaults,
+                        &mut commands,
+                    );
+                }
+            }
 
             // redirect all parameters to follow the sample source
             for effect in effects_chain.0.iter() {
@@ -441,92 +1106,432 @@ fn assign_work<T: Component + Clone>(
                 ty.insert_default(&mut commands.entity(sample));
             }
 
-            rank.0.remove(0);
-            commands.entity(sample).remove::<QueuedSample>();
-            commands.entity(node_entity).insert(ActiveSample {
-                sample_entity: sample,
-            });
+            commands
+                .entity(sample)
+                .remove::<(QueuedSample, QueuedAt, Seek)>();
+            commands.entity(node_entity).insert(active);
         }
     });
 }
 
-// Stop playback if the source entity no longer exists.
-fn monitor_active(
-    mut nodes: Query<(Entity, &ActiveSample, &mut Events, &EffectsChain)>,
-    samples: Query<&SamplePlayer>,
+/// Forward [`PlaybackState`] changes to each sample's sampler node.
+///
+/// Stopped samples with a [`PlaybackSettings::fade_out`] are
+/// stopped once the fade completes.
+fn update_playback_state(
+    mut nodes: Query<(Entity, &ActiveSample, &mut Events, &VoiceFade), With<SamplerNode>>,
+    samples: Query<Ref<PlaybackState>>,
+    mut fades: Query<&mut FadeNode>,
     mut commands: Commands,
+    mut context: ResMut<AudioContext>,
 ) {
-    for (node_entity, active, mut events, effects_chain) in nodes.iter_mut() {
-        if samples.get(active.sample_entity).is_err() {
-            events.push(NodeEventType::SequenceCommand(SequenceCommand::Stop));
+    let mut now = None;
 
-            commands.entity(node_entity).remove::<ActiveSample>();
+    for (node, active, mut events, voice_fade) in nodes.iter_mut() {
+        let Ok(state) = samples.get(active.sample_entity) else {
+            continue;
+        };
 
-            for effect in effects_chain.0.iter() {
-                commands.entity(*effect).remove::<ParamFollower>();
-            }
+        if !state.is_changed() {
+            continue;
         }
-    }
-}
 
-/// Assign the default pool label to a sample player that has no label.
-fn assign_default(
-    samples: Query<
-        Entity,
-        (
-            With<SamplePlayer>,
-            Without<PoolLabelContainer>,
-            Without<DynamicPoolRegistry>,
-        ),
-    >,
-    mut commands: Commands,
-) {
-    for sample in samples.iter() {
-        commands.entity(sample).insert(DefaultPool);
+        let command = match *state {
+            PlaybackState::Playing => SequenceCommand::Resume,
+            PlaybackState::Paused => SequenceCommand::Pause,
+            PlaybackState::Stopped => {
+                if let (Some(fade_out), Ok(mut fade)) =
+                    (active.fade_out, fades.get_mut(voice_fade.0))
+                {
+                    let now = *now.get_or_insert_with(|| context.now());
+                    let end = begin_fade_out(&mut fade, fade_out, now);
+                    commands.entity(node).insert(FadingOut(end));
+
+                    continue;
+                }
+
+                SequenceCommand::Stop
+            }
+        };
+
+        events.push(NodeEventType::SequenceCommand(command));
     }
 }
 
-/// A pool despawner command.
-///
-/// Despawn a sample pool, cleaning up its resources
-/// in the ECS and audio graph.
-///
-/// Despawning the terminal volume node recursively
-/// will produce the same effect.
-///
-/// This can be used directly or via the [`PoolCommands`] trait.
+/// Forward [`PlaybackSpeed`] and any Doppler shift to variable-speed
+/// samples, stopping them once they've played to completion.
 ///
-/// ```
-/// # use bevy_ecs::prelude::*;
-/// # use bevy_seedling::prelude::*;
-/// #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
-/// struct MyLabel;
+/// This runs for every variable-speed sample, even those whose
+/// [`PlaybackSpeed`] has since been removed, since the sampler
+/// can't detect their end on its own. Samples that slow down
+/// past their reported length are restarted where they left off.
 ///
-/// fn system(mut commands: Commands) {
-///     commands.queue(PoolDespawn::new(MyLabel));
-/// }
-/// ```
-#[derive(Debug)]
-pub struct PoolDespawn<T>(T);
+/// Samples that gain a [`PlaybackSpeed`] during playback are
+/// restarted at their [`PlaybackPosition`] with a [`Seek`].
+fn update_playback_speed(
+    mut nodes: Query<(
+        &mut ActiveSample,
+        &mut SamplerNode,
+        &mut Events,
+        &FirewheelNode,
+    )>,
+    samples: Query<(
+        Option<Ref<PlaybackSpeed>>,
+        Option<&DopplerFactor>,
+        &SamplePlayer,
+        &PlaybackSettings,
+        &PlaybackState,
+        &PlaybackPosition,
+    )>,
+    assets: Res<Assets<Sample>>,
+    mut commands: Commands,
+    mut context: ResMut<AudioContext>,
+) {
+    context.with(|context| {
+        let now = context.clock_now();
 
-impl<T: PoolLabel + Component> PoolDespawn<T> {
-    /// Construct a new [`PoolDespawn`] with the provided label.
-    pub fn new(label: T) -> Self {
-        Self(label)
-    }
-}
+        for (mut active, mut params, mut events, node) in nodes.iter_mut() {
+            let sample = samples.get(active.sample_entity);
 
-impl<T: PoolLabel + Component> Command for PoolDespawn<T> {
-    fn apply(self, world: &mut World) {
-        let mut roots =
-            world.query_filtered::<(Entity, &PoolLabelContainer), (With<T>, With<SamplePoolNode>, With<VolumeNode>)>();
+            let Some(control) = active.speed.clone() else {
+                if let Ok((Some(speed), .., position)) = sample {
+                    if speed.is_added() {
+                        commands
+                            .entity(active.sample_entity)
+                            .insert_if_new(Seek(Playhead::Frames(position.frames)));
+                    }
+                }
 
-        let roots: Vec<_> = roots
-            .iter(world)
-            .map(|(root, label)| (root, label.clone()))
-            .collect();
+                continue;
+            };
 
-        let mut commands = world.commands();
+            if control.is_finished() {
+                events.push(NodeEventType::SequenceCommand(SequenceCommand::Stop));
+                continue;
+            }
+
+            let Ok((speed, doppler, player, settings, state, _)) = sample else {
+                continue;
+            };
+
+            let speed = speed.map(|s| s.speed.value_at(now)).unwrap_or(1.0);
+            let doppler = doppler.map(|d| d.0).unwrap_or(1.0);
+            control.set_speed(speed * doppler);
+
+            if !control.needs_restart() {
+                continue;
+            }
+
+            let (Some(asset), Some(sampler_state)) = (
+                assets.get(&player.0),
+                context.node_state::<SamplerState>(node.0),
+            ) else {
+                continue;
+            };
+
+            let region = active.region;
+            active.speed = Some(set_resampled(
+                &mut params,
+                asset,
+                settings,
+                region,
+                control.position(),
+                speed * doppler,
+            ));
+
+            events.push(sampler_state.sync_params_event(&params, true));
+
+            if *state == PlaybackState::Paused {
+                events.push(NodeEventType::SequenceCommand(SequenceCommand::Pause));
+            }
+        }
+    });
+}
+
+/// Restart active samples from their [`Seek`] position.
+///
+/// Queued samples apply their [`Seek`] when they're assigned.
+fn apply_seek(
+    mut nodes: Query<(
+        &mut ActiveSample,
+        &mut SamplerNode,
+        &mut Events,
+        &FirewheelNode,
+    )>,
+    samples: Query<(
+        &Seek,
+        &SamplePlayer,
+        &PlaybackSettings,
+        &PlaybackState,
+        Option<&PlaybackSpeed>,
+    )>,
+    assets: Res<Assets<Sample>>,
+    mut commands: Commands,
+    mut context: ResMut<AudioContext>,
+) {
+    if samples.is_empty() {
+        return;
+    }
+
+    context.with(|context| {
+        let Some(sample_rate) = context.stream_info().map(|info| info.sample_rate) else {
+            return;
+        };
+        let now = context.clock_now();
+
+        for (mut active, mut params, mut events, node) in nodes.iter_mut() {
+            let Ok((seek, player, settings, state, speed)) = samples.get(active.sample_entity)
+            else {
+                continue;
+            };
+
+            let Some(asset) = assets.get(&player.0) else {
+                continue;
+            };
+
+            let Some(sampler_state) = context.node_state::<SamplerState>(node.0) else {
+                continue;
+            };
+
+            *active = start_sample(
+                SampleStart {
+                    sample_entity: active.sample_entity,
+                    player,
+                    asset,
+                    settings,
+                    state: *state,
+                    start_frame: seek.0.as_frames(sample_rate),
+                    speed: speed.map(|speed| speed.speed.value_at(now)),
+                    started: active.started,
+                    sample_rate,
+                },
+                &mut params,
+                sampler_state,
+                &mut events,
+            );
+
+            commands.entity(active.sample_entity).remove::<Seek>();
+        }
+    });
+}
+
+/// Synchronize each active sample's [`PlaybackPosition`] with its sampler.
+fn update_playback_position(
+    mut nodes: Query<(&mut ActiveSample, &FirewheelNode, &PoolLabelContainer)>,
+    mut samples: Query<&mut PlaybackPosition>,
+    mut commands: Commands,
+    mut context: ResMut<AudioContext>,
+) {
+    context.with(|context| {
+        let Some(sample_rate) = context.stream_info().map(|info| info.sample_rate) else {
+            return;
+        };
+
+        for (mut active, node, label) in nodes.iter_mut() {
+            let Ok(mut position) = samples.get_mut(active.sample_entity) else {
+                continue;
+            };
+
+            let Some(state) = context.node_state::<SamplerState>(node.0) else {
+                continue;
+            };
+
+            let frames = if let Some(region) = &active.region {
+                let virtual_frames = match &active.speed {
+                    Some(speed) => speed.playhead(),
+                    None => active.start_frame + state.playhead_frames(),
+                };
+
+                region.map(virtual_frames)
+            } else if let Some(speed) = &active.speed {
+                speed.playhead()
+            } else if active.looping {
+                (active.start_frame + state.playhead_frames()) % active.len_frames.max(1)
+            } else {
+                (active.start_frame + state.playhead_frames()).min(active.len_frames)
+            };
+
+            if active.looping && frames < active.last_frames {
+                active.loops += 1;
+
+                commands.trigger_targets(
+                    LoopWrapped {
+                        sample: active.sample_entity,
+                        pool: label.label,
+                        handle: active.handle.clone(),
+                        loops: active.loops,
+                    },
+                    active.sample_entity,
+                );
+            }
+
+            if active.last_frames != frames {
+                active.last_frames = frames;
+            }
+
+            let new_position = PlaybackPosition {
+                frames,
+                seconds: frames as f64 / sample_rate.get() as f64,
+            };
+
+            position.set_if_neq(new_position);
+        }
+    });
+}
+
+// Stop playback if the source entity no longer exists,
+// fading out first if requested.
+fn monitor_active(
+    mut nodes: Query<
+        (
+            Entity,
+            &ActiveSample,
+            &mut Events,
+            &EffectsChain,
+            &PoolLabelContainer,
+            &VoiceFade,
+        ),
+        Without<FadingOut>,
+    >,
+    samples: Query<&SamplePlayer>,
+    mut fades: Query<&mut FadeNode>,
+    mut commands: Commands,
+    mut context: ResMut<AudioContext>,
+) {
+    let mut now = None;
+
+    for (node_entity, active, mut events, effects_chain, label, voice_fade) in nodes.iter_mut() {
+        if samples.get(active.sample_entity).is_err() {
+            let event = PlaybackCompleted {
+                sample: active.sample_entity,
+                pool: label.label,
+                handle: active.handle.clone(),
+            };
+
+            if commands.get_entity(active.sample_entity).is_some() {
+                commands.trigger_targets(event, active.sample_entity);
+            } else {
+                commands.trigger(event);
+            }
+
+            if let (Some(fade_out), Ok(mut fade)) = (active.fade_out, fades.get_mut(voice_fade.0)) {
+                let now = *now.get_or_insert_with(|| context.now());
+                let end = begin_fade_out(&mut fade, fade_out, now);
+                commands.entity(node_entity).insert(FadingOut(end));
+
+                continue;
+            }
+
+            events.push(NodeEventType::SequenceCommand(SequenceCommand::Stop));
+
+            commands.entity(node_entity).remove::<ActiveSample>();
+
+            for effect in effects_chain.0.iter() {
+                commands.entity(*effect).remove::<ParamFollower>();
+            }
+        }
+    }
+}
+
+/// Stop samplers whose fade-out has completed.
+fn finish_fades(
+    mut nodes: Query<(
+        Entity,
+        &ActiveSample,
+        &FadingOut,
+        &mut Events,
+        &EffectsChain,
+    )>,
+    samples: Query<&SamplePlayer>,
+    mut commands: Commands,
+    mut context: ResMut<AudioContext>,
+) {
+    if nodes.is_empty() {
+        return;
+    }
+
+    let now = context.now();
+
+    for (node_entity, active, fading, mut events, effects_chain) in nodes.iter_mut() {
+        if now < fading.0 {
+            continue;
+        }
+
+        events.push(NodeEventType::SequenceCommand(SequenceCommand::Stop));
+
+        // Samples that still exist are completed in `remove_finished`.
+        if samples.get(active.sample_entity).is_ok() {
+            continue;
+        }
+
+        commands
+            .entity(node_entity)
+            .remove::<(ActiveSample, FadingOut)>();
+
+        for effect in effects_chain.0.iter() {
+            commands.entity(*effect).remove::<ParamFollower>();
+        }
+    }
+}
+
+/// Assign the default pool label to a sample player that has no label.
+fn assign_default(
+    samples: Query<
+        Entity,
+        (
+            With<SamplePlayer>,
+            Without<PoolLabelContainer>,
+            Without<DynamicPoolRegistry>,
+        ),
+    >,
+    mut commands: Commands,
+) {
+    for sample in samples.iter() {
+        commands.entity(sample).insert(DefaultPool);
+    }
+}
+
+/// A pool despawner command.
+///
+/// Despawn a sample pool, cleaning up its resources
+/// in the ECS and audio graph.
+///
+/// Despawning the terminal volume node recursively
+/// will produce the same effect.
+///
+/// This can be used directly or via the [`PoolCommands`] trait.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+/// struct MyLabel;
+///
+/// fn system(mut commands: Commands) {
+///     commands.queue(PoolDespawn::new(MyLabel));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PoolDespawn<T>(T);
+
+impl<T: PoolLabel + Component> PoolDespawn<T> {
+    /// Construct a new [`PoolDespawn`] with the provided label.
+    pub fn new(label: T) -> Self {
+        Self(label)
+    }
+}
+
+impl<T: PoolLabel + Component> Command for PoolDespawn<T> {
+    fn apply(self, world: &mut World) {
+        let mut roots =
+            world.query_filtered::<(Entity, &PoolLabelContainer), (With<T>, With<SamplePoolNode>, With<VolumeNode>)>();
+
+        let roots: Vec<_> = roots
+            .iter(world)
+            .map(|(root, label)| (root, label.clone()))
+            .collect();
+
+        let mut commands = world.commands();
 
         let interned = self.0.intern();
         for (root, label) in roots {
@@ -556,9 +1561,15 @@ impl PoolCommands for Commands<'_, '_> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{pool::NodeRank, prelude::*, profiling::ProfilingBackend};
+    use crate::{
+        offline::{OfflineBackend, OfflineClock, OfflineConfig, OfflineMode, OfflinePlugin},
+        pool::NodeRank,
+        prelude::*,
+        profiling::ProfilingBackend,
+    };
     use bevy::prelude::*;
     use bevy_ecs::system::RunSystemOnce;
+    use std::time::Duration;
 
     fn prepare_app<F: IntoSystem<(), (), M>, M>(startup: F) -> App {
         let mut app = App::new();
@@ -568,6 +1579,7 @@ mod test {
             AssetPlugin::default(),
             SeedlingPlugin::<ProfilingBackend> {
                 default_pool_size: None,
+                main_bus_limiter: None,
                 ..SeedlingPlugin::<ProfilingBackend>::new()
             },
             HierarchyPlugin,
@@ -581,11 +1593,54 @@ mod test {
         app
     }
 
+    /// Prepare an app that renders a fixed block of audio per update.
+    fn prepare_lockstep_app<F: IntoSystem<(), (), M>, M>(startup: F) -> App {
+        let clock = OfflineClock::new(512);
+        let mut app = App::new();
+
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            SeedlingPlugin::<OfflineBackend> {
+                stream_config: OfflineConfig {
+                    mode: OfflineMode::Lockstep(clock.clone()),
+                    ..Default::default()
+                },
+                default_pool_size: None,
+                main_bus_limiter: None,
+                ..SeedlingPlugin::<OfflineBackend>::new()
+            },
+            OfflinePlugin::new(clock),
+            HierarchyPlugin,
+        ))
+        .add_systems(Startup, startup);
+
+        app.finish();
+        app.cleanup();
+        app.update();
+
+        app
+    }
+
     fn run<F: IntoSystem<(), O, M>, O, M>(app: &mut App, system: F) -> O {
         let world = app.world_mut();
         world.run_system_once(system).unwrap()
     }
 
+    /// Update the app until `condition` holds.
+    ///
+    /// Returns `false` if it doesn't hold within a bounded
+    /// number of updates, so regressions fail rather than hang.
+    fn update_until(app: &mut App, mut condition: impl FnMut(&mut App) -> bool) -> bool {
+        (0..512).any(|_| {
+            app.update();
+            // Assets load on other threads, so give them a moment. With the
+            // lockstep backend, audio time only advances with each update.
+            std::thread::sleep(Duration::from_millis(1));
+            condition(app)
+        })
+    }
+
     #[test]
     fn test_despawn_static() {
         #[derive(PoolLabel, Clone, Debug, PartialEq, Eq, Hash)]
@@ -598,8 +1653,8 @@ mod test {
         });
 
         run(&mut app, |pool_nodes: Query<&FirewheelNode>| {
-            // 2 * 4 (sampler and low pass nodes) + 1 (pool volume) + 1 (global volume)
-            assert_eq!(pool_nodes.iter().count(), 10);
+            // 3 * 4 (sampler, fade, and low pass nodes) + 1 (pool volume) + 1 (global volume)
+            assert_eq!(pool_nodes.iter().count(), 14);
         });
 
         run(&mut app, |mut commands: Commands| {
@@ -623,8 +1678,8 @@ mod test {
         });
 
         run(&mut app, |pool_nodes: Query<&FirewheelNode>| {
-            // 2 * 4 (sampler and low pass nodes) + 1 (pool volume) + 1 (global volume)
-            assert_eq!(pool_nodes.iter().count(), 10);
+            // 3 * 4 (sampler, fade, and low pass nodes) + 1 (pool volume) + 1 (global volume)
+            assert_eq!(pool_nodes.iter().count(), 14);
         });
 
         run(
@@ -727,4 +1782,515 @@ mod test {
         assert_eq!(archetype.components().count(), 1);
         assert!(entity.contains::<EmptyComponent>());
     }
+
+    #[test]
+    fn test_pause_preserves_sampler() {
+        #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct PausePool;
+
+        let mut app = prepare_lockstep_app(|mut commands: Commands, server: Res<AssetServer>| {
+            Pool::new(PausePool, 1).spawn(&mut commands);
+
+            // This sample is short enough to finish within a few updates if not paused.
+            commands.spawn((
+                PausePool,
+                SamplePlayer::new(server.load("sine_440hz_1ms.wav")),
+                PlaybackState::Paused,
+                EmptyComponent,
+            ));
+        });
+
+        // Wait until the sample is assigned to the sampler.
+        let assigned = (0..64).any(|_| {
+            app.update();
+            run(&mut app, |q: Query<&ActiveSample>| q.iter().len()) > 0
+        });
+        assert!(assigned, "sample was never assigned");
+
+        // Each update renders 512 frames, well past the sample's 48.
+        for _ in 0..10 {
+            app.update();
+        }
+
+        // A paused sample should neither complete nor lose its sampler.
+        run(
+            &mut app,
+            |active: Query<&ActiveSample>,
+             player: Query<Entity, (With<SamplePlayer>, With<EmptyComponent>)>| {
+                let player = player.single();
+
+                assert_eq!(active.single().sample_entity, player);
+            },
+        );
+    }
+
+    #[test]
+    fn test_queued_stop_completes() {
+        #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct StopPool;
+
+        let mut app = prepare_lockstep_app(|mut commands: Commands, server: Res<AssetServer>| {
+            Pool::new(StopPool, 1).spawn(&mut commands);
+
+            commands.spawn((
+                StopPool,
+                SamplePlayer::new(server.load("sine_440hz_1ms.wav")),
+                PlaybackState::Stopped,
+            ));
+        });
+
+        // A sample spawned stopped should be despawned without playing.
+        let despawned = (0..64).any(|_| {
+            app.update();
+            run(&mut app, |q: Query<&SamplePlayer>| q.iter().len()) == 0
+        });
+        assert!(despawned, "stopped sample was never completed");
+
+        run(&mut app, |q: Query<&ActiveSample>| {
+            assert_eq!(q.iter().len(), 0);
+        });
+    }
+
+    #[test]
+    fn test_speed_removed_completes() {
+        #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct SpeedPool;
+
+        let mut app = prepare_lockstep_app(|mut commands: Commands, server: Res<AssetServer>| {
+            Pool::new(SpeedPool, 1).spawn(&mut commands);
+
+            commands.spawn((
+                SpeedPool,
+                SamplePlayer::new(server.load("sine_440hz_1ms.wav")),
+                PlaybackSpeed::new(0.5),
+                PlaybackState::Paused,
+            ));
+        });
+
+        let assigned = (0..64).any(|_| {
+            app.update();
+            run(&mut app, |q: Query<&ActiveSample>| q.iter().len()) > 0
+        });
+        assert!(assigned, "sample was never assigned");
+
+        run(
+            &mut app,
+            |mut commands: Commands, q: Query<Entity, With<SamplePlayer>>| {
+                commands
+                    .entity(q.single())
+                    .remove::<PlaybackSpeed>()
+                    .insert(PlaybackState::Playing);
+            },
+        );
+
+        // The variable-speed sample should still finish and release its sampler.
+        let despawned = (0..64).any(|_| {
+            app.update();
+            run(&mut app, |q: Query<&SamplePlayer>| q.iter().len()) == 0
+        });
+        assert!(despawned, "sample never completed after removing its speed");
+    }
+
+    #[test]
+    fn test_speed_added_restarts() {
+        #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct SpeedPool;
+
+        let mut app = prepare_lockstep_app(|mut commands: Commands, server: Res<AssetServer>| {
+            Pool::new(SpeedPool, 1).spawn(&mut commands);
+
+            commands.spawn((
+                SpeedPool,
+                SamplePlayer::new(server.load("caw.ogg")),
+                PlaybackSettings::PRESERVE.with_start(Playhead::Frames(1000)),
+            ));
+        });
+
+        let assigned = update_until(&mut app, |app| {
+            run(app, |q: Query<&ActiveSample>| q.iter().len()) > 0
+        });
+        assert!(assigned, "sample was never assigned");
+
+        app.update();
+        let before = run(&mut app, |q: Query<&PlaybackPosition>| q.single().frames());
+
+        run(
+            &mut app,
+            |mut commands: Commands, q: Query<Entity, With<SamplePlayer>>| {
+                commands.entity(q.single()).insert(PlaybackSpeed::new(0.5));
+            },
+        );
+
+        let resampled = update_until(&mut app, |app| {
+            run(app, |q: Query<&ActiveSample>| {
+                q.iter().any(|active| active.speed.is_some())
+            })
+        });
+        assert!(
+            resampled,
+            "sample never switched to variable-speed playback"
+        );
+
+        // The sample resumes from where it was rather than its start.
+        let after = run(&mut app, |q: Query<&PlaybackPosition>| q.single().frames());
+        assert!(after >= before, "restarted at {after}, before {before}");
+    }
+
+    #[test]
+    fn test_start_position() {
+        #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct StartPool;
+
+        let mut app = prepare_lockstep_app(|mut commands: Commands, server: Res<AssetServer>| {
+            Pool::new(StartPool, 1).spawn(&mut commands);
+
+            commands.spawn((
+                StartPool,
+                SamplePlayer::new(server.load("caw.ogg")),
+                PlaybackSettings::PRESERVE.with_start(Playhead::Frames(1000)),
+                PlaybackState::Paused,
+            ));
+        });
+
+        let assigned = update_until(&mut app, |app| {
+            run(app, |q: Query<&ActiveSample>| q.iter().len()) > 0
+        });
+        assert!(assigned, "sample was never assigned");
+
+        app.update();
+
+        run(&mut app, |q: Query<&PlaybackPosition>| {
+            assert!(q.single().frames() >= 1000);
+        });
+    }
+
+    #[test]
+    fn test_priority_timeout() {
+        #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct PriorityPool;
+
+        #[derive(Component)]
+        struct Dialogue;
+
+        #[derive(Component)]
+        struct Debris;
+
+        let mut app = prepare_lockstep_app(|mut commands: Commands, server: Res<AssetServer>| {
+            Pool::new(PriorityPool, 1)
+                .queue_timeout(QueueTimeout::After(Duration::ZERO))
+                .spawn(&mut commands);
+
+            commands.spawn((
+                PriorityPool,
+                SamplePlayer::new(server.load("caw.ogg")),
+                PlaybackSettings::LOOP,
+                SamplePriority(10),
+                Dialogue,
+            ));
+        });
+
+        let assigned = update_until(&mut app, |app| {
+            run(app, |q: Query<&ActiveSample>| q.iter().len()) > 0
+        });
+        assert!(assigned, "sample was never assigned");
+
+        run(
+            &mut app,
+            |mut commands: Commands, server: Res<AssetServer>| {
+                commands.spawn((
+                    PriorityPool,
+                    SamplePlayer::new(server.load("caw.ogg")),
+                    Debris,
+                ));
+            },
+        );
+
+        // The lower-priority sample can't steal the sampler,
+        // so it should be dropped once it loads.
+        let dropped = update_until(&mut app, |app| {
+            run(app, |q: Query<&Debris>| q.iter().len()) == 0
+        });
+        assert!(dropped, "lower-priority sample was never dropped");
+
+        run(
+            &mut app,
+            |active: Query<&ActiveSample>, dialogue: Query<Entity, With<Dialogue>>| {
+                assert_eq!(active.single().sample_entity, dialogue.single());
+            },
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_events() {
+        #[derive(Resource, Default)]
+        struct Log(Vec<&'static str>);
+
+        let mut app = prepare_lockstep_app(|mut commands: Commands, server: Res<AssetServer>| {
+            Pool::new(DefaultPool, 1).spawn(&mut commands);
+
+            commands.spawn((
+                SamplePlayer::new(server.load("sine_440hz_1ms.wav")),
+                EmptyComponent,
+            ));
+            commands.spawn(SamplePlayer::new(server.load("does_not_exist.wav")));
+        });
+
+        app.init_resource::<Log>()
+            .add_observer(|_: Trigger<PlaybackStarted>, mut log: ResMut<Log>| log.0.push("started"))
+            .add_observer(|_: Trigger<PlaybackCompleted>, mut log: ResMut<Log>| {
+                log.0.push("completed")
+            })
+            .add_observer(|_: Trigger<SampleLoadFailed>, mut log: ResMut<Log>| {
+                log.0.push("failed")
+            });
+
+        // Wait until both samples have been despawned.
+        let despawned = update_until(&mut app, |app| {
+            run(app, |q: Query<&SamplePlayer>| q.iter().len()) == 0
+        });
+        assert!(despawned, "samples were never despawned");
+
+        let mut log = app.world_mut().remove_resource::<Log>().unwrap().0;
+        log.sort_unstable();
+
+        assert_eq!(log, ["completed", "failed", "started"]);
+    }
+
+    #[test]
+    fn test_deferred_stop() {
+        #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct FadePool;
+
+        let mut app = prepare_lockstep_app(|mut commands: Commands, server: Res<AssetServer>| {
+            Pool::new(FadePool, 1).spawn(&mut commands);
+
+            commands.spawn((
+                FadePool,
+                SamplePlayer::new(server.load("caw.ogg")),
+                PlaybackSettings::LOOP.with_fade_out(Fade::new(Duration::from_millis(50))),
+                EmptyComponent,
+            ));
+        });
+
+        let assigned = update_until(&mut app, |app| {
+            run(app, |q: Query<&ActiveSample>| q.iter().len()) > 0
+        });
+        assert!(assigned, "sample was never assigned");
+
+        run(
+            &mut app,
+            |q: Query<Entity, With<EmptyComponent>>, mut commands: Commands| {
+                commands.entity(q.single()).despawn();
+            },
+        );
+        app.update();
+
+        // The sampler should remain occupied while fading out.
+        run(&mut app, |q: Query<&ActiveSample, With<FadingOut>>| {
+            assert_eq!(q.iter().len(), 1);
+        });
+
+        // Each update renders 512 frames, so the 50ms fade
+        // finishes within a handful of updates.
+        let released = (0..64).any(|_| {
+            app.update();
+            run(&mut app, |q: Query<&ActiveSample>| q.iter().len()) == 0
+        });
+        assert!(released, "sampler was never released after fading out");
+    }
+
+    #[test]
+    fn test_quietest_includes_fade() {
+        #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+        struct QuietPool;
+
+        #[derive(Component)]
+        struct FadingIn;
+
+        let mut app = prepare_lockstep_app(|mut commands: Commands, server: Res<AssetServer>| {
+            Pool::new(QuietPool, 2)
+                .steal_policy(StealPolicy::Quietest)
+                .spawn(&mut commands);
+
+            // Louder by its settings, but barely audible while fading in.
+            commands.spawn((
+                QuietPool,
+                SamplePlayer::new(server.load("caw.ogg")),
+                PlaybackSettings::LOOP.with_fade_in(Fade::new(Duration::from_secs(60))),
+                FadingIn,
+            ));
+
+            commands.spawn((
+                QuietPool,
+                SamplePlayer::new(server.load("caw.ogg")),
+                PlaybackSettings {
+                    volume: Volume::Linear(0.5),
+                    ..PlaybackSettings::LOOP
+                },
+            ));
+        });
+
+        let assigned = update_until(&mut app, |app| {
+            run(app, |q: Query<&ActiveSample>| q.iter().len()) == 2
+        });
+        assert!(assigned, "samples were never assigned");
+
+        app.update();
+
+        run(
+            &mut app,
+            |rank: Query<&NodeRank>,
+             active: Query<&ActiveSample>,
+             fading: Query<Entity, With<FadingIn>>| {
+                let first = rank.single().stealable[0].0;
+                assert_eq!(active.get(first).unwrap().sample_entity, fading.single());
+            },
+        );
+    }
+
+    #[test]
+    fn test_scene_round_trip() {
+        use bevy::ecs::entity::EntityHashMap;
+        use bevy::scene::{serde::SceneDeserializer, DynamicSceneBuilder};
+        use serde::de::DeserializeSeed;
+
+        #[derive(PoolLabel, Reflect, Debug, Clone, PartialEq, Eq, Hash)]
+        #[reflect(Component)]
+        struct ScenePool;
+
+        #[derive(NodeLabel, Reflect, Debug, Clone, PartialEq, Eq, Hash)]
+        #[reflect(Component)]
+        struct SceneBus;
+
+        #[derive(Component, Reflect, Default)]
+        #[reflect(Component, Default)]
+        struct Filtered;
+
+        fn register(app: &mut App) {
+            app.register_type::<ScenePool>()
+                .register_type::<SceneBus>()
+                .register_type::<Filtered>();
+        }
+
+        let mut source = prepare_app(|mut commands: Commands| {
+            Pool::new(ScenePool, 2)
+                .effect(LowPassNode::default())
+                .spawn(&mut commands);
+
+            commands.spawn((
+                VolumeNode {
+                    volume: Volume::Linear(0.25),
+                },
+                SceneBus,
+            ));
+            commands
+                .spawn((LowPassNode::default(), Filtered))
+                .connect(SceneBus);
+        });
+        register(&mut source);
+        source.update();
+
+        let text = run(
+            &mut source,
+            |world: &World,
+             nodes: Query<
+                Entity,
+                (With<Connections>, Without<MainBus>, Without<MainBusLimiter>),
+            >| {
+                let scene = DynamicSceneBuilder::from_world(world)
+                    .extract_entities(nodes.iter())
+                    .build();
+
+                scene
+                    .serialize(&world.resource::<AppTypeRegistry>().read())
+                    .unwrap()
+            },
+        );
+
+        let mut target = prepare_app(|| {});
+        register(&mut target);
+
+        let scene = {
+            let registry = target.world().resource::<AppTypeRegistry>().read();
+            let mut deserializer = ron::de::Deserializer::from_str(&text).unwrap();
+
+            SceneDeserializer {
+                type_registry: &registry,
+            }
+            .deserialize(&mut deserializer)
+            .unwrap()
+        };
+        scene
+            .write_to_world(target.world_mut(), &mut EntityHashMap::default())
+            .unwrap();
+
+        // 2 * 3 (sampler, fade, and low pass nodes) + 1 (pool volume)
+        let restored = (0..64).any(|_| {
+            target.update();
+            run(
+                &mut target,
+                |q: Query<&FirewheelNode, With<SamplePoolNode>>| q.iter().len(),
+            ) == 7
+        });
+        assert!(restored, "pool nodes were never restored");
+
+        run(&mut target, |types: Query<&SamplePoolTypes>| {
+            assert_eq!(types.single().0.len(), 1);
+        });
+
+        // Unreflected nodes keep their parameters.
+        run(&mut target, |bus: Single<&VolumeNode, With<SceneBus>>| {
+            assert_eq!(bus.volume, Volume::Linear(0.25));
+        });
+
+        run(
+            &mut target,
+            |mut context: ResMut<AudioContext>,
+             samplers: Query<(&FirewheelNode, &VoiceFade, &EffectsChain, &PoolRoot)>,
+             nodes: Query<&FirewheelNode>,
+             filtered: Single<&FirewheelNode, With<Filtered>>,
+             bus: Single<&FirewheelNode, With<SceneBus>>| {
+                let filtered = filtered.into_inner();
+                let bus = bus.into_inner();
+
+                context.with(|context| {
+                    let edges: Vec<_> = context
+                        .edges()
+                        .into_iter()
+                        .map(|e| (e.src_node, e.dst_node))
+                        .collect();
+
+                    // The labelled connection is restored.
+                    assert!(edges.contains(&(filtered.0, bus.0)));
+
+                    // As is each sampler's chain.
+                    assert_eq!(samplers.iter().len(), 2);
+                    for (sampler, fade, chain, root) in samplers.iter() {
+                        let fade = nodes.get(fade.0).unwrap();
+                        let effect = nodes.get(chain.0[0]).unwrap();
+                        let root = nodes.get(root.0).unwrap();
+
+                        assert!(edges.contains(&(sampler.0, fade.0)));
+                        assert!(edges.contains(&(fade.0, effect.0)));
+                        assert!(edges.contains(&(effect.0, root.0)));
+                    }
+                });
+            },
+        );
+
+        // Finally, the restored pool should play samples.
+        run(
+            &mut target,
+            |mut commands: Commands, server: Res<AssetServer>| {
+                commands.spawn((ScenePool, SamplePlayer::new(server.load("caw.ogg"))));
+            },
+        );
+
+        let assigned = (0..64).any(|_| {
+            target.update();
+            std::thread::sleep(Duration::from_millis(5));
+            run(&mut target, |q: Query<&ActiveSample>| q.iter().len()) > 0
+        });
+        assert!(assigned, "sample was never assigned");
+    }
 }
@@ -0,0 +1,94 @@
+//! Voice stealing and queueing policies for sampler pools.
+
+use bevy_ecs::prelude::*;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use core::time::Duration;
+
+/// Determines which sample is interrupted when a pool
+/// has no idle samplers and can't grow any further.
+///
+/// This component lives on the pool entity, so it can be
+/// set through the [`Pool`][crate::prelude::Pool] builder or
+/// inserted directly.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+/// struct DebrisPool;
+///
+/// fn spawn_pool(mut commands: Commands) {
+///     Pool::new(DebrisPool, 8)
+///         .steal_policy(StealPolicy::Quietest)
+///         .spawn(&mut commands);
+/// }
+/// ```
+///
+/// Regardless of the policy, a sample will never steal a sampler from a
+/// sample with a higher [`SamplePriority`][crate::prelude::SamplePriority],
+/// and [paused][crate::prelude::PlaybackState::Paused] samples are never stolen.
+/// Stolen samples are treated as though they completed, applying their
+/// [`OnComplete`][crate::prelude::OnComplete] behavior.
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
+pub enum StealPolicy {
+    /// Steal the sampler that began playing the earliest.
+    #[default]
+    Oldest,
+    /// Steal the sampler playing the sample with the lowest volume.
+    ///
+    /// This accounts for the sample's [`PlaybackSettings::volume`][crate::prelude::PlaybackSettings],
+    /// any fade in progress, and the gain of a
+    /// [`SpatialBasicNode`][crate::prelude::SpatialBasicNode] in its effects.
+    Quietest,
+    /// Steal the sampler playing the sample with the lowest
+    /// [`SamplePriority`][crate::prelude::SamplePriority],
+    /// preferring the oldest when priorities are equal.
+    LowestPriority,
+    /// Steal the sampler whose sample is furthest from the closest
+    /// spatial listener.
+    ///
+    /// Samples without a `GlobalTransform` are considered
+    /// to be right at the listener.
+    FurthestFromListener,
+    /// Never steal samplers.
+    ///
+    /// New samples remain queued until a sampler becomes idle
+    /// or the pool's [`QueueTimeout`] expires.
+    RejectNew,
+}
+
+/// Determines how long samples may wait for a sampler.
+///
+/// Like [`StealPolicy`], this component lives on the pool entity.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// # use std::time::Duration;
+/// #[derive(PoolLabel, Debug, Clone, PartialEq, Eq, Hash)]
+/// struct UiPool;
+///
+/// fn spawn_pool(mut commands: Commands) {
+///     // UI sounds are only meaningful right away.
+///     Pool::new(UiPool, 4)
+///         .steal_policy(StealPolicy::RejectNew)
+///         .queue_timeout(QueueTimeout::After(Duration::from_millis(50)))
+///         .spawn(&mut commands);
+/// }
+/// ```
+///
+/// The timeout begins once a sample's asset has loaded and is measured
+/// with the audio clock. When a sample times out, it's treated as though
+/// it completed, applying its [`OnComplete`][crate::prelude::OnComplete] behavior.
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
+pub enum QueueTimeout {
+    /// Wait indefinitely.
+    #[default]
+    Never,
+    /// Drop samples that haven't been assigned a sampler within the duration.
+    ///
+    /// [`Duration::ZERO`] drops samples immediately if no sampler is available.
+    After(Duration),
+}
@@ -0,0 +1,183 @@
+//! Reflection for foreign Firewheel types.
+//!
+//! These mirrors let you reflect Firewheel types in your own nodes,
+//! which [`RegisterNode::register_reflected_node`][crate::prelude::RegisterNode::register_reflected_node]
+//! requires.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use firewheel::{diff::{Diff, Patch}, Volume};
+//! #[derive(Diff, Patch, Reflect, Debug, Clone, Component)]
+//! #[reflect(Component)]
+//! struct GainNode {
+//!     #[reflect(remote = bevy_seedling::reflect::VolumeReflect)]
+//!     volume: Volume,
+//! }
+//! ```
+//!
+//! Firewheel's built-in nodes can't be reflected directly, so their
+//! parameters are saved in scenes through *state* components, like
+//! [`VolumeNodeState`]. These are kept in sync with their nodes
+//! and applied when a scene is loaded.
+
+use bevy_ecs::prelude::*;
+use bevy_reflect::{reflect_remote, std_traits::ReflectDefault, Reflect};
+use firewheel::{
+    dsp::pan_law::PanLaw,
+    nodes::{spatial_basic::SpatialBasicNode, volume::VolumeNode, volume_pan::VolumePanNode},
+    Volume,
+};
+
+/// A reflected mirror of [`firewheel::Volume`].
+#[reflect_remote(firewheel::Volume)]
+#[derive(Debug, Clone, Copy)]
+pub enum VolumeReflect {
+    /// A linear volume.
+    Linear(f32),
+    /// A volume in decibels.
+    Decibels(f32),
+}
+
+/// A reflected mirror of [`firewheel::nodes::sampler::RepeatMode`].
+#[reflect_remote(firewheel::nodes::sampler::RepeatMode)]
+#[derive(Debug, Clone, Copy)]
+pub enum RepeatModeReflect {
+    /// Play the sample once.
+    PlayOnce,
+    /// Repeat the sample a number of times.
+    RepeatMultiple {
+        /// The number of repetitions after the first playthrough.
+        num_times_to_repeat: u32,
+    },
+    /// Repeat the sample forever.
+    RepeatEndlessly,
+}
+
+/// A reflected mirror of [`PanLaw`].
+#[reflect_remote(PanLaw)]
+#[derive(Debug, Clone, Copy)]
+pub enum PanLawReflect {
+    /// A circular pan law with each channel at -3dB when panned center.
+    EqualPower3dB,
+    /// A circular pan law with each channel at -6dB when panned center.
+    EqualPower6dB,
+    /// A square root pan law.
+    SquareRoot,
+    /// A linear pan law.
+    Linear,
+}
+
+/// A reflected copy of an unreflected node's parameters.
+pub(crate) trait NodeState<T>: Component + Sized {
+    /// Capture `node`'s parameters.
+    fn capture(node: &T) -> Self;
+
+    /// Apply the saved parameters to `node`.
+    fn apply(&self, node: &mut T);
+}
+
+/// The saved parameters of a [`VolumeNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct VolumeNodeState {
+    /// The node's volume.
+    #[reflect(remote = VolumeReflect)]
+    pub volume: Volume,
+}
+
+impl Default for VolumeNodeState {
+    fn default() -> Self {
+        Self::capture(&VolumeNode::default())
+    }
+}
+
+impl NodeState<VolumeNode> for VolumeNodeState {
+    fn capture(node: &VolumeNode) -> Self {
+        Self {
+            volume: node.volume,
+        }
+    }
+
+    fn apply(&self, node: &mut VolumeNode) {
+        node.volume = self.volume;
+    }
+}
+
+/// The saved parameters of a [`VolumePanNode`].
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct VolumePanNodeState {
+    /// The node's volume.
+    #[reflect(remote = VolumeReflect)]
+    pub volume: Volume,
+    /// The node's pan, from `-1.0` to `1.0`.
+    pub pan: f32,
+    /// The node's pan law.
+    #[reflect(remote = PanLawReflect)]
+    pub pan_law: PanLaw,
+}
+
+impl Default for VolumePanNodeState {
+    fn default() -> Self {
+        Self::capture(&VolumePanNode::default())
+    }
+}
+
+impl NodeState<VolumePanNode> for VolumePanNodeState {
+    fn capture(node: &VolumePanNode) -> Self {
+        Self {
+            volume: node.volume,
+            pan: node.pan,
+            pan_law: node.pan_law,
+        }
+    }
+
+    fn apply(&self, node: &mut VolumePanNode) {
+        node.volume = self.volume;
+        node.pan = self.pan;
+        node.pan_law = self.pan_law;
+    }
+}
+
+/// The saved parameters of a [`SpatialBasicNode`].
+///
+/// The offset is omitted, since it's recalculated from the
+/// emitter's transform, and the remaining parameters are saved
+/// as they were before any spatial attenuation was applied.
+#[derive(Debug, Clone, Copy, PartialEq, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct SpatialBasicNodeState {
+    /// The node's volume.
+    #[reflect(remote = VolumeReflect)]
+    pub volume: Volume,
+    /// The distance at which the signal is fully damped.
+    pub damping_distance: f32,
+    /// The muffling cutoff in hertz.
+    pub muffle_cutoff_hz: f32,
+    /// The maximum amount of panning.
+    pub panning_threshold: f32,
+}
+
+impl Default for SpatialBasicNodeState {
+    fn default() -> Self {
+        Self::capture(&SpatialBasicNode::default())
+    }
+}
+
+impl NodeState<SpatialBasicNode> for SpatialBasicNodeState {
+    fn capture(node: &SpatialBasicNode) -> Self {
+        Self {
+            volume: node.volume,
+            damping_distance: node.damping_distance,
+            muffle_cutoff_hz: node.muffle_cutoff_hz,
+            panning_threshold: node.panning_threshold,
+        }
+    }
+
+    fn apply(&self, node: &mut SpatialBasicNode) {
+        node.volume = self.volume;
+        node.damping_distance = self.damping_distance;
+        node.muffle_cutoff_hz = self.muffle_cutoff_hz;
+        node.panning_threshold = self.panning_threshold;
+    }
+}
@@ -1,26 +1,366 @@
+use super::looping::{self, LoopPoints};
+use super::process::PlanarSample;
+use super::stream::{open_format, StreamSource};
+use super::{LoopRegion, Playhead};
 use bevy_asset::{Asset, AssetLoader};
+use bevy_log::warn;
 use bevy_reflect::TypePath;
 use firewheel::{collector::ArcGc, sample_resource::SampleResource};
-use std::num::NonZeroU32;
+use serde::{Deserialize, Serialize};
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::ops::Range;
 use std::sync::Arc;
+use symphonia::core::formats::FormatReader;
 
 /// An audio sample.
+///
+/// Samples are either fully decoded when loaded or, if loaded with
+/// [`SampleLoaderSettings::stream`], decoded incrementally during playback.
+///
+/// Samples may also carry a [`LoopRegion`] read from the file's
+/// loop points. For more details, see [`LoopRegion`].
 #[derive(Asset, TypePath, Clone)]
-pub struct Sample(ArcGc<dyn SampleResource>);
+pub struct Sample {
+    data: SampleData,
+    loop_region: Option<LoopRegion>,
+}
+
+#[derive(Clone)]
+enum SampleData {
+    Decoded(ArcGc<dyn SampleResource>),
+    Streamed(Arc<StreamSource>),
+}
 
 impl Sample {
     /// Share the inner value.
+    ///
+    /// For streamed samples, this begins a new stream.
     pub fn get(&self) -> ArcGc<dyn SampleResource> {
-        self.0.clone()
+        match &self.data {
+            SampleData::Decoded(resource) => resource.clone(),
+            SampleData::Streamed(source) => source.open(0, false),
+        }
+    }
+
+    /// Returns true if this sample is streamed rather than fully decoded.
+    pub fn is_streamed(&self) -> bool {
+        matches!(self.data, SampleData::Streamed(_))
+    }
+
+    /// The loop region embedded in the sample's file, if any.
+    pub fn loop_region(&self) -> Option<LoopRegion> {
+        self.loop_region
+    }
+
+    /// The sample's length in frames.
+    pub fn len_frames(&self) -> u64 {
+        match &self.data {
+            SampleData::Decoded(resource) => resource.len_frames(),
+            SampleData::Streamed(source) => source.len_frames(),
+        }
+    }
+
+    /// Share the inner value, beginning playback at `start_frame`.
+    ///
+    /// When `looping` is set, the resource wraps back to the
+    /// beginning of the sample rather than to `start_frame`.
+    pub(crate) fn get_from(&self, start_frame: u64, looping: bool) -> ArcGc<dyn SampleResource> {
+        let resource = match &self.data {
+            SampleData::Decoded(resource) => resource,
+            SampleData::Streamed(source) => return source.open(start_frame, looping),
+        };
+
+        let len_frames = resource.len_frames();
+
+        if start_frame == 0 || len_frames == 0 {
+            return self.get();
+        }
+
+        let offset = OffsetSample {
+            inner: resource.clone(),
+            start_frame: start_frame % len_frames,
+            looping,
+        };
+
+        ArcGc::new_unsized(|| Arc::new(offset) as Arc<dyn SampleResource>)
     }
 }
 
+/// A view into a sample resource that begins at an arbitrary frame.
+struct OffsetSample {
+    inner: ArcGc<dyn SampleResource>,
+    start_frame: u64,
+    looping: bool,
+}
+
+impl SampleResource for OffsetSample {
+    fn num_channels(&self) -> NonZeroUsize {
+        self.inner.num_channels()
+    }
+
+    fn len_frames(&self) -> u64 {
+        if self.looping {
+            self.inner.len_frames()
+        } else {
+            self.inner.len_frames() - self.start_frame
+        }
+    }
+
+    fn fill_buffers(
+        &self,
+        buffers: &mut [&mut [f32]],
+        buffer_range: Range<usize>,
+        start_frame: u64,
+    ) {
+        let len_frames = self.inner.len_frames();
+        let mut frame = self.start_frame + start_frame;
+
+        if !self.looping {
+            self.inner.fill_buffers(buffers, buffer_range, frame);
+            return;
+        }
+
+        // Looping samples may wrap around the end of the inner resource.
+        let mut range = buffer_range;
+        frame %= len_frames;
+
+        while !range.is_empty() {
+            let chunk = (len_frames - frame).min(range.len() as u64) as usize;
+
+            self.inner
+                .fill_buffers(buffers, range.start..range.start + chunk, frame);
+
+            range.start += chunk;
+            frame = 0;
+        }
+    }
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// Process a decoded sample, returning the number of leading frames trimmed.
+fn process(sample: &mut PlanarSample, settings: &SampleLoaderSettings, sample_rate: u32) -> u64 {
+    match settings.channels {
+        ChannelMix::Preserve => {}
+        ChannelMix::Mono => sample.downmix_mono(),
+        ChannelMix::Stereo => sample.mix_stereo(),
+    }
+
+    let trimmed = settings
+        .trim_silence
+        .map(|threshold| sample.trim_silence(db_to_gain(threshold)))
+        .unwrap_or(0);
+
+    let normalization = match settings.normalize {
+        Some(Normalize::Peak(target)) => {
+            let peak = sample.peak();
+            (peak > 0.0).then(|| db_to_gain(target) / peak)
+        }
+        Some(Normalize::Loudness(target)) => sample
+            .integrated_loudness(sample_rate)
+            .map(|loudness| db_to_gain(target - loudness)),
+        None => None,
+    };
+
+    let gain = normalization.unwrap_or(1.0) * db_to_gain(settings.gain_db);
+    if gain != 1.0 {
+        sample.apply_gain(gain);
+    }
+
+    trimmed as u64
+}
+
+/// Read loop points from a WAV `smpl` chunk or Ogg Vorbis comments.
+///
+/// Other formats aren't inspected. Ogg files reuse an already
+/// probed `format` if one is provided rather than probing again.
+fn read_loop_points(
+    bytes: &Arc<[u8]>,
+    extension: Option<&str>,
+    format: Option<&mut (Box<dyn FormatReader>, u32)>,
+) -> Option<LoopPoints> {
+    if let Some(points) = looping::read_wav_loop(bytes) {
+        return Some(points);
+    }
+
+    if !bytes.starts_with(b"OggS") {
+        return None;
+    }
+
+    let mut opened;
+    let (format, track_id) = match format {
+        Some(format) => format,
+        None => {
+            opened = open_format(bytes, extension).ok()?;
+            &mut opened
+        }
+    };
+
+    let sample_rate = format
+        .tracks()
+        .iter()
+        .find(|t| t.id == *track_id)?
+        .codec_params
+        .sample_rate?;
+
+    let metadata = format.metadata();
+    let revision = metadata.current()?;
+
+    looping::read_tag_loop(
+        revision
+            .tags()
+            .iter()
+            .map(|tag| (tag.key.as_str(), tag.value.to_string())),
+        sample_rate,
+    )
+}
+
 impl core::fmt::Debug for Sample {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("Sample").finish_non_exhaustive()
     }
 }
 
+/// Settings for [`SampleLoader`].
+///
+/// These can be provided in `.meta` files or with
+/// [`AssetServer::load_with_settings`][bevy_asset::AssetServer::load_with_settings].
+///
+/// ```ron
+/// (
+///     meta_format_version: "1.0",
+///     asset: Load(
+///         loader: "bevy_seedling::sample::assets::SampleLoader",
+///         settings: (
+///             channels: Mono,
+///             normalize: Some(Loudness(-16.0)),
+///             trim_silence: Some(-60.0),
+///             gain_db: -3.0,
+///         ),
+///     ),
+/// )
+/// ```
+///
+/// Processing is applied in the order listed here: channel mixing,
+/// silence trimming, normalization, then gain.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// use bevy_seedling::sample::SampleLoaderSettings;
+///
+/// fn play_music(mut commands: Commands, server: Res<AssetServer>) {
+///     let music = server.load_with_settings(
+///         "soundtrack.ogg",
+///         |settings: &mut SampleLoaderSettings| settings.stream = true,
+///     );
+///
+///     commands.spawn((SamplePlayer::new(music), PlaybackSettings::LOOP));
+/// }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SampleLoaderSettings {
+    /// Stream the sample from memory rather than decoding it entirely.
+    ///
+    /// Streamed samples keep only their encoded data in memory and decode
+    /// on a background thread during playback, making them well-suited
+    /// for long music tracks. Short sound effects should generally
+    /// remain fully decoded.
+    ///
+    /// Streamed samples support looping and seeking, but not
+    /// [`PlaybackSpeed`][crate::prelude::PlaybackSpeed].
+    /// If a format doesn't report its length up front, the
+    /// sample is fully decoded instead.
+    ///
+    /// Of the processing options below, only [`gain_db`][Self::gain_db]
+    /// applies to streamed samples.
+    pub stream: bool,
+
+    /// The quality of the resampler used when the sample's rate
+    /// differs from the engine's.
+    ///
+    /// Streamed samples are always resampled with linear interpolation,
+    /// equivalent to [`ResampleQuality::Low`]. Requesting
+    /// [`ResampleQuality::High`] for a streamed sample logs a warning.
+    pub resample_quality: ResampleQuality,
+
+    /// Forces a particular channel count.
+    pub channels: ChannelMix,
+
+    /// Normalizes the sample's level.
+    pub normalize: Option<Normalize>,
+
+    /// Removes leading and trailing frames quieter than
+    /// the provided threshold in dBFS.
+    pub trim_silence: Option<f32>,
+
+    /// A gain in decibels applied after all other processing.
+    pub gain_db: f32,
+}
+
+impl SampleLoaderSettings {
+    /// Returns true if the decoded sample must be post-processed.
+    fn requires_processing(&self) -> bool {
+        self.channels != ChannelMix::Preserve
+            || self.normalize.is_some()
+            || self.trim_silence.is_some()
+            || self.gain_db != 0.0
+    }
+}
+
+/// The quality of the resampler used while loading samples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ResampleQuality {
+    /// Fast, lower-quality resampling.
+    Low,
+    /// A good balance of speed and quality.
+    #[default]
+    Normal,
+    /// Slow, high-quality resampling.
+    High,
+}
+
+impl From<ResampleQuality> for symphonium::ResampleQuality {
+    fn from(value: ResampleQuality) -> Self {
+        match value {
+            ResampleQuality::Low => Self::Low,
+            ResampleQuality::Normal => Self::Normal,
+            ResampleQuality::High => Self::High,
+        }
+    }
+}
+
+/// Determines a sample's channel count after loading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChannelMix {
+    /// Keep the sample's original channels.
+    #[default]
+    Preserve,
+    /// Average all channels into one.
+    Mono,
+    /// Produce two channels.
+    ///
+    /// Mono samples are duplicated into both channels. Samples with
+    /// more than two channels mix even channels into the left and
+    /// odd channels into the right.
+    Stereo,
+}
+
+/// A normalization target.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Normalize {
+    /// Scale the sample so its peak reaches the provided level in dBFS.
+    Peak(f32),
+    /// Scale the sample so its integrated loudness reaches the
+    /// provided level in LUFS, as measured by ITU-R BS.1770.
+    ///
+    /// Samples shorter than 400ms can't be measured and are left unchanged.
+    Loudness(f32),
+}
+
 /// A simple loader for audio samples.
 #[derive(Debug)]
 pub struct SampleLoader {
@@ -38,6 +378,8 @@ pub enum SampleLoaderError {
     StdIo(std::io::Error),
     /// An error directly from `symphonium`.
     Symphonium(String),
+    /// An error directly from `symphonia`.
+    Symphonia(String),
 }
 
 impl From<std::io::Error> for SampleLoaderError {
@@ -52,34 +394,93 @@ impl From<symphonium::error::LoadError> for SampleLoaderError {
     }
 }
 
+impl From<symphonia::core::errors::Error> for SampleLoaderError {
+    fn from(value: symphonia::core::errors::Error) -> Self {
+        Self::Symphonia(value.to_string())
+    }
+}
+
 impl std::error::Error for SampleLoaderError {}
 
 impl std::fmt::Display for SampleLoaderError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::StdIo(stdio) => stdio.fmt(f),
-            Self::Symphonium(sy) => f.write_str(sy),
+            Self::Symphonium(sy) | Self::Symphonia(sy) => f.write_str(sy),
         }
     }
 }
 
 impl AssetLoader for SampleLoader {
     type Asset = Sample;
-    type Settings = ();
+    type Settings = SampleLoaderSettings;
     type Error = SampleLoaderError;
 
     async fn load(
         &self,
         reader: &mut dyn bevy_asset::io::Reader,
-        _settings: &Self::Settings,
+        settings: &Self::Settings,
         load_context: &mut bevy_asset::LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         // Unfortunately, we need to bridge the gap between sync and async APIs here.
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
 
+        let extension = load_context
+            .path()
+            .extension()
+            .map(|e| e.to_string_lossy().into_owned());
+
+        let bytes: Arc<[u8]> = bytes.into();
+
+        // Streams are probed up front, so their loop points are read from the same reader.
+        let mut format = if settings.stream {
+            Some(open_format(&bytes, extension.as_deref())?)
+        } else {
+            None
+        };
+
+        let loop_points = read_loop_points(&bytes, extension.as_deref(), format.as_mut())
+            .map(|points| points.resample(self.sample_rate.get()));
+        let sample = |data, trimmed: u64| Sample {
+            data,
+            loop_region: loop_points.map(|(start, end)| {
+                LoopRegion::new(
+                    Playhead::Frames(start.saturating_sub(trimmed)),
+                    Playhead::Frames(end.saturating_sub(trimmed)),
+                )
+            }),
+        };
+
+        if let Some((format, track_id)) = &format {
+            if settings.resample_quality == ResampleQuality::High {
+                warn!(
+                    "streamed samples only support low-quality resampling; ignoring `ResampleQuality::High` for {}",
+                    load_context.path().display()
+                );
+            }
+
+            let gain = db_to_gain(settings.gain_db);
+            match StreamSource::probe(
+                &bytes,
+                extension.clone(),
+                format.as_ref(),
+                *track_id,
+                self.sample_rate,
+                gain,
+            ) {
+                Some(source) => return Ok(sample(SampleData::Streamed(Arc::new(source)), 0)),
+                None => warn!(
+                    "unable to determine the length of {}; decoding fully instead of streaming",
+                    load_context.path().display()
+                ),
+            }
+        }
+
         let mut hint = symphonia::core::probe::Hint::new();
-        hint.with_extension(&load_context.path().to_string_lossy());
+        if let Some(extension) = &extension {
+            hint.with_extension(extension);
+        }
 
         let mut loader = symphonium::SymphoniumLoader::new();
         let source = firewheel::load_audio_file_from_source(
@@ -87,12 +488,25 @@ impl AssetLoader for SampleLoader {
             Box::new(std::io::Cursor::new(bytes)),
             Some(hint),
             self.sample_rate,
-            Default::default(),
+            settings.resample_quality.into(),
         )?;
 
-        Ok(Sample(ArcGc::new_unsized(|| {
-            Arc::new(source) as Arc<dyn SampleResource>
-        })))
+        if !settings.requires_processing() {
+            let data = SampleData::Decoded(ArcGc::new_unsized(|| {
+                Arc::new(source) as Arc<dyn SampleResource>
+            }));
+
+            return Ok(sample(data, 0));
+        }
+
+        let mut planar = PlanarSample::read(&source);
+        let trimmed = process(&mut planar, settings, self.sample_rate.get());
+
+        let data = SampleData::Decoded(ArcGc::new_unsized(|| {
+            Arc::new(planar) as Arc<dyn SampleResource>
+        }));
+
+        Ok(sample(data, trimmed))
     }
 
     fn extensions(&self) -> &[&str] {
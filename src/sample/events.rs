@@ -0,0 +1,104 @@
+//! Sample playback lifecycle events.
+//!
+//! Each event is triggered on the [`SamplePlayer`][super::SamplePlayer] entity,
+//! so they can be observed either globally or per-entity.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_seedling::prelude::*;
+//! #[derive(Component)]
+//! struct Subtitle(&'static str);
+//!
+//! fn play_line(mut commands: Commands, server: Res<AssetServer>) {
+//!     commands
+//!         .spawn((
+//!             SamplePlayer::new(server.load("line_01.wav")),
+//!             Subtitle("The bridge is out!"),
+//!         ))
+//!         .observe(|trigger: Trigger<PlaybackStarted>, q: Query<&Subtitle>| {
+//!             if let Ok(subtitle) = q.get(trigger.entity()) {
+//!                 info!("{}", subtitle.0);
+//!             }
+//!         })
+//!         .observe(|_: Trigger<PlaybackCompleted>| {
+//!             info!("(subtitle hidden)");
+//!         });
+//! }
+//! ```
+//!
+//! Events are triggered before the sample's [`OnComplete`][super::OnComplete]
+//! behavior is applied, so despawned entities still receive them.
+
+use super::Sample;
+use crate::pool::label::InternedPoolLabel;
+use bevy_asset::Handle;
+use bevy_ecs::prelude::*;
+
+/// Triggered when a sample is assigned a sampler and begins playback.
+#[derive(Event, Debug, Clone)]
+pub struct PlaybackStarted {
+    /// The sample player entity.
+    pub sample: Entity,
+    /// The pool the sample is playing in.
+    pub pool: InternedPoolLabel,
+    /// The sample's asset handle.
+    pub handle: Handle<Sample>,
+}
+
+/// Triggered when a sample finishes playing.
+///
+/// This is also triggered when a sample is
+/// [stopped][crate::prelude::PlaybackState::Stopped] or its
+/// [`SamplePlayer`][super::SamplePlayer] is removed during playback.
+/// If the entity was despawned, the event is triggered without a target.
+#[derive(Event, Debug, Clone)]
+pub struct PlaybackCompleted {
+    /// The sample player entity.
+    pub sample: Entity,
+    /// The pool the sample played in.
+    pub pool: InternedPoolLabel,
+    /// The sample's asset handle.
+    pub handle: Handle<Sample>,
+}
+
+/// Triggered when a sample's sampler is taken by another sample.
+///
+/// For more details, see [`StealPolicy`][crate::prelude::StealPolicy].
+#[derive(Event, Debug, Clone)]
+pub struct PlaybackStolen {
+    /// The interrupted sample player entity.
+    pub sample: Entity,
+    /// The pool the sample played in.
+    pub pool: InternedPoolLabel,
+    /// The interrupted sample's asset handle.
+    pub handle: Handle<Sample>,
+    /// The sample player entity that took the sampler.
+    pub stolen_by: Entity,
+}
+
+/// Triggered when a sample's asset fails to load.
+///
+/// The sample is then treated as though it completed, applying its
+/// [`OnComplete`][super::OnComplete] behavior.
+#[derive(Event, Debug, Clone)]
+pub struct SampleLoadFailed {
+    /// The sample player entity.
+    pub sample: Entity,
+    /// The pool the sample was queued in.
+    pub pool: InternedPoolLabel,
+    /// The sample's asset handle.
+    pub handle: Handle<Sample>,
+}
+
+/// Triggered when a looping sample wraps back to its beginning.
+#[derive(Event, Debug, Clone)]
+pub struct LoopWrapped {
+    /// The sample player entity.
+    pub sample: Entity,
+    /// The pool the sample is playing in.
+    pub pool: InternedPoolLabel,
+    /// The sample's asset handle.
+    pub handle: Handle<Sample>,
+    /// The number of times the sample has wrapped since it started.
+    pub loops: u64,
+}
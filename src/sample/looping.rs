@@ -0,0 +1,318 @@
+//! Loop region playback and loop point parsing.
+//!
+//! A looped sample is laid out as a sequence of *virtual* frames: the intro
+//! up to the region's end, the region body repeated, and finally the outro
+//! if the repetitions are finite. [`RegionSample`] reads the inner resource
+//! through this layout, so the sampler itself always plays once.
+
+use firewheel::{collector::ArcGc, nodes::sampler::RepeatMode, sample_resource::SampleResource};
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// The virtual length of endlessly repeating samples.
+const ENDLESS: u64 = u64::MAX / 2;
+
+/// Maps virtual frames onto a sample with a loop region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LoopMap {
+    start: u64,
+    end: u64,
+    len_frames: u64,
+    /// The number of additional repetitions, or `None` if endless.
+    repeats: Option<u64>,
+}
+
+impl LoopMap {
+    /// Construct a mapping for the region `start..end`.
+    ///
+    /// Returns `None` if the region is empty or the sample doesn't repeat.
+    pub fn new(start: u64, end: u64, len_frames: u64, repeat_mode: RepeatMode) -> Option<Self> {
+        let end = end.min(len_frames);
+
+        if start >= end {
+            return None;
+        }
+
+        let repeats = match repeat_mode {
+            RepeatMode::PlayOnce => return None,
+            RepeatMode::RepeatEndlessly => None,
+            RepeatMode::RepeatMultiple {
+                num_times_to_repeat,
+            } => Some(num_times_to_repeat as u64),
+        };
+
+        Some(Self {
+            start,
+            end,
+            len_frames,
+            repeats,
+        })
+    }
+
+    fn body(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// The total number of virtual frames.
+    pub fn virtual_len(&self) -> u64 {
+        match self.repeats {
+            Some(repeats) => self.len_frames + repeats * self.body(),
+            None => ENDLESS,
+        }
+    }
+
+    /// Convert a frame within the sample to the first virtual frame that plays it.
+    ///
+    /// For endless repetition, frames beyond the region are wrapped into it.
+    pub fn to_virtual(&self, frame: u64) -> u64 {
+        if frame < self.end {
+            return frame;
+        }
+
+        match self.repeats {
+            Some(repeats) => frame.min(self.len_frames) + repeats * self.body(),
+            None => self.start + (frame - self.start) % self.body(),
+        }
+    }
+
+    /// Map a virtual frame onto the sample, returning the frame and
+    /// the number of contiguous frames that follow it.
+    fn map_run(&self, frame: u64) -> (u64, u64) {
+        if frame < self.end {
+            return (frame, self.end - frame);
+        }
+
+        let over = frame - self.end;
+        let looped = self.repeats.map(|r| r * self.body()).unwrap_or(u64::MAX);
+
+        if over < looped {
+            let offset = over % self.body();
+            (self.start + offset, self.body() - offset)
+        } else {
+            let frame = self.end + (over - looped);
+            (frame, self.len_frames.saturating_sub(frame))
+        }
+    }
+
+    /// Map a virtual frame onto the sample.
+    pub fn map(&self, frame: u64) -> u64 {
+        self.map_run(frame).0.min(self.len_frames)
+    }
+}
+
+/// A sample resource that plays its inner resource through a [`LoopMap`].
+///
+/// The sampler should play this resource with [`RepeatMode::PlayOnce`],
+/// since repetition is handled here.
+pub(crate) struct RegionSample {
+    inner: ArcGc<dyn SampleResource>,
+    map: LoopMap,
+    /// The virtual frame at which playback begins.
+    offset: u64,
+}
+
+impl RegionSample {
+    pub fn new(
+        inner: ArcGc<dyn SampleResource>,
+        map: LoopMap,
+        offset: u64,
+    ) -> ArcGc<dyn SampleResource> {
+        let resource = Self { inner, map, offset };
+
+        ArcGc::new_unsized(|| Arc::new(resource) as Arc<dyn SampleResource>)
+    }
+}
+
+impl SampleResource for RegionSample {
+    fn num_channels(&self) -> NonZeroUsize {
+        self.inner.num_channels()
+    }
+
+    fn len_frames(&self) -> u64 {
+        self.map.virtual_len().saturating_sub(self.offset)
+    }
+
+    fn fill_buffers(
+        &self,
+        buffers: &mut [&mut [f32]],
+        buffer_range: Range<usize>,
+        start_frame: u64,
+    ) {
+        let mut range = buffer_range;
+        let mut frame = self.offset + start_frame;
+
+        while !range.is_empty() {
+            let (inner_frame, run) = self.map.map_run(frame);
+
+            if run == 0 {
+                for channel in buffers.iter_mut() {
+                    channel[range.clone()].fill(0.0);
+                }
+
+                return;
+            }
+
+            let chunk = run.min(range.len() as u64) as usize;
+            self.inner
+                .fill_buffers(buffers, range.start..range.start + chunk, inner_frame);
+
+            range.start += chunk;
+            frame += chunk as u64;
+        }
+    }
+}
+
+/// Loop points in frames at a file's own sample rate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LoopPoints {
+    pub start: u64,
+    pub end: u64,
+    pub sample_rate: u32,
+}
+
+impl LoopPoints {
+    /// Convert the loop points to the provided sample rate.
+    pub fn resample(&self, sample_rate: u32) -> (u64, u64) {
+        let convert = |frame: u64| {
+            (frame as f64 * sample_rate as f64 / self.sample_rate as f64).round() as u64
+        };
+
+        (convert(self.start), convert(self.end))
+    }
+}
+
+/// Read the first loop from a WAV file's `smpl` chunk.
+pub(crate) fn read_wav_loop(bytes: &[u8]) -> Option<LoopPoints> {
+    if bytes.get(0..4)? != b"RIFF" || bytes.get(8..12)? != b"WAVE" {
+        return None;
+    }
+
+    let read_u32 = |data: &[u8], offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(
+            data.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+
+    let mut sample_rate = None;
+    let mut points = None;
+    let mut offset = 12;
+
+    while let (Some(id), Some(size)) = (bytes.get(offset..offset + 4), read_u32(bytes, offset + 4))
+    {
+        let start = offset + 8;
+        let end = start.checked_add(size as usize)?.min(bytes.len());
+        let data = &bytes[start..end];
+
+        match id {
+            b"fmt " => sample_rate = read_u32(data, 4),
+            b"smpl" => {
+                if read_u32(data, 28)? > 0 {
+                    // The end of a sampler loop is inclusive.
+                    points = Some((read_u32(data, 44)?, read_u32(data, 48)? as u64 + 1));
+                }
+            }
+            _ => {}
+        }
+
+        // Chunks are padded to an even length.
+        offset = end + (size as usize & 1);
+    }
+
+    let (start, end) = points?;
+
+    Some(LoopPoints {
+        start: start as u64,
+        end,
+        sample_rate: sample_rate?,
+    })
+}
+
+/// Read loop points from `LOOPSTART` and `LOOPLENGTH` or `LOOPEND` tags,
+/// as commonly found in Ogg Vorbis comments.
+pub(crate) fn read_tag_loop<'a>(
+    tags: impl IntoIterator<Item = (&'a str, String)>,
+    sample_rate: u32,
+) -> Option<LoopPoints> {
+    let mut start = None;
+    let mut length = None;
+    let mut end = None;
+
+    for (key, value) in tags {
+        let value = value.trim().parse::<u64>().ok();
+
+        match key.to_ascii_uppercase().as_str() {
+            "LOOPSTART" => start = value,
+            "LOOPLENGTH" => length = value,
+            "LOOPEND" => end = value,
+            _ => {}
+        }
+    }
+
+    let start = start?;
+    let end = match (length, end) {
+        (Some(length), _) => start + length,
+        (None, Some(end)) => end,
+        (None, None) => return None,
+    };
+
+    Some(LoopPoints {
+        start,
+        end,
+        sample_rate,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_loop_map() {
+        let map = LoopMap::new(
+            4,
+            8,
+            10,
+            RepeatMode::RepeatMultiple {
+                num_times_to_repeat: 1,
+            },
+        )
+        .unwrap();
+
+        let frames: Vec<_> = (0..map.virtual_len()).map(|f| map.map(f)).collect();
+        assert_eq!(frames, [0, 1, 2, 3, 4, 5, 6, 7, 4, 5, 6, 7, 8, 9]);
+
+        let endless = LoopMap::new(4, 8, 10, RepeatMode::RepeatEndlessly).unwrap();
+        assert_eq!(endless.map(13), 5);
+        assert_eq!(endless.to_virtual(9), 5);
+    }
+
+    #[test]
+    fn test_wav_smpl() {
+        let mut fmt = vec![0u8; 16];
+        fmt[4..8].copy_from_slice(&44100u32.to_le_bytes());
+
+        let mut smpl = vec![0u8; 60];
+        smpl[28..32].copy_from_slice(&1u32.to_le_bytes());
+        smpl[44..48].copy_from_slice(&1000u32.to_le_bytes());
+        smpl[48..52].copy_from_slice(&4999u32.to_le_bytes());
+
+        let mut bytes = b"RIFF\0\0\0\0WAVE".to_vec();
+        for (id, data) in [(b"fmt ", fmt), (b"smpl", smpl)] {
+            bytes.extend_from_slice(id);
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&data);
+        }
+
+        let points = read_wav_loop(&bytes).unwrap();
+        assert_eq!(
+            points,
+            LoopPoints {
+                start: 1000,
+                end: 5000,
+                sample_rate: 44100,
+            }
+        );
+        assert_eq!(points.resample(48000), (1088, 5442));
+    }
+}
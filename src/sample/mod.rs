@@ -2,13 +2,27 @@
 
 use crate::node::ExcludeNode;
 use crate::prelude::Volume;
+use crate::timeline::Timeline;
 use bevy_asset::Handle;
 use bevy_ecs::{component::ComponentId, prelude::*, world::DeferredWorld};
+use bevy_math::curve::EaseFunction;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use core::time::Duration;
 use firewheel::nodes::sampler::RepeatMode;
+use std::num::NonZeroU32;
 
 mod assets;
+pub mod events;
+pub(crate) mod looping;
+pub(crate) mod process;
+pub(crate) mod resample;
+pub mod set;
+mod stream;
 
-pub use assets::{Sample, SampleLoader, SampleLoaderError};
+pub use assets::{
+    ChannelMix, Normalize, ResampleQuality, Sample, SampleLoader, SampleLoaderError,
+    SampleLoaderSettings,
+};
 
 /// A component that queues sample playback.
 ///
@@ -116,8 +130,9 @@ pub use assets::{Sample, SampleLoader, SampleLoaderError};
 /// [has some tradeoffs][crate::pool::dynamic#when-to-use-dynamic-pools], so you may
 /// find yourself gravitating towards manually defined [`Pool`][crate::prelude::Pool]s as your
 /// requirements grow.
-#[derive(Debug, Component, Clone)]
-#[require(PlaybackSettings, ExcludeNode)]
+#[derive(Debug, Component, Clone, Reflect)]
+#[reflect(Component)]
+#[require(PlaybackSettings, PlaybackState, PlaybackPosition, ExcludeNode)]
 #[component(on_insert = on_insert_sample)]
 pub struct SamplePlayer(pub(crate) Handle<Sample>);
 
@@ -143,14 +158,33 @@ impl SamplePlayer {
 }
 
 /// Controls the playback settings of a [`SamplePlayer`].
-#[derive(Debug, Component, Clone, Default)]
+#[derive(Debug, Component, Clone, Default, Reflect)]
+#[reflect(Component, Default)]
 pub struct PlaybackSettings {
     /// Sets the sample's [`RepeatMode`].
+    #[reflect(remote = crate::reflect::RepeatModeReflect)]
     pub repeat_mode: RepeatMode,
     /// Determines this sample's behavior on playback completion.
     pub on_complete: OnComplete,
     /// Sets the volume of the sample.
+    #[reflect(remote = crate::reflect::VolumeReflect)]
     pub volume: Volume,
+    /// Sets where playback begins within the sample.
+    ///
+    /// When looping, the sample wraps back to its beginning
+    /// rather than to the start position.
+    pub start: Playhead,
+    /// Fades the sample in when playback begins.
+    pub fade_in: Option<Fade>,
+    /// Fades the sample out when it's stopped, or when its
+    /// [`SamplePlayer`] is removed or despawned.
+    ///
+    /// The sampler remains occupied until the fade completes.
+    pub fade_out: Option<Fade>,
+    /// Overrides the sample's embedded [`LoopRegion`].
+    ///
+    /// Loop regions only apply when the sample repeats.
+    pub loop_region: Option<LoopRegion>,
 }
 
 impl PlaybackSettings {
@@ -160,6 +194,10 @@ impl PlaybackSettings {
         repeat_mode: RepeatMode::PlayOnce,
         volume: Volume::Linear(1.0),
         on_complete: OnComplete::Despawn,
+        start: Playhead::ZERO,
+        fade_in: None,
+        fade_out: None,
+        loop_region: None,
     };
 
     /// Repeatedly loop the audio source until
@@ -168,6 +206,10 @@ impl PlaybackSettings {
         repeat_mode: RepeatMode::RepeatEndlessly,
         volume: Volume::Linear(1.0),
         on_complete: OnComplete::Despawn,
+        start: Playhead::ZERO,
+        fade_in: None,
+        fade_out: None,
+        loop_region: None,
     };
 
     /// Play the sample once, removing the audio-related components on completion.
@@ -175,6 +217,10 @@ impl PlaybackSettings {
         repeat_mode: RepeatMode::PlayOnce,
         volume: Volume::Linear(1.0),
         on_complete: OnComplete::Remove,
+        start: Playhead::ZERO,
+        fade_in: None,
+        fade_out: None,
+        loop_region: None,
     };
 
     /// Play the sample once, preserving the components and entity on completion.
@@ -182,11 +228,369 @@ impl PlaybackSettings {
         repeat_mode: RepeatMode::PlayOnce,
         volume: Volume::Linear(1.0),
         on_complete: OnComplete::Preserve,
+        start: Playhead::ZERO,
+        fade_in: None,
+        fade_out: None,
+        loop_region: None,
     };
+
+    /// Begin playback at `start`.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_seedling::prelude::*;
+    /// fn play_mid_song(mut commands: Commands, server: Res<AssetServer>) {
+    ///     commands.spawn((
+    ///         SamplePlayer::new(server.load("my_song.wav")),
+    ///         PlaybackSettings::LOOP.with_start(Playhead::Seconds(30.0)),
+    ///     ));
+    /// }
+    /// ```
+    pub const fn with_start(self, start: Playhead) -> Self {
+        Self { start, ..self }
+    }
+
+    /// Fade the sample in when playback begins.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_seedling::prelude::*;
+    /// # use std::time::Duration;
+    /// fn play_ambience(mut commands: Commands, server: Res<AssetServer>) {
+    ///     commands.spawn((
+    ///         SamplePlayer::new(server.load("wind.wav")),
+    ///         PlaybackSettings::LOOP
+    ///             .with_fade_in(Fade::new(Duration::from_secs(2)))
+    ///             .with_fade_out(Fade::new(Duration::from_millis(500))),
+    ///     ));
+    /// }
+    /// ```
+    pub const fn with_fade_in(self, fade: Fade) -> Self {
+        Self {
+            fade_in: Some(fade),
+            ..self
+        }
+    }
+
+    /// Fade the sample out when it's stopped, removed, or despawned.
+    pub const fn with_fade_out(self, fade: Fade) -> Self {
+        Self {
+            fade_out: Some(fade),
+            ..self
+        }
+    }
+
+    /// Loop `region` rather than the sample's embedded loop region.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_seedling::prelude::*;
+    /// fn play_music(mut commands: Commands, server: Res<AssetServer>) {
+    ///     commands.spawn((
+    ///         SamplePlayer::new(server.load("my_song.wav")),
+    ///         PlaybackSettings::LOOP.with_loop_region(LoopRegion::new(
+    ///             Playhead::Seconds(8.0),
+    ///             Playhead::Seconds(72.0),
+    ///         )),
+    ///     ));
+    /// }
+    /// ```
+    pub const fn with_loop_region(self, region: LoopRegion) -> Self {
+        Self {
+            loop_region: Some(region),
+            ..self
+        }
+    }
+}
+
+/// A region of a sample that repeats once playback first reaches its end.
+///
+/// This allows a sample to play an intro once before seamlessly looping
+/// its body. Loop regions are read from WAV `smpl` chunks and Ogg
+/// `LOOPSTART` / `LOOPLENGTH` comments, and can be overridden with
+/// [`PlaybackSettings::loop_region`].
+///
+/// With [`RepeatMode::RepeatMultiple`], the region repeats the requested
+/// number of times before playback continues to the end of the sample.
+/// Samples played with [`RepeatMode::PlayOnce`] ignore their loop region.
+///
+/// Since [streamed][crate::sample::SampleLoaderSettings::stream] samples
+/// can only be decoded sequentially, they must seek at each loop boundary,
+/// which may briefly interrupt playback.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct LoopRegion {
+    /// The beginning of the region.
+    pub start: Playhead,
+    /// The end of the region, exclusive.
+    pub end: Playhead,
+}
+
+impl LoopRegion {
+    /// Construct a new [`LoopRegion`].
+    pub const fn new(start: Playhead, end: Playhead) -> Self {
+        Self { start, end }
+    }
+}
+
+/// A volume fade applied to a [`SamplePlayer`].
+///
+/// For more details, see [`PlaybackSettings::fade_in`]
+/// and [`PlaybackSettings::fade_out`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct Fade {
+    /// The duration of the fade.
+    pub duration: Duration,
+    /// The easing curve applied to the sample's linear gain.
+    pub ease: EaseFunction,
+}
+
+impl Fade {
+    /// Construct a new [`Fade`] with a linear easing curve.
+    pub const fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            ease: EaseFunction::Linear,
+        }
+    }
+
+    /// Set the fade's easing curve.
+    pub const fn with_ease(self, ease: EaseFunction) -> Self {
+        Self { ease, ..self }
+    }
+}
+
+/// A position within a sample.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Default)]
+pub enum Playhead {
+    /// A position in seconds.
+    Seconds(f64),
+    /// A position in frames at the audio engine's sample rate.
+    Frames(u64),
+}
+
+impl Playhead {
+    /// The very beginning of a sample.
+    pub const ZERO: Self = Self::Frames(0);
+
+    /// Convert this position into frames at the provided sample rate.
+    pub fn as_frames(&self, sample_rate: NonZeroU32) -> u64 {
+        match *self {
+            Self::Seconds(seconds) => (seconds.max(0.0) * sample_rate.get() as f64).round() as u64,
+            Self::Frames(frames) => frames,
+        }
+    }
+}
+
+impl Default for Playhead {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+/// Moves the playhead of a [`SamplePlayer`].
+///
+/// Inserting this component on a playing or paused sample restarts
+/// it from the provided position. If the sample is still queued,
+/// it overrides [`PlaybackSettings::start`] instead. The component
+/// is removed once it's been applied.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// #[derive(Component)]
+/// struct Music;
+///
+/// fn skip_intro(q: Query<Entity, With<Music>>, mut commands: Commands) {
+///     for music in q.iter() {
+///         commands.entity(music).insert(Seek(Playhead::Seconds(12.5)));
+///     }
+/// }
+/// ```
+///
+/// Seeking a sample that has already finished has no effect.
+#[derive(Debug, Component, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+#[component(storage = "SparseSet")]
+pub struct Seek(pub Playhead);
+
+/// The current playhead of a [`SamplePlayer`].
+///
+/// This is updated every frame from the sampler node
+/// the sample is assigned to. While the sample is queued,
+/// the position remains at zero.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// fn log_position(q: Query<&PlaybackPosition>) {
+///     for position in q.iter() {
+///         info!("playhead: {:.2}s", position.seconds());
+///     }
+/// }
+/// ```
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct PlaybackPosition {
+    pub(crate) frames: u64,
+    pub(crate) seconds: f64,
+}
+
+impl PlaybackPosition {
+    /// The playhead in frames at the audio engine's sample rate.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// The playhead in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.seconds
+    }
+}
+
+/// The importance of a [`SamplePlayer`] when sampler pools are full.
+///
+/// A sample can only steal a sampler from samples with an
+/// equal or lower priority. Samples without this component
+/// have a priority of `0`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// fn play_dialogue(mut commands: Commands, server: Res<AssetServer>) {
+///     commands.spawn((
+///         SamplePlayer::new(server.load("dialogue.wav")),
+///         SamplePriority(10),
+///     ));
+/// }
+/// ```
+///
+/// For more details, see [`StealPolicy`][crate::prelude::StealPolicy].
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+#[reflect(Component, Default)]
+pub struct SamplePriority(pub i32);
+
+/// Controls the playback speed of a [`SamplePlayer`].
+///
+/// Samples are resampled during playback, so changing the speed
+/// also changes the pitch. A speed of `2.0` plays the sample twice
+/// as fast, an octave higher.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// fn play_footstep(mut commands: Commands, server: Res<AssetServer>) {
+///     commands.spawn((
+///         SamplePlayer::new(server.load("footstep.wav")),
+///         PlaybackSpeed::from_semitones(-2.0),
+///     ));
+/// }
+/// ```
+///
+/// Since the speed is a [`Timeline`], it can be smoothly ramped.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// fn slow_motion(mut q: Query<&mut PlaybackSpeed>, mut context: ResMut<AudioContext>) {
+///     let now = context.now();
+///
+///     for mut speed in q.iter_mut() {
+///         speed
+///             .speed
+///             .push_curve(
+///                 0.5,
+///                 now,
+///                 now + ClockSeconds(0.5),
+///                 EaseFunction::QuadraticOut,
+///             )
+///             .unwrap();
+///     }
+/// }
+/// ```
+///
+/// The speed is clamped between `0.0` and `8.0`. Inserting this component on
+/// a playing sample restarts it at its current [`PlaybackPosition`], much like
+/// a [`Seek`]. Removing it during playback returns the sample to its normal speed.
+///
+/// Streamed samples can only be read sequentially, so they always play
+/// at their normal speed, and a warning is logged if this component is present.
+#[derive(Debug, Component, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct PlaybackSpeed {
+    /// The playback speed, where `1.0` is the original speed.
+    pub speed: Timeline<f32>,
+}
+
+impl PlaybackSpeed {
+    /// Construct a new [`PlaybackSpeed`].
+    pub fn new(speed: f32) -> Self {
+        Self {
+            speed: Timeline::new(speed),
+        }
+    }
+
+    /// Construct a new [`PlaybackSpeed`] that shifts
+    /// the pitch by `semitones`.
+    pub fn from_semitones(semitones: f32) -> Self {
+        Self::new(Self::semitones_to_speed(semitones))
+    }
+
+    /// Convert a pitch shift in semitones to a playback speed.
+    pub fn semitones_to_speed(semitones: f32) -> f32 {
+        2f32.powf(semitones / 12.0)
+    }
+}
+
+impl Default for PlaybackSpeed {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+/// Pauses, resumes, or stops an individual [`SamplePlayer`].
+///
+/// Changes are forwarded to the sampler node the sample is
+/// assigned to. A paused sample keeps its sampler, so it resumes
+/// exactly where it left off, and paused samplers are never
+/// handed over to other samples queued in the same pool.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// #[derive(Component)]
+/// struct GameplaySound;
+///
+/// fn pause_gameplay(mut q: Query<&mut PlaybackState, With<GameplaySound>>) {
+///     for mut state in q.iter_mut() {
+///         *state = PlaybackState::Paused;
+///     }
+/// }
+/// ```
+///
+/// A sample spawned with [`PlaybackState::Paused`] will acquire a sampler
+/// but won't produce any sound until it's set to [`PlaybackState::Playing`].
+#[derive(Debug, Component, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default)]
+pub enum PlaybackState {
+    /// Play the sample, resuming it if it was paused.
+    #[default]
+    Playing,
+    /// Pause the sample, preserving its playhead and sampler.
+    Paused,
+    /// Stop the sample.
+    ///
+    /// The sample's [`OnComplete`] behavior is applied as if
+    /// it had finished playing. Samples that are stopped before
+    /// acquiring a sampler complete without playing.
+    Stopped,
 }
 
 /// Determines what happens when a sample completes plaback.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, Reflect,
+)]
+#[reflect(Default)]
 pub enum OnComplete {
     /// Preserve the entity and components, leaving them untouched.
     Preserve,
@@ -199,6 +603,7 @@ pub enum OnComplete {
 
 /// A marker struct for entities that are waiting
 /// for asset loading and playback assignment.
-#[derive(Debug, Component, Default)]
+#[derive(Debug, Component, Default, Reflect)]
+#[reflect(Component, Default)]
 #[component(storage = "SparseSet")]
 pub struct QueuedSample;
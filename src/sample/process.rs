@@ -0,0 +1,325 @@
+//! Offline processing applied to decoded samples.
+
+use firewheel::sample_resource::SampleResource;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+
+/// The number of frames read from a resource at a time.
+const READ_FRAMES: usize = 1024;
+
+/// A fully decoded, planar `f32` sample.
+#[derive(Debug, Clone)]
+pub(crate) struct PlanarSample {
+    pub channels: Vec<Vec<f32>>,
+}
+
+impl PlanarSample {
+    /// Read every frame of `resource`.
+    pub fn read(resource: &dyn SampleResource) -> Self {
+        let len_frames = resource.len_frames() as usize;
+        let mut channels = vec![vec![0.0; len_frames]; resource.num_channels().get()];
+
+        let mut start = 0;
+        while start < len_frames {
+            let end = (start + READ_FRAMES).min(len_frames);
+            let mut buffers: Vec<&mut [f32]> =
+                channels.iter_mut().map(|c| &mut c[start..end]).collect();
+
+            resource.fill_buffers(&mut buffers, 0..end - start, start as u64);
+            start = end;
+        }
+
+        Self { channels }
+    }
+
+    pub fn len_frames(&self) -> usize {
+        self.channels.first().map(Vec::len).unwrap_or(0)
+    }
+
+    /// Average all channels into one.
+    pub fn downmix_mono(&mut self) {
+        if self.channels.len() <= 1 {
+            return;
+        }
+
+        let scale = 1.0 / self.channels.len() as f32;
+        let mut mono = vec![0.0; self.len_frames()];
+        for channel in &self.channels {
+            for (m, s) in mono.iter_mut().zip(channel) {
+                *m += s * scale;
+            }
+        }
+
+        self.channels = vec![mono];
+    }
+
+    /// Produce exactly two channels.
+    ///
+    /// Mono samples are duplicated. With more than two channels,
+    /// even channels are averaged into the left and odd channels into the right.
+    pub fn mix_stereo(&mut self) {
+        match self.channels.len() {
+            0 | 2 => {}
+            1 => {
+                let mono = self.channels[0].clone();
+                self.channels.push(mono);
+            }
+            count => {
+                let len_frames = self.len_frames();
+                let mut stereo = [vec![0.0; len_frames], vec![0.0; len_frames]];
+                let scales = [1.0 / count.div_ceil(2) as f32, 1.0 / (count / 2) as f32];
+
+                for (i, channel) in self.channels.iter().enumerate() {
+                    let side = i % 2;
+                    let scale = scales[side];
+
+                    for (o, s) in stereo[side].iter_mut().zip(channel) {
+                        *o += s * scale;
+                    }
+                }
+
+                self.channels = stereo.into();
+            }
+        }
+    }
+
+    /// Remove leading and trailing frames whose magnitude
+    /// doesn't exceed `threshold` in any channel.
+    ///
+    /// Returns the number of leading frames removed.
+    pub fn trim_silence(&mut self, threshold: f32) -> usize {
+        let audible = |frame: usize| self.channels.iter().any(|c| c[frame].abs() > threshold);
+        let len_frames = self.len_frames();
+
+        let Some(first) = (0..len_frames).find(|f| audible(*f)) else {
+            for channel in &mut self.channels {
+                channel.clear();
+            }
+            return len_frames;
+        };
+        let last = (0..len_frames).rev().find(|f| audible(*f)).unwrap_or(first);
+
+        for channel in &mut self.channels {
+            channel.truncate(last + 1);
+            channel.drain(..first);
+        }
+
+        first
+    }
+
+    /// The maximum absolute sample value.
+    pub fn peak(&self) -> f32 {
+        self.channels
+            .iter()
+            .flatten()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()))
+    }
+
+    /// The integrated loudness in LUFS, following ITU-R BS.1770.
+    ///
+    /// Returns `None` for samples that are entirely gated out.
+    pub fn integrated_loudness(&self, sample_rate: u32) -> Option<f32> {
+        let block = (sample_rate as f64 * 0.4) as usize;
+        let hop = block / 4;
+        let len_frames = self.len_frames();
+
+        if block == 0 || len_frames < block {
+            return None;
+        }
+
+        // K-weighted squares, summed across channels.
+        let mut power = vec![0.0f64; len_frames];
+        for channel in &self.channels {
+            let mut filter = KWeighting::new(sample_rate as f32);
+            for (p, s) in power.iter_mut().zip(channel) {
+                let weighted = filter.process(*s) as f64;
+                *p += weighted * weighted;
+            }
+        }
+
+        let blocks: Vec<f64> = (0..=(len_frames - block) / hop)
+            .map(|i| power[i * hop..i * hop + block].iter().sum::<f64>() / block as f64)
+            .collect();
+
+        let loudness = |p: f64| -0.691 + 10.0 * p.log10();
+        let gated_mean = |threshold: f64| {
+            let gated: Vec<f64> = blocks
+                .iter()
+                .copied()
+                .filter(|p| loudness(*p) > threshold)
+                .collect();
+
+            (!gated.is_empty()).then(|| gated.iter().sum::<f64>() / gated.len() as f64)
+        };
+
+        let absolute = gated_mean(-70.0)?;
+        let relative = gated_mean(loudness(absolute) - 10.0)?;
+
+        Some(loudness(relative) as f32)
+    }
+
+    /// Scale every sample by `gain`.
+    pub fn apply_gain(&mut self, gain: f32) {
+        for sample in self.channels.iter_mut().flatten() {
+            *sample *= gain;
+        }
+    }
+}
+
+impl SampleResource for PlanarSample {
+    fn num_channels(&self) -> NonZeroUsize {
+        NonZeroUsize::new(self.channels.len()).unwrap_or(NonZeroUsize::MIN)
+    }
+
+    fn len_frames(&self) -> u64 {
+        self.len_frames() as u64
+    }
+
+    fn fill_buffers(
+        &self,
+        buffers: &mut [&mut [f32]],
+        buffer_range: Range<usize>,
+        start_frame: u64,
+    ) {
+        let start = start_frame as usize;
+
+        for (i, buffer) in buffers.iter_mut().enumerate() {
+            let output = &mut buffer[buffer_range.clone()];
+
+            match self.channels.get(i) {
+                Some(channel) => {
+                    let end = (start + output.len()).min(channel.len());
+                    let available = end.saturating_sub(start);
+
+                    output[..available].copy_from_slice(&channel[start..start + available]);
+                    output[available..].fill(0.0);
+                }
+                None => output.fill(0.0),
+            }
+        }
+    }
+}
+
+/// The two-stage K-weighting filter from ITU-R BS.1770.
+#[derive(Debug, Clone)]
+pub(crate) struct KWeighting {
+    shelf: Biquad,
+    highpass: Biquad,
+}
+
+impl KWeighting {
+    pub fn new(sample_rate: f32) -> Self {
+        let rate = sample_rate as f64;
+
+        let shelf = {
+            let f0 = 1_681.974_450_955_533;
+            let gain = 3.999_843_853_973_347;
+            let q = 0.707_175_236_955_419_6;
+
+            let k = (std::f64::consts::PI * f0 / rate).tan();
+            let vh = 10f64.powf(gain / 20.0);
+            let vb = vh.powf(0.499_666_774_154_541_6);
+            let a0 = 1.0 + k / q + k * k;
+
+            Biquad::new(
+                [
+                    (vh + vb * k / q + k * k) / a0,
+                    2.0 * (k * k - vh) / a0,
+                    (vh - vb * k / q + k * k) / a0,
+                ],
+                [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            )
+        };
+
+        let highpass = {
+            let f0 = 38.135_470_876_024_44;
+            let q = 0.500_327_037_323_877_3;
+
+            let k = (std::f64::consts::PI * f0 / rate).tan();
+            let a0 = 1.0 + k / q + k * k;
+
+            Biquad::new(
+                [1.0, -2.0, 1.0],
+                [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            )
+        };
+
+        Self { shelf, highpass }
+    }
+
+    pub fn process(&mut self, sample: f32) -> f32 {
+        self.highpass.process(self.shelf.process(sample))
+    }
+}
+
+/// A direct form I biquad with normalized coefficients.
+#[derive(Debug, Clone)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self {
+            b,
+            a,
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    fn process(&mut self, sample: f32) -> f32 {
+        let x = sample as f64;
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+
+        y as f32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn planar(channels: &[&[f32]]) -> PlanarSample {
+        PlanarSample {
+            channels: channels.iter().map(|c| c.to_vec()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_trim_silence() {
+        let mut sample = planar(&[&[0.0, 0.001, 0.5, 0.0, 0.2, 0.0], &[0.0; 6]]);
+        assert_eq!(sample.trim_silence(0.01), 2);
+
+        assert_eq!(sample.channels[0], [0.5, 0.0, 0.2]);
+        assert_eq!(sample.channels[1].len(), 3);
+    }
+
+    #[test]
+    fn test_mix_stereo() {
+        let mut sample = planar(&[&[1.0], &[2.0], &[3.0], &[4.0]]);
+        sample.mix_stereo();
+
+        assert_eq!(sample.channels, [vec![2.0], vec![3.0]]);
+    }
+
+    #[test]
+    fn test_loudness() {
+        // A full-scale 1kHz sine reads about -3 LUFS per channel.
+        let rate = 48000;
+        let sine: Vec<f32> = (0..rate)
+            .map(|i| (i as f32 * 1000.0 * std::f32::consts::TAU / rate as f32).sin())
+            .collect();
+
+        let loudness = planar(&[&sine]).integrated_loudness(rate).unwrap();
+        assert!((loudness + 3.0).abs() < 0.2, "{loudness}");
+    }
+}
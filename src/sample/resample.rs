@@ -0,0 +1,524 @@
+//! Variable-speed sample playback.
+//!
+//! Firewheel's sampler plays resources at a fixed rate, so speed changes
+//! are implemented by wrapping the sample resource itself. The wrapper
+//! interpolates the inner resource at an arbitrary rate, handling looping
+//! on its own. The reported length assumes the starting speed, so samples
+//! that slow down signal that they should be restarted with a longer one.
+
+use arrayvec::ArrayVec;
+use firewheel::{collector::ArcGc, nodes::sampler::RepeatMode, sample_resource::SampleResource};
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// The maximum number of channels supported by variable-speed playback.
+///
+/// Any additional channels are silent.
+pub(crate) const MAX_CHANNELS: usize = 8;
+
+/// The maximum playback speed.
+pub(crate) const MAX_SPEED: f32 = 8.0;
+
+/// The number of output frames processed per scratch read.
+const CHUNK_FRAMES: usize = 64;
+
+/// Enough scratch space for a chunk at maximum speed plus interpolation.
+const SCRATCH_FRAMES: usize = CHUNK_FRAMES * MAX_SPEED as usize + 2;
+
+/// The per-frame smoothing coefficient for speed changes, roughly 10ms at 48kHz.
+const SMOOTHING: f32 = 0.002;
+
+/// The number of recent positions retained for seeking backwards.
+const CHECKPOINTS: usize = 32;
+
+/// The length reported for samples that never end.
+const ENDLESS: u64 = u64::MAX / 2;
+
+/// Shared state between the ECS and a [`ResampledSample`].
+#[derive(Debug)]
+pub(crate) struct SpeedControl {
+    /// The target speed as `f32` bits.
+    target: AtomicU32,
+    /// The current position within the sample, wrapped to its length.
+    playhead: AtomicU64,
+    /// The current position, counting each repetition.
+    position: AtomicU64,
+    finished: AtomicBool,
+    overrun: AtomicBool,
+}
+
+impl SpeedControl {
+    /// Set the target playback speed.
+    ///
+    /// The audio thread smoothly approaches this value.
+    pub fn set_speed(&self, speed: f32) {
+        self.target
+            .store(clamp_speed(speed).to_bits(), Ordering::Relaxed);
+    }
+
+    /// The current playhead in frames.
+    pub fn playhead(&self) -> u64 {
+        self.playhead.load(Ordering::Relaxed)
+    }
+
+    /// The current position in frames, counting each repetition.
+    ///
+    /// Passing this to [`ResampledSample::new`] resumes playback.
+    pub fn position(&self) -> u64 {
+        self.position.load(Ordering::Relaxed)
+    }
+
+    /// Returns true once the sample has played to completion.
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if the sample has slowed down enough that
+    /// it won't complete within its reported length.
+    pub fn needs_restart(&self) -> bool {
+        self.overrun.load(Ordering::Relaxed)
+    }
+}
+
+fn clamp_speed(speed: f32) -> f32 {
+    if speed.is_nan() {
+        1.0
+    } else {
+        speed.clamp(0.0, MAX_SPEED)
+    }
+}
+
+/// The playback state at a given output frame.
+#[derive(Debug, Clone, Copy)]
+struct Cursor {
+    out_frame: u64,
+    /// The unwrapped position within the inner resource.
+    position: f64,
+    /// The smoothed speed.
+    speed: f32,
+}
+
+impl Cursor {
+    /// Advance by `frames` output frames toward a constant `target` speed.
+    ///
+    /// This is the closed form of the per-frame smoothing in
+    /// [`ResampledSample::fill_buffers`].
+    fn advance(&mut self, frames: u64, target: f32) {
+        let retain = 1.0 - SMOOTHING as f64;
+        let decay = retain.powf(frames as f64);
+        let offset = (self.speed - target) as f64;
+
+        self.out_frame += frames;
+        self.position +=
+            frames as f64 * target as f64 + offset * retain * (1.0 - decay) / SMOOTHING as f64;
+        self.speed = (target as f64 + offset * decay) as f32;
+    }
+}
+
+/// State only touched by the audio thread.
+struct ReaderState {
+    cursor: Cursor,
+    /// Recent cursors in ascending order, for seeking backwards.
+    checkpoints: ArrayVec<Cursor, CHECKPOINTS>,
+    /// Channel-major scratch space for reading the inner resource.
+    scratch: Box<[f32]>,
+}
+
+/// A sample resource that plays its inner resource at a variable speed.
+///
+/// The sampler should play this resource with [`RepeatMode::PlayOnce`],
+/// since repetition is handled here.
+pub(crate) struct ResampledSample {
+    inner: ArcGc<dyn SampleResource>,
+    control: Arc<SpeedControl>,
+    /// The total number of inner frames to play, or `None` if endless.
+    total_frames: Option<u64>,
+    /// The length in output frames at the starting speed.
+    len_frames: u64,
+    /// The cursor at the first output frame.
+    initial: Cursor,
+    /// Only the audio thread reads samples, so this lock is never contended.
+    state: Mutex<ReaderState>,
+}
+
+impl ResampledSample {
+    /// Wrap `inner`, beginning playback at `start_frame`.
+    ///
+    /// `start_frame` may lie beyond the end of `inner`
+    /// to resume a repeating sample partway through.
+    pub fn new(
+        inner: ArcGc<dyn SampleResource>,
+        start_frame: u64,
+        speed: f32,
+        repeat_mode: RepeatMode,
+    ) -> (ArcGc<dyn SampleResource>, Arc<SpeedControl>) {
+        let len_frames = inner.len_frames();
+        let total_frames = match repeat_mode {
+            RepeatMode::PlayOnce => Some(len_frames),
+            RepeatMode::RepeatMultiple {
+                num_times_to_repeat,
+            } => Some(len_frames * (num_times_to_repeat as u64 + 1)),
+            RepeatMode::RepeatEndlessly => None,
+        };
+
+        let speed = clamp_speed(speed);
+        let finished = len_frames == 0 || total_frames.is_some_and(|total| start_frame >= total);
+        let control = Arc::new(SpeedControl {
+            target: AtomicU32::new(speed.to_bits()),
+            playhead: AtomicU64::new(start_frame % len_frames.max(1)),
+            position: AtomicU64::new(start_frame),
+            finished: AtomicBool::new(finished),
+            overrun: AtomicBool::new(false),
+        });
+
+        let out_frames = match total_frames {
+            Some(total) if speed > 0.0 => {
+                ((total.saturating_sub(start_frame) as f64 / speed as f64).ceil() as u64)
+                    .min(ENDLESS)
+            }
+            _ => ENDLESS,
+        };
+
+        let initial = Cursor {
+            out_frame: 0,
+            position: start_frame as f64,
+            speed,
+        };
+
+        let channels = inner.num_channels().get().min(MAX_CHANNELS);
+        let resource = Self {
+            state: Mutex::new(ReaderState {
+                cursor: initial,
+                checkpoints: ArrayVec::new(),
+                scratch: vec![0.0; channels * SCRATCH_FRAMES].into_boxed_slice(),
+            }),
+            inner,
+            control: control.clone(),
+            total_frames,
+            len_frames: out_frames,
+            initial,
+        };
+
+        (
+            ArcGc::new_unsized(|| Arc::new(resource) as Arc<dyn SampleResource>),
+            control,
+        )
+    }
+
+    /// Read `scratch[..][0..span]` from the unwrapped frame `first`,
+    /// wrapping around the inner resource and zeroing frames past the end.
+    fn read_span(&self, scratch: &mut [&mut [f32]], first: u64, span: usize, len_frames: u64) {
+        let mut filled = 0;
+
+        while filled < span {
+            let frame = first + filled as u64;
+            let remaining = self
+                .total_frames
+                .map(|total| total.saturating_sub(frame))
+                .unwrap_or(u64::MAX);
+
+            if remaining == 0 {
+                for channel in scratch.iter_mut() {
+                    channel[filled..span].fill(0.0);
+                }
+
+                return;
+            }
+
+            let local = frame % len_frames;
+            let chunk = (len_frames - local)
+                .min(remaining)
+                .min((span - filled) as u64) as usize;
+
+            self.inner
+                .fill_buffers(scratch, filled..filled + chunk, local);
+
+            filled += chunk;
+        }
+    }
+}
+
+impl SampleResource for ResampledSample {
+    fn num_channels(&self) -> NonZeroUsize {
+        self.inner.num_channels()
+    }
+
+    fn len_frames(&self) -> u64 {
+        // This assumes the starting speed. Speeding up completes early,
+        // which is signalled through the `SpeedControl`, while slowing
+        // down signals that the sample should be restarted.
+        self.len_frames
+    }
+
+    fn fill_buffers(
+        &self,
+        buffers: &mut [&mut [f32]],
+        buffer_range: Range<usize>,
+        start_frame: u64,
+    ) {
+        let len_frames = self.inner.len_frames();
+        let channels = buffers
+            .len()
+            .min(self.inner.num_channels().get())
+            .min(MAX_CHANNELS);
+
+        for channel in buffers.iter_mut().skip(channels) {
+            channel[buffer_range.clone()].fill(0.0);
+        }
+
+        let mut state = match self.state.try_lock() {
+            Ok(state) if len_frames != 0 => state,
+            _ => {
+                for channel in buffers.iter_mut().take(channels) {
+                    channel[buffer_range.clone()].fill(0.0);
+                }
+
+                return;
+            }
+        };
+        let ReaderState {
+            cursor,
+            checkpoints,
+            scratch,
+        } = &mut *state;
+
+        let target = f32::from_bits(self.control.target.load(Ordering::Relaxed));
+
+        // The sampler may jump to another frame, in which case we resume
+        // from the nearest earlier cursor, assuming the current target
+        // speed for any frames in between.
+        if start_frame != cursor.out_frame {
+            checkpoints.retain(|checkpoint| checkpoint.out_frame <= start_frame);
+            *cursor = checkpoints.last().copied().unwrap_or(self.initial);
+            cursor.advance(start_frame - cursor.out_frame, target);
+        }
+
+        if checkpoints
+            .last()
+            .is_none_or(|last| last.out_frame < cursor.out_frame)
+        {
+            if checkpoints.is_full() {
+                checkpoints.remove(0);
+            }
+            checkpoints.push(*cursor);
+        }
+
+        let mut scratch: ArrayVec<&mut [f32], MAX_CHANNELS> =
+            scratch.chunks_mut(SCRATCH_FRAMES).collect();
+        let scratch = &mut scratch[..channels];
+
+        let mut speed = cursor.speed;
+        let mut position = cursor.position;
+        let mut finished = self
+            .total_frames
+            .is_some_and(|total| position >= total as f64);
+
+        let mut chunk_start = buffer_range.start;
+        while chunk_start < buffer_range.end {
+            let chunk_end = (chunk_start + CHUNK_FRAMES).min(buffer_range.end);
+
+            if finished {
+                for channel in buffers.iter_mut().take(channels) {
+                    channel[chunk_start..buffer_range.end].fill(0.0);
+                }
+
+                break;
+            }
+
+            let first = position as u64;
+            let span =
+                (((chunk_end - chunk_start) as f32 * MAX_SPEED) as usize + 2).min(SCRATCH_FRAMES);
+            self.read_span(scratch, first, span, len_frames);
+
+            for frame in chunk_start..chunk_end {
+                let offset = position - first as f64;
+                let index = (offset as usize).min(span - 2);
+                let fract = (offset - index as f64) as f32;
+
+                for (output, input) in buffers.iter_mut().zip(scratch.iter()) {
+                    let a = input[index];
+                    let b = input[index + 1];
+                    output[frame] = a + (b - a) * fract;
+                }
+
+                speed += (target - speed) * SMOOTHING;
+                position += speed as f64;
+            }
+
+            if self
+                .total_frames
+                .is_some_and(|total| position >= total as f64)
+            {
+                finished = true;
+            }
+
+            chunk_start = chunk_end;
+        }
+
+        *cursor = Cursor {
+            out_frame: start_frame + buffer_range.len() as u64,
+            position,
+            speed,
+        };
+
+        let playhead = if finished {
+            len_frames
+        } else {
+            position as u64 % len_frames
+        };
+        self.control.playhead.store(playhead, Ordering::Relaxed);
+        self.control
+            .position
+            .store(position as u64, Ordering::Relaxed);
+
+        if finished {
+            self.control.finished.store(true, Ordering::Relaxed);
+        } else if let Some(total) = self.total_frames.filter(|_| self.len_frames != ENDLESS) {
+            let remaining = self.len_frames.saturating_sub(cursor.out_frame);
+            let needed = (total as f64 - position) / speed.max(target) as f64;
+
+            if needed > (remaining + CHUNK_FRAMES as u64) as f64 {
+                self.control.overrun.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A mono ramp where each frame's value is its index.
+    struct Ramp(u64);
+
+    impl SampleResource for Ramp {
+        fn num_channels(&self) -> NonZeroUsize {
+            NonZeroUsize::new(1).unwrap()
+        }
+
+        fn len_frames(&self) -> u64 {
+            self.0
+        }
+
+        fn fill_buffers(
+            &self,
+            buffers: &mut [&mut [f32]],
+            buffer_range: Range<usize>,
+            start_frame: u64,
+        ) {
+            for (i, frame) in buffer_range.enumerate() {
+                buffers[0][frame] = (start_frame + i as u64) as f32;
+            }
+        }
+    }
+
+    fn ramp(len: u64) -> ArcGc<dyn SampleResource> {
+        ArcGc::new_unsized(|| Arc::new(Ramp(len)) as Arc<dyn SampleResource>)
+    }
+
+    #[test]
+    fn test_double_speed() {
+        let (resource, control) = ResampledSample::new(ramp(100), 0, 2.0, RepeatMode::PlayOnce);
+
+        let mut output = [0f32; 64];
+        resource.fill_buffers(&mut [&mut output], 0..64, 0);
+
+        assert_eq!(output[0], 0.0);
+        assert_eq!(output[10], 20.0);
+        assert_eq!(output[49], 98.0);
+        // past the end of the sample
+        assert_eq!(output[50], 0.0);
+        assert!(control.is_finished());
+    }
+
+    #[test]
+    fn test_looping_half_speed() {
+        let (resource, control) =
+            ResampledSample::new(ramp(16), 8, 0.5, RepeatMode::RepeatEndlessly);
+
+        let mut output = [0f32; 32];
+        resource.fill_buffers(&mut [&mut output], 0..32, 0);
+
+        assert_eq!(output[0], 8.0);
+        assert_eq!(output[1], 8.5);
+        // wraps back to the start of the sample
+        assert_eq!(output[16], 0.0);
+        assert_eq!(control.playhead(), 8);
+        assert!(!control.is_finished());
+    }
+
+    #[test]
+    fn test_length() {
+        let (resource, _) = ResampledSample::new(ramp(100), 20, 2.0, RepeatMode::PlayOnce);
+        assert_eq!(resource.len_frames(), 40);
+
+        let (resource, _) = ResampledSample::new(
+            ramp(100),
+            0,
+            0.5,
+            RepeatMode::RepeatMultiple {
+                num_times_to_repeat: 1,
+            },
+        );
+        assert_eq!(resource.len_frames(), 400);
+
+        let (resource, _) = ResampledSample::new(ramp(100), 0, 0.0, RepeatMode::PlayOnce);
+        assert_eq!(resource.len_frames(), ENDLESS);
+    }
+
+    #[test]
+    fn test_start_frame() {
+        let (resource, control) = ResampledSample::new(ramp(1000), 0, 1.0, RepeatMode::PlayOnce);
+        control.set_speed(2.0);
+
+        let mut first = [0f32; 128];
+        resource.fill_buffers(&mut [&mut first], 0..128, 0);
+
+        let mut second = [0f32; 128];
+        resource.fill_buffers(&mut [&mut second], 0..128, 128);
+
+        // reading from an earlier frame replays the same audio
+        let mut replayed = [0f32; 128];
+        resource.fill_buffers(&mut [&mut replayed], 0..128, 128);
+        assert_eq!(second, replayed);
+
+        let mut replayed = [0f32; 128];
+        resource.fill_buffers(&mut [&mut replayed], 0..128, 0);
+        assert_eq!(first, replayed);
+
+        // skipping ahead follows the speed curve
+        let mut skipped = [0f32; 1];
+        resource.fill_buffers(&mut [&mut skipped], 0..1, 256);
+
+        let mut continued = [0f32; 256];
+        resource.fill_buffers(&mut [&mut continued], 0..128, 0);
+        resource.fill_buffers(&mut [&mut continued], 128..256, 128);
+        let mut next = [0f32; 1];
+        resource.fill_buffers(&mut [&mut next], 0..1, 256);
+
+        assert!((skipped[0] - next[0]).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_slowdown_restart() {
+        let (resource, control) = ResampledSample::new(ramp(1000), 0, 1.0, RepeatMode::PlayOnce);
+        control.set_speed(0.25);
+
+        let mut output = [0f32; 512];
+        for block in 0..4 {
+            resource.fill_buffers(&mut [&mut output], 0..512, block * 512);
+        }
+
+        assert!(control.needs_restart());
+        assert!(!control.is_finished());
+
+        // resuming from the current position continues where it left off
+        let position = control.position();
+        let (resource, control) =
+            ResampledSample::new(ramp(1000), position, 0.25, RepeatMode::PlayOnce);
+        assert_eq!(resource.len_frames(), (1000 - position) * 4);
+        assert_eq!(control.playhead(), position);
+    }
+}
@@ -0,0 +1,445 @@
+//! Sample variation sets.
+//!
+//! A [`SampleSet`] groups several interchangeable samples, like footsteps
+//! or impacts, choosing one each time a [`SampleSetPlayer`] is spawned.
+//!
+//! Sets are loaded from RON manifests with the `.sampleset.ron` extension.
+//! Sample paths are relative to the asset root.
+//!
+//! ```ron
+//! (
+//!     mode: Shuffle,
+//!     samples: [
+//!         "footsteps/grass_01.wav",
+//!         "footsteps/grass_02.wav",
+//!         // Entries may also be weighted for `Weighted` selection.
+//!         (path: "footsteps/grass_03.wav", weight: 0.5),
+//!     ],
+//!     volume_jitter: 2.0,
+//!     pitch_jitter: 0.5,
+//! )
+//! ```
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_seedling::prelude::*;
+//! fn footstep(mut commands: Commands, server: Res<AssetServer>) {
+//!     commands.spawn(SampleSetPlayer::new(server.load("grass.sampleset.ron")));
+//! }
+//! ```
+
+use super::{PlaybackSettings, PlaybackSpeed, Sample, SamplePlayer};
+use bevy_asset::{Asset, AssetId, AssetLoader, Assets, Handle};
+use bevy_ecs::prelude::*;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect, TypePath};
+use bevy_utils::HashMap;
+use firewheel::Volume;
+use serde::{Deserialize, Serialize};
+
+/// A set of interchangeable samples.
+///
+/// For more details, see the [module docs][self].
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct SampleSet {
+    /// The samples and their selection weights.
+    pub samples: Vec<(Handle<Sample>, f32)>,
+    /// How samples are chosen.
+    pub mode: SelectionMode,
+    /// The maximum random volume deviation in decibels, applied in both directions.
+    pub volume_jitter: f32,
+    /// The maximum random pitch deviation in semitones, applied in both directions.
+    pub pitch_jitter: f32,
+}
+
+/// Determines how a [`SampleSet`] chooses its next sample.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[reflect(Default)]
+pub enum SelectionMode {
+    /// Choose uniformly at random. Samples may repeat.
+    #[default]
+    Random,
+    /// Choose at random according to each sample's weight.
+    Weighted,
+    /// Play every sample once in a random order before reshuffling.
+    ///
+    /// The same sample never plays twice in a row.
+    Shuffle,
+    /// Play each sample in order, wrapping around at the end.
+    RoundRobin,
+}
+
+/// The selection state of a single [`SampleSet`].
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SelectionState {
+    /// The remaining shuffled indices, consumed from the back.
+    order: Vec<usize>,
+    next: usize,
+    last: Option<usize>,
+}
+
+impl SampleSet {
+    /// Choose the next sample's index.
+    pub(crate) fn select(
+        &self,
+        state: &mut SelectionState,
+        rng: &mut fastrand::Rng,
+    ) -> Option<usize> {
+        let len = self.samples.len();
+        if len == 0 {
+            return None;
+        }
+
+        let index = match self.mode {
+            SelectionMode::Random => rng.usize(..len),
+            SelectionMode::Weighted => {
+                let total: f32 = self.samples.iter().map(|(_, w)| w.max(0.0)).sum();
+                let mut target = rng.f32() * total;
+
+                self.samples
+                    .iter()
+                    .position(|(_, weight)| {
+                        target -= weight.max(0.0);
+                        target < 0.0
+                    })
+                    .unwrap_or(len - 1)
+            }
+            SelectionMode::Shuffle => {
+                if state.order.is_empty() {
+                    state.order.extend(0..len);
+                    rng.shuffle(&mut state.order);
+
+                    // Avoid repeating across shuffles.
+                    if len > 1 && state.order.last() == state.last.as_ref() {
+                        state.order.swap(0, len - 1);
+                    }
+                }
+
+                state.order.pop().unwrap_or(0)
+            }
+            SelectionMode::RoundRobin => {
+                let index = state.next % len;
+                state.next = index + 1;
+                index
+            }
+        };
+
+        state.last = Some(index);
+
+        Some(index)
+    }
+}
+
+/// A component that plays a sample chosen from a [`SampleSet`].
+///
+/// Once the set has loaded, a sample is chosen and this component is
+/// replaced with a [`SamplePlayer`], so [`PlaybackSettings`], effects,
+/// and pools all apply as usual. Inserting this component again
+/// plays another sample from the set. Any volume and pitch jitter is
+/// applied on top of the entity's [`PlaybackSettings`] and [`PlaybackSpeed`].
+/// Jitter doesn't accumulate across retriggers; each one deviates from the
+/// values that were present before the set's first jitter was applied.
+/// A [`PlaybackSpeed`] is only inserted if the set has pitch jitter.
+///
+/// Selection state, such as the shuffled order, is shared
+/// between all players of the same set.
+#[derive(Debug, Component, Clone, Reflect)]
+#[reflect(Component)]
+pub struct SampleSetPlayer(pub(crate) Handle<SampleSet>);
+
+impl SampleSetPlayer {
+    /// Construct a new [`SampleSetPlayer`].
+    pub fn new(handle: Handle<SampleSet>) -> Self {
+        Self(handle)
+    }
+}
+
+/// The values a [`SampleSetPlayer`] entity had before jitter was applied.
+///
+/// Each value is paired with its jittered result, so changes made
+/// between retriggers can be told apart from the previous jitter.
+#[derive(Debug, Component, Clone, Copy)]
+pub(crate) struct SampleSetBase {
+    volume: (Volume, Volume),
+    speed: Option<(f32, f32)>,
+}
+
+/// The selection state of every [`SampleSet`].
+#[derive(Resource, Default)]
+pub(crate) struct SampleSetStates {
+    states: HashMap<AssetId<SampleSet>, SelectionState>,
+    rng: fastrand::Rng,
+}
+
+/// Choose samples for newly loaded sample set players.
+pub(crate) fn play_sample_sets(
+    players: Query<(
+        Entity,
+        &SampleSetPlayer,
+        Option<&PlaybackSettings>,
+        Option<&PlaybackSpeed>,
+        Option<&SampleSetBase>,
+    )>,
+    sets: Res<Assets<SampleSet>>,
+    mut states: ResMut<SampleSetStates>,
+    mut commands: Commands,
+) {
+    let SampleSetStates { states, rng } = states.as_mut();
+
+    for (entity, player, settings, speed, base) in players.iter() {
+        let Some(set) = sets.get(&player.0) else {
+            continue;
+        };
+
+        let state = states.entry(player.0.id()).or_default();
+        let Some(index) = set.select(state, rng) else {
+            continue;
+        };
+
+        let mut jitter = |range: f32| (rng.f32() * 2.0 - 1.0) * range.abs();
+
+        // Values that still hold the previous jitter are restored to their base.
+        let mut settings = settings.cloned().unwrap_or_default();
+        let base_volume = match base {
+            Some(SampleSetBase {
+                volume: (base, jittered),
+                ..
+            }) if settings.volume == *jittered => *base,
+            _ => settings.volume,
+        };
+        let gain = 10f32.powf(jitter(set.volume_jitter) / 20.0);
+        settings.volume = Volume::Linear(base_volume.amp() * gain);
+
+        let mut new_base = SampleSetBase {
+            volume: (base_volume, settings.volume),
+            speed: None,
+        };
+
+        let mut entity = commands.entity(entity);
+        entity
+            .remove::<SampleSetPlayer>()
+            .insert((SamplePlayer::new(set.samples[index].0.clone()), settings));
+
+        // Variable-speed playback has a cost, so it's only used when needed.
+        if set.pitch_jitter != 0.0 {
+            let mut speed = speed.cloned().unwrap_or_default();
+            let current = speed.speed.get();
+            let base_speed = match base.and_then(|base| base.speed) {
+                Some((base, jittered)) if current == jittered => base,
+                _ => current,
+            };
+
+            let jittered = base_speed * PlaybackSpeed::semitones_to_speed(jitter(set.pitch_jitter));
+            speed.speed.set(jittered);
+            new_base.speed = Some((base_speed, jittered));
+
+            entity.insert(speed);
+        }
+
+        entity.insert(new_base);
+    }
+}
+
+/// A serialized [`SampleSet`].
+#[derive(Debug, Deserialize)]
+struct SampleSetManifest {
+    #[serde(default)]
+    mode: SelectionMode,
+    samples: Vec<ManifestEntry>,
+    #[serde(default)]
+    volume_jitter: f32,
+    #[serde(default)]
+    pitch_jitter: f32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ManifestEntry {
+    Path(String),
+    Weighted { path: String, weight: f32 },
+}
+
+/// A loader for [`SampleSet`] RON manifests.
+#[derive(Debug, Default)]
+pub struct SampleSetLoader;
+
+/// Errors produced while loading sample sets.
+#[derive(Debug)]
+pub enum SampleSetLoaderError {
+    /// An I/O error, such as missing files.
+    StdIo(std::io::Error),
+    /// The manifest couldn't be parsed.
+    Ron(ron::error::SpannedError),
+}
+
+impl From<std::io::Error> for SampleSetLoaderError {
+    fn from(value: std::io::Error) -> Self {
+        Self::StdIo(value)
+    }
+}
+
+impl From<ron::error::SpannedError> for SampleSetLoaderError {
+    fn from(value: ron::error::SpannedError) -> Self {
+        Self::Ron(value)
+    }
+}
+
+impl std::error::Error for SampleSetLoaderError {}
+
+impl std::fmt::Display for SampleSetLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::StdIo(stdio) => stdio.fmt(f),
+            Self::Ron(ron) => ron.fmt(f),
+        }
+    }
+}
+
+impl AssetLoader for SampleSetLoader {
+    type Asset = SampleSet;
+    type Settings = ();
+    type Error = SampleSetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn bevy_asset::io::Reader,
+        _settings: &Self::Settings,
+        load_context: &mut bevy_asset::LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let manifest: SampleSetManifest = ron::de::from_bytes(&bytes)?;
+
+        let samples = manifest
+            .samples
+            .into_iter()
+            .map(|entry| match entry {
+                ManifestEntry::Path(path) => (load_context.load(path), 1.0),
+                ManifestEntry::Weighted { path, weight } => (load_context.load(path), weight),
+            })
+            .collect();
+
+        Ok(SampleSet {
+            samples,
+            mode: manifest.mode,
+            volume_jitter: manifest.volume_jitter,
+            pitch_jitter: manifest.pitch_jitter,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["sampleset.ron"]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn set(mode: SelectionMode, weights: &[f32]) -> SampleSet {
+        SampleSet {
+            samples: weights.iter().map(|w| (Handle::default(), *w)).collect(),
+            mode,
+            volume_jitter: 0.0,
+            pitch_jitter: 0.0,
+        }
+    }
+
+    fn select_many(set: &SampleSet, count: usize) -> Vec<usize> {
+        let mut state = SelectionState::default();
+        let mut rng = fastrand::Rng::with_seed(7);
+
+        (0..count)
+            .map(|_| set.select(&mut state, &mut rng).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let set = set(SelectionMode::RoundRobin, &[1.0; 3]);
+        assert_eq!(select_many(&set, 5), [0, 1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn test_shuffle_no_repeat() {
+        let set = set(SelectionMode::Shuffle, &[1.0; 4]);
+        let selections = select_many(&set, 400);
+
+        for window in selections.windows(2) {
+            assert_ne!(window[0], window[1]);
+        }
+
+        for pass in selections.chunks(4) {
+            let mut pass = pass.to_vec();
+            pass.sort();
+            assert_eq!(pass, [0, 1, 2, 3]);
+        }
+    }
+
+    #[test]
+    fn test_jitter_base() {
+        use bevy_ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let mut sets = Assets::<SampleSet>::default();
+        let handle = sets.add(SampleSet {
+            volume_jitter: 6.0,
+            pitch_jitter: 1.0,
+            ..set(SelectionMode::Random, &[1.0])
+        });
+        world.insert_resource(sets);
+        world.init_resource::<SampleSetStates>();
+
+        let entity = world
+            .spawn((
+                SampleSetPlayer::new(handle.clone()),
+                PlaybackSettings {
+                    volume: Volume::Linear(0.5),
+                    ..Default::default()
+                },
+            ))
+            .id();
+
+        // Retriggering many times should never drift beyond the jitter range.
+        for _ in 0..200 {
+            world.run_system_once(play_sample_sets).unwrap();
+
+            let volume = world.get::<PlaybackSettings>(entity).unwrap().volume.amp();
+            let speed = world.get::<PlaybackSpeed>(entity).unwrap().speed.get();
+
+            assert!((0.25..=1.0).contains(&volume), "{volume}");
+            assert!(
+                (PlaybackSpeed::semitones_to_speed(-1.0)..=PlaybackSpeed::semitones_to_speed(1.0))
+                    .contains(&speed),
+                "{speed}"
+            );
+
+            world
+                .entity_mut(entity)
+                .insert(SampleSetPlayer::new(handle.clone()));
+        }
+    }
+
+    #[test]
+    fn test_no_pitch_jitter() {
+        use bevy_ecs::system::RunSystemOnce;
+
+        let mut world = World::new();
+        let mut sets = Assets::<SampleSet>::default();
+        let handle = sets.add(set(SelectionMode::Random, &[1.0]));
+        world.insert_resource(sets);
+        world.init_resource::<SampleSetStates>();
+
+        let entity = world.spawn(SampleSetPlayer::new(handle)).id();
+        world.run_system_once(play_sample_sets).unwrap();
+
+        assert!(world.get::<SamplePlayer>(entity).is_some());
+        assert!(world.get::<PlaybackSpeed>(entity).is_none());
+    }
+
+    #[test]
+    fn test_weighted() {
+        let set = set(SelectionMode::Weighted, &[0.0, 1.0, 0.0]);
+        assert!(select_many(&set, 50).iter().all(|i| *i == 1));
+    }
+}
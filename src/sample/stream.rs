@@ -0,0 +1,655 @@
+//! Streamed sample playback.
+//!
+//! Streamed samples keep only their encoded bytes in memory. A single
+//! long-lived decoder thread fills a ring buffer for each playback ahead of
+//! the audio thread, resampling to the engine's rate as it goes. The decoder always continues
+//! from the beginning once it reaches the end, so loops are seamless, while
+//! any other discontinuity in the sampler's reads is treated as a seek.
+
+use super::assets::SampleLoaderError;
+use bevy_log::warn;
+use firewheel::{collector::ArcGc, sample_resource::SampleResource};
+use std::collections::VecDeque;
+use std::io::Cursor;
+use std::num::{NonZeroU32, NonZeroUsize};
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex, OnceLock};
+use std::time::Duration;
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{Decoder, DecoderOptions},
+    errors::Error as SymphoniaError,
+    formats::{FormatOptions, FormatReader, SeekMode, SeekTo},
+    io::MediaSourceStream,
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+/// How far ahead of the audio thread the decoder reads.
+const BUFFER_SECONDS: f64 = 1.0;
+
+/// How long the decoder sleeps when every ring buffer is full.
+const IDLE_SLEEP: Duration = Duration::from_millis(5);
+
+/// Encoded audio shared by every stream of a streamed [`Sample`][super::Sample].
+pub(crate) struct StreamSource {
+    bytes: Arc<[u8]>,
+    extension: Option<String>,
+    channels: NonZeroUsize,
+    source_rate: u32,
+    sample_rate: NonZeroU32,
+    len_frames: u64,
+    gain: f32,
+}
+
+impl core::fmt::Debug for StreamSource {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StreamSource")
+            .field("bytes", &self.bytes.len())
+            .field("extension", &self.extension)
+            .field("channels", &self.channels)
+            .field("source_rate", &self.source_rate)
+            .field("sample_rate", &self.sample_rate)
+            .field("len_frames", &self.len_frames)
+            .field("gain", &self.gain)
+            .finish()
+    }
+}
+
+impl StreamSource {
+    /// Describe `bytes` from its already probed `format`, returning `None`
+    /// if the stream's length can't be determined without decoding it entirely.
+    pub fn probe(
+        bytes: &Arc<[u8]>,
+        extension: Option<String>,
+        format: &dyn FormatReader,
+        track_id: u32,
+        sample_rate: NonZeroU32,
+        gain: f32,
+    ) -> Option<Self> {
+        let track = format.tracks().iter().find(|t| t.id == track_id)?;
+        let params = &track.codec_params;
+
+        let (Some(source_rate), Some(source_frames), Some(channels)) =
+            (params.sample_rate, params.n_frames, params.channels)
+        else {
+            return None;
+        };
+
+        let channels = NonZeroUsize::new(channels.count())?;
+
+        let len_frames =
+            (source_frames as f64 * sample_rate.get() as f64 / source_rate as f64).round() as u64;
+
+        Some(Self {
+            bytes: bytes.clone(),
+            extension,
+            channels,
+            source_rate,
+            sample_rate,
+            len_frames,
+            gain,
+        })
+    }
+
+    /// The stream's length in frames at the engine's sample rate.
+    pub fn len_frames(&self) -> u64 {
+        self.len_frames
+    }
+
+    /// Begin a new stream at `start_frame`.
+    ///
+    /// When `looping` is set, the resource wraps back to the
+    /// beginning of the sample rather than to `start_frame`.
+    pub fn open(self: &Arc<Self>, start_frame: u64, looping: bool) -> ArcGc<dyn SampleResource> {
+        let start_frame = if self.len_frames == 0 {
+            0
+        } else {
+            start_frame % self.len_frames
+        };
+
+        let shared = Arc::new(StreamShared {
+            ring: Mutex::new(VecDeque::new()),
+            capacity: (self.sample_rate.get() as f64 * BUFFER_SECONDS) as usize
+                * self.channels.get(),
+            seek_frame: AtomicU64::new(start_frame),
+            requested: AtomicU32::new(1),
+            acknowledged: AtomicU32::new(0),
+            shutdown: AtomicBool::new(false),
+        });
+
+        if let Some(decoder) = decoder_thread() {
+            let _ = decoder.send(StreamJob {
+                source: self.clone(),
+                shared: shared.clone(),
+            });
+        }
+
+        let resource = StreamedSample {
+            source: self.clone(),
+            shared,
+            start_frame,
+            looping,
+            expected: AtomicU64::new(0),
+            missed: AtomicU64::new(0),
+        };
+
+        ArcGc::new_unsized(|| Arc::new(resource) as Arc<dyn SampleResource>)
+    }
+}
+
+/// State shared between a [`StreamedSample`] and the decoder thread.
+struct StreamShared {
+    /// Interleaved frames at the engine's sample rate.
+    ring: Mutex<VecDeque<f32>>,
+    /// The ring's capacity in samples.
+    capacity: usize,
+    /// The most recently requested seek position.
+    seek_frame: AtomicU64,
+    /// Incremented for each seek request.
+    requested: AtomicU32,
+    /// The last seek request the decoder has applied.
+    acknowledged: AtomicU32,
+    shutdown: AtomicBool,
+}
+
+impl StreamShared {
+    fn request_seek(&self, frame: u64) {
+        self.seek_frame.store(frame, Ordering::Relaxed);
+        self.requested.fetch_add(1, Ordering::Release);
+    }
+
+    fn seek_pending(&self) -> bool {
+        self.requested.load(Ordering::Acquire) != self.acknowledged.load(Ordering::Acquire)
+    }
+}
+
+/// A sample resource that reads from the decoder thread's ring buffer.
+///
+/// The audio thread never blocks on the decoder. If the ring buffer
+/// can't provide the requested frames, the output is silent and
+/// the missed frames are skipped once the decoder catches up.
+struct StreamedSample {
+    source: Arc<StreamSource>,
+    shared: Arc<StreamShared>,
+    start_frame: u64,
+    looping: bool,
+    /// The next frame the sampler is expected to read.
+    expected: AtomicU64,
+    /// Frames missed during underruns, to be discarded from the ring buffer.
+    missed: AtomicU64,
+}
+
+impl StreamedSample {
+    /// Map a frame relative to this resource onto the source stream.
+    fn source_frame(&self, frame: u64) -> u64 {
+        (self.start_frame + frame) % self.source.len_frames.max(1)
+    }
+}
+
+impl Drop for StreamedSample {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::Relaxed);
+    }
+}
+
+impl SampleResource for StreamedSample {
+    fn num_channels(&self) -> NonZeroUsize {
+        self.source.channels
+    }
+
+    fn len_frames(&self) -> u64 {
+        if self.looping {
+            self.source.len_frames
+        } else {
+            self.source.len_frames - self.start_frame
+        }
+    }
+
+    fn fill_buffers(
+        &self,
+        buffers: &mut [&mut [f32]],
+        buffer_range: Range<usize>,
+        start_frame: u64,
+    ) {
+        let frames = buffer_range.len();
+        let expected = self.expected.load(Ordering::Relaxed);
+        self.expected
+            .store(start_frame + frames as u64, Ordering::Relaxed);
+
+        // Reads that don't continue where the last one left off are seeks.
+        // Wrapping to the start of a looping sample is continuous, however.
+        let len_frames = self.len_frames().max(1);
+        if start_frame % len_frames != expected % len_frames {
+            self.shared.request_seek(self.source_frame(start_frame));
+            self.missed.store(0, Ordering::Relaxed);
+        }
+
+        let silence = |buffers: &mut [&mut [f32]]| {
+            for channel in buffers.iter_mut() {
+                channel[buffer_range.clone()].fill(0.0);
+            }
+        };
+
+        if self.shared.seek_pending() {
+            silence(buffers);
+            self.missed.fetch_add(frames as u64, Ordering::Relaxed);
+            return;
+        }
+
+        let channels = self.source.channels.get();
+        let Ok(mut ring) = self.shared.ring.try_lock() else {
+            silence(buffers);
+            self.missed.fetch_add(frames as u64, Ordering::Relaxed);
+            return;
+        };
+
+        let missed = self.missed.load(Ordering::Relaxed);
+        let discard = (missed as usize).min(ring.len() / channels);
+        ring.drain(..discard * channels);
+        self.missed
+            .store(missed - discard as u64, Ordering::Relaxed);
+
+        if missed > discard as u64 || ring.len() < frames * channels {
+            drop(ring);
+            silence(buffers);
+            self.missed.fetch_add(frames as u64, Ordering::Relaxed);
+            return;
+        }
+
+        for frame in buffer_range.clone() {
+            for channel in 0..channels {
+                let value = ring.pop_front().unwrap_or(0.0);
+
+                if let Some(buffer) = buffers.get_mut(channel) {
+                    buffer[frame] = value;
+                }
+            }
+        }
+
+        drop(ring);
+
+        for channel in buffers.iter_mut().skip(channels) {
+            channel[buffer_range.clone()].fill(0.0);
+        }
+    }
+}
+
+pub(super) fn open_format(
+    bytes: &Arc<[u8]>,
+    extension: Option<&str>,
+) -> Result<(Box<dyn FormatReader>, u32), SampleLoaderError> {
+    let stream = MediaSourceStream::new(Box::new(Cursor::new(bytes.clone())), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(extension) = extension {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        stream,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+
+    let track_id = probed
+        .format
+        .default_track()
+        .map(|t| t.id)
+        .ok_or_else(|| SampleLoaderError::Symphonia("no default track".into()))?;
+
+    Ok((probed.format, track_id))
+}
+
+/// Decodes and resamples a stream, producing exactly
+/// [`StreamSource::len_frames`] frames per pass.
+struct StreamDecoder {
+    source: Arc<StreamSource>,
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    resampler: LinearResampler,
+    /// Source frames to discard after an inexact seek.
+    skip: u64,
+    /// The output position within the current pass.
+    position: u64,
+    /// Whether the current pass has run out of packets.
+    exhausted: bool,
+}
+
+impl StreamDecoder {
+    fn new(source: Arc<StreamSource>) -> Result<Self, SampleLoaderError> {
+        let (format, track_id) = open_format(&source.bytes, source.extension.as_deref())?;
+        let params = format
+            .tracks()
+            .iter()
+            .find(|t| t.id == track_id)
+            .map(|t| t.codec_params.clone())
+            .ok_or_else(|| SampleLoaderError::Symphonia("missing track".into()))?;
+
+        let decoder = symphonia::default::get_codecs().make(&params, &DecoderOptions::default())?;
+
+        Ok(Self {
+            resampler: LinearResampler::new(
+                source.source_rate as f64 / source.sample_rate.get() as f64,
+                source.channels.get(),
+            ),
+            source,
+            format,
+            decoder,
+            track_id,
+            skip: 0,
+            position: 0,
+            exhausted: false,
+        })
+    }
+
+    /// Seek to `frame` at the engine's sample rate.
+    fn seek(&mut self, frame: u64) -> Result<(), SampleLoaderError> {
+        let source_frame = (frame as f64 * self.source.source_rate as f64
+            / self.source.sample_rate.get() as f64) as u64;
+
+        let seeked = self.format.seek(
+            SeekMode::Accurate,
+            SeekTo::TimeStamp {
+                ts: source_frame,
+                track_id: self.track_id,
+            },
+        )?;
+
+        self.decoder.reset();
+        self.resampler.reset();
+        self.skip = seeked.required_ts.saturating_sub(seeked.actual_ts);
+        self.position = frame;
+        self.exhausted = false;
+
+        Ok(())
+    }
+
+    /// Append the next chunk of interleaved frames to `output`.
+    fn next_chunk(&mut self, output: &mut Vec<f32>) -> Result<(), SampleLoaderError> {
+        let channels = self.source.channels.get();
+        let len_frames = self.source.len_frames;
+
+        if self.position >= len_frames {
+            return self.seek(0);
+        }
+
+        let start = output.len();
+
+        if self.exhausted {
+            // Pad out any rounding difference at the end of the pass.
+            let remaining = (len_frames - self.position).min(1024);
+            output.resize(start + remaining as usize * channels, 0.0);
+        } else {
+            match self.format.next_packet() {
+                Ok(packet) => {
+                    if packet.track_id() != self.track_id {
+                        return Ok(());
+                    }
+
+                    let decoded = match self.decoder.decode(&packet) {
+                        Ok(decoded) => decoded,
+                        // Corrupt packets are skipped.
+                        Err(SymphoniaError::DecodeError(_)) => return Ok(()),
+                        Err(e) => return Err(e.into()),
+                    };
+
+                    let mut buffer =
+                        SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+                    buffer.copy_interleaved_ref(decoded);
+
+                    let samples = buffer.samples();
+                    let skip = (self.skip as usize * channels).min(samples.len());
+                    self.skip -= (skip / channels) as u64;
+
+                    self.resampler.process(&samples[skip..], output);
+                }
+                Err(SymphoniaError::IoError(e))
+                    if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    self.exhausted = true;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        let produced = ((output.len() - start) / channels) as u64;
+        let allowed = produced.min(len_frames - self.position);
+        output.truncate(start + allowed as usize * channels);
+        self.position += allowed;
+
+        if self.source.gain != 1.0 {
+            for sample in &mut output[start..] {
+                *sample *= self.source.gain;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A stream handed to the decoder thread.
+struct StreamJob {
+    source: Arc<StreamSource>,
+    shared: Arc<StreamShared>,
+}
+
+/// The result of servicing a stream once.
+enum Progress {
+    /// The stream was decoded or seeked.
+    Decoded,
+    /// The stream's ring buffer is full.
+    Idle,
+    /// The stream's resource was dropped.
+    Closed,
+}
+
+/// A stream being decoded.
+struct ActiveStream {
+    decoder: StreamDecoder,
+    shared: Arc<StreamShared>,
+}
+
+impl ActiveStream {
+    fn service(&mut self, chunk: &mut Vec<f32>) -> Result<Progress, SampleLoaderError> {
+        let shared = &self.shared;
+
+        if shared.shutdown.load(Ordering::Relaxed) {
+            return Ok(Progress::Closed);
+        }
+
+        let requested = shared.requested.load(Ordering::Acquire);
+        if requested != shared.acknowledged.load(Ordering::Acquire) {
+            let frame = shared.seek_frame.load(Ordering::Relaxed);
+            self.decoder.seek(frame)?;
+
+            let mut ring = shared.ring.lock().unwrap_or_else(|e| e.into_inner());
+            ring.clear();
+            shared.acknowledged.store(requested, Ordering::Release);
+
+            return Ok(Progress::Decoded);
+        }
+
+        let buffered = shared
+            .ring
+            .lock()
+            .map(|r| r.len())
+            .unwrap_or_else(|e| e.into_inner().len());
+
+        if buffered >= shared.capacity {
+            return Ok(Progress::Idle);
+        }
+
+        chunk.clear();
+        self.decoder.next_chunk(chunk)?;
+
+        let mut ring = shared.ring.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Discard the chunk if a seek arrived while decoding.
+        if shared.requested.load(Ordering::Acquire) == requested {
+            ring.extend(chunk.iter().copied());
+        }
+
+        Ok(Progress::Decoded)
+    }
+}
+
+/// The decoder thread shared by every stream, spawned on first use.
+fn decoder_thread() -> Option<&'static mpsc::Sender<StreamJob>> {
+    static DECODER: OnceLock<Option<mpsc::Sender<StreamJob>>> = OnceLock::new();
+
+    DECODER
+        .get_or_init(|| {
+            let (sender, receiver) = mpsc::channel();
+            let spawned = std::thread::Builder::new()
+                .name("seedling stream".into())
+                .spawn(move || run_decoder(receiver));
+
+            match spawned {
+                Ok(_) => Some(sender),
+                Err(e) => {
+                    warn!("failed to spawn stream decoder thread: {e}");
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+fn run_decoder(jobs: mpsc::Receiver<StreamJob>) {
+    let open = |job: StreamJob| match StreamDecoder::new(job.source) {
+        Ok(decoder) => Some(ActiveStream {
+            decoder,
+            shared: job.shared,
+        }),
+        Err(e) => {
+            warn!("failed to open stream: {e}");
+            None
+        }
+    };
+
+    let mut streams = Vec::new();
+    let mut chunk = Vec::new();
+
+    loop {
+        // Wait for work rather than spinning while nothing is playing.
+        if streams.is_empty() {
+            match jobs.recv() {
+                Ok(job) => streams.extend(open(job)),
+                Err(_) => return,
+            }
+        }
+
+        streams.extend(jobs.try_iter().filter_map(open));
+
+        let mut decoded = false;
+        streams.retain_mut(|stream| match stream.service(&mut chunk) {
+            Ok(Progress::Decoded) => {
+                decoded = true;
+                true
+            }
+            Ok(Progress::Idle) => true,
+            Ok(Progress::Closed) => false,
+            Err(e) => {
+                warn!("failed to decode stream: {e}");
+                false
+            }
+        });
+
+        if !decoded {
+            std::thread::sleep(IDLE_SLEEP);
+        }
+    }
+}
+
+/// A streaming linear-interpolation resampler for interleaved frames.
+struct LinearResampler {
+    /// Source frames per output frame.
+    ratio: f64,
+    channels: usize,
+    /// The next output position relative to `previous`.
+    position: f64,
+    previous: Option<Vec<f32>>,
+}
+
+impl LinearResampler {
+    fn new(ratio: f64, channels: usize) -> Self {
+        Self {
+            ratio,
+            channels,
+            position: 0.0,
+            previous: None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.position = 0.0;
+        self.previous = None;
+    }
+
+    fn process(&mut self, input: &[f32], output: &mut Vec<f32>) {
+        let channels = self.channels;
+
+        if self.ratio == 1.0 {
+            output.extend_from_slice(input);
+            return;
+        }
+
+        let mut input = input;
+        let previous = match self.previous.take() {
+            Some(previous) => previous,
+            None => {
+                if input.len() < channels {
+                    return;
+                }
+
+                let (first, rest) = input.split_at(channels);
+                input = rest;
+                first.to_vec()
+            }
+        };
+
+        let frames = input.len() / channels;
+        let frame = |index: usize| -> &[f32] {
+            if index == 0 {
+                &previous
+            } else {
+                &input[(index - 1) * channels..index * channels]
+            }
+        };
+
+        while self.position + 1.0 <= frames as f64 {
+            let index = self.position as usize;
+            let fract = (self.position - index as f64) as f32;
+            let (a, b) = (frame(index), frame(index + 1));
+
+            for channel in 0..channels {
+                output.push(a[channel] + (b[channel] - a[channel]) * fract);
+            }
+
+            self.position += self.ratio;
+        }
+
+        self.position -= frames as f64;
+        self.previous = Some(frame(frames).to_vec());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_linear_resampler() {
+        let mut resampler = LinearResampler::new(0.5, 1);
+        let mut output = Vec::new();
+
+        resampler.process(&[0.0, 1.0], &mut output);
+        resampler.process(&[2.0, 3.0], &mut output);
+
+        assert_eq!(output, [0.0, 0.5, 1.0, 1.5, 2.0, 2.5]);
+    }
+}
@@ -0,0 +1,230 @@
+//! Distance-based attenuation for spatial emitters.
+
+use bevy_ecs::prelude::*;
+use bevy_math::curve::Curve;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use std::sync::Arc;
+
+/// Per-emitter distance attenuation.
+///
+/// By default, [`SpatialBasicNode`][firewheel::nodes::spatial_basic::SpatialBasicNode]
+/// halves an emitter's amplitude at 10 units and for each doubling in distance
+/// thereafter. Inserting [`SpatialAttenuation`] on an emitter replaces this with
+/// the given [`Rolloff`] model. The resulting gain scales the node's `volume`,
+/// and the node's own distance damping is disabled while the component is present.
+/// Both are restored when it's removed.
+///
+/// Distances are measured after applying the [`SpatialScale`][super::SpatialScale].
+/// Within [`min_distance`][Self::min_distance], emitters play at full volume,
+/// and beyond [`max_distance`][Self::max_distance], they stop getting quieter.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// fn spawn_fire(mut commands: Commands, server: Res<AssetServer>) {
+///     commands
+///         .spawn((
+///             SamplePlayer::new(server.load("crackle.wav")),
+///             Transform::default(),
+///             SpatialAttenuation {
+///                 min_distance: 2.0,
+///                 max_distance: 30.0,
+///                 rolloff: Rolloff::Linear,
+///                 air_absorption: Some(AirAbsorption::default()),
+///             },
+///         ))
+///         // Air absorption drives this filter's cutoff.
+///         .effect(LowPassNode::default())
+///         .effect(SpatialBasicNode::default());
+/// }
+/// ```
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct SpatialAttenuation {
+    /// The distance within which no attenuation is applied.
+    pub min_distance: f32,
+    /// The distance beyond which attenuation stops increasing.
+    pub max_distance: f32,
+    /// How the amplitude falls between the minimum and maximum distance.
+    pub rolloff: Rolloff,
+    /// Optional distance-based high-frequency damping.
+    pub air_absorption: Option<AirAbsorption>,
+}
+
+impl Default for SpatialAttenuation {
+    /// Matches [`SpatialBasicNode`][firewheel::nodes::spatial_basic::SpatialBasicNode]'s
+    /// built-in attenuation.
+    fn default() -> Self {
+        Self {
+            min_distance: 5.0,
+            max_distance: f32::INFINITY,
+            rolloff: Rolloff::Inverse(1.0),
+            air_absorption: None,
+        }
+    }
+}
+
+impl SpatialAttenuation {
+    /// Compute the linear gain at a distance.
+    pub fn gain(&self, distance: f32) -> f32 {
+        let min = self.min_distance.max(f32::EPSILON);
+        let max = self.max_distance.max(min);
+        let distance = distance.clamp(min, max);
+
+        let gain = match &self.rolloff {
+            Rolloff::None => 1.0,
+            Rolloff::Inverse(factor) => min / (min + factor * (distance - min)),
+            Rolloff::Linear => {
+                if max.is_finite() && max > min {
+                    1.0 - (distance - min) / (max - min)
+                } else {
+                    1.0
+                }
+            }
+            Rolloff::Exponential(factor) => (distance / min).powf(-factor),
+            Rolloff::Custom(curve) => curve.0.sample_clamped(distance),
+        };
+
+        gain.max(0.0)
+    }
+}
+
+/// A distance rolloff model for [`SpatialAttenuation`].
+///
+/// Distances are clamped to the attenuation's minimum and maximum.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Default)]
+pub enum Rolloff {
+    /// No distance attenuation.
+    None,
+    /// Amplitude falls inversely with distance, scaled by a rolloff factor.
+    ///
+    /// With a factor of one, the amplitude halves each time the
+    /// distance doubles beyond the minimum distance.
+    Inverse(f32),
+    /// Amplitude falls linearly, reaching silence at the maximum distance.
+    Linear,
+    /// Amplitude falls with the distance raised to the power of a rolloff factor.
+    Exponential(f32),
+    /// A custom curve mapping distance to linear amplitude.
+    Custom(AttenuationCurve),
+}
+
+impl Default for Rolloff {
+    fn default() -> Self {
+        Self::Inverse(1.0)
+    }
+}
+
+/// A shared curve mapping distance to linear amplitude.
+///
+/// The curve is sampled with clamping, so distances outside
+/// its domain take the value at the nearest end.
+///
+/// ```
+/// # use bevy::math::curve::{Curve, EaseFunction, EasingCurve, Interval};
+/// # use bevy_seedling::prelude::*;
+/// let rolloff = Rolloff::Custom(AttenuationCurve::new(
+///     EasingCurve::new(1.0, 0.0, EaseFunction::QuadraticOut)
+///         .reparametrize_linear(Interval::new(2.0, 40.0).unwrap())
+///         .unwrap(),
+/// ));
+/// ```
+#[derive(Clone, Reflect)]
+#[reflect(opaque)]
+pub struct AttenuationCurve(Arc<dyn Curve<f32> + Send + Sync>);
+
+impl AttenuationCurve {
+    /// Construct a new [`AttenuationCurve`].
+    pub fn new(curve: impl Curve<f32> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(curve))
+    }
+}
+
+impl core::fmt::Debug for AttenuationCurve {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("AttenuationCurve")
+            .field(&self.0.domain())
+            .finish()
+    }
+}
+
+/// Distance-based low-pass filtering, simulating the air's
+/// absorption of high frequencies.
+///
+/// Air absorption drives the cutoff frequency of a
+/// [`LowPassNode`][crate::prelude::LowPassNode] on the same emitter.
+/// The cutoff starts at [`max_cutoff`][Self::max_cutoff] at the
+/// attenuation's minimum distance and falls by an octave every
+/// [`octave_distance`][Self::octave_distance] units.
+#[derive(Debug, Clone, Reflect)]
+#[reflect(Default)]
+pub struct AirAbsorption {
+    /// The cutoff frequency in hertz at the minimum distance.
+    pub max_cutoff: f32,
+    /// The lowest cutoff frequency in hertz.
+    pub min_cutoff: f32,
+    /// The distance over which the cutoff falls by one octave.
+    pub octave_distance: f32,
+}
+
+impl Default for AirAbsorption {
+    fn default() -> Self {
+        Self {
+            max_cutoff: 20_000.0,
+            min_cutoff: 500.0,
+            octave_distance: 50.0,
+        }
+    }
+}
+
+impl AirAbsorption {
+    /// Compute the cutoff frequency at a distance beyond the minimum distance.
+    pub fn cutoff(&self, distance: f32) -> f32 {
+        let octaves = distance.max(0.0) / self.octave_distance.max(f32::EPSILON);
+        let cutoff = self.max_cutoff * 2f32.powf(-octaves);
+
+        cutoff.max(self.min_cutoff)
+    }
+}
+
+/// Whether two cutoff frequencies differ enough to be worth updating.
+pub(super) fn cutoff_changed(old: f32, new: f32) -> bool {
+    // One percent is well below an audible change in cutoff.
+    (old - new).abs() > old.abs() * 0.01
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rolloff() {
+        let default = SpatialAttenuation::default();
+        assert_eq!(default.gain(1.0), 1.0);
+        assert!((default.gain(10.0) - 0.5).abs() < 1e-6);
+        assert!((default.gain(20.0) - 0.25).abs() < 1e-6);
+
+        let linear = SpatialAttenuation {
+            min_distance: 1.0,
+            max_distance: 11.0,
+            rolloff: Rolloff::Linear,
+            air_absorption: None,
+        };
+        assert!((linear.gain(6.0) - 0.5).abs() < 1e-6);
+        assert_eq!(linear.gain(20.0), 0.0);
+
+        let exponential = SpatialAttenuation {
+            min_distance: 1.0,
+            max_distance: f32::INFINITY,
+            rolloff: Rolloff::Exponential(2.0),
+            air_absorption: None,
+        };
+        assert!((exponential.gain(2.0) - 0.25).abs() < 1e-6);
+
+        let absorption = AirAbsorption::default();
+        assert_eq!(absorption.cutoff(0.0), 20_000.0);
+        assert!((absorption.cutoff(50.0) - 10_000.0).abs() < 1e-2);
+        assert_eq!(absorption.cutoff(1e6), 500.0);
+    }
+}
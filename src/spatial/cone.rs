@@ -0,0 +1,149 @@
+//! Directional emitters.
+
+use bevy_ecs::prelude::*;
+use bevy_math::Vec3;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+
+/// A directional sound cone for spatial emitters.
+///
+/// Within [`inner_angle`][Self::inner_angle] of the emitter's forward
+/// direction, listeners hear the emitter normally. Beyond
+/// [`outer_angle`][Self::outer_angle], the emitter is attenuated by
+/// [`outer_gain`][Self::outer_gain] and optionally low-pass filtered. In
+/// between, the two are smoothly interpolated.
+///
+/// The gain scales the emitter's
+/// [`SpatialBasicNode`][firewheel::nodes::spatial_basic::SpatialBasicNode] volume,
+/// while the filtering drives the cutoff of a
+/// [`LowPassNode`][crate::prelude::LowPassNode] on the same emitter.
+/// Both combine with [`SpatialAttenuation`][super::SpatialAttenuation], where
+/// the lower of the cone's and the air absorption's cutoffs is used. The
+/// volume and cutoff are restored when the cone is removed.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// fn spawn_speaker(mut commands: Commands, server: Res<AssetServer>) {
+///     commands
+///         .spawn((
+///             SamplePlayer::new(server.load("announcement.wav")),
+///             Transform::default().looking_to(Vec3::X, Vec3::Y),
+///             EmitterCone {
+///                 outer_gain: 0.25,
+///                 outer_cutoff: Some(2000.0),
+///                 ..Default::default()
+///             },
+///         ))
+///         .effect(LowPassNode::new(20_000.0))
+///         .effect(SpatialBasicNode::default());
+/// }
+/// ```
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct EmitterCone {
+    /// The full angle in radians of the cone within
+    /// which the emitter is unaffected.
+    pub inner_angle: f32,
+    /// The full angle in radians of the cone beyond
+    /// which the emitter is fully attenuated.
+    pub outer_angle: f32,
+    /// The linear gain applied outside the outer cone.
+    pub outer_gain: f32,
+    /// The low-pass cutoff frequency in hertz outside the outer cone.
+    ///
+    /// If `None`, no filtering is applied.
+    pub outer_cutoff: Option<f32>,
+    /// The emitter's forward direction in its local space.
+    ///
+    /// Defaults to [`Vec3::NEG_Z`]. In 2D, you may prefer [`Vec3::Y`].
+    pub forward: Vec3,
+}
+
+impl Default for EmitterCone {
+    fn default() -> Self {
+        Self {
+            inner_angle: core::f32::consts::FRAC_PI_2,
+            outer_angle: core::f32::consts::PI * 1.5,
+            outer_gain: 0.5,
+            outer_cutoff: None,
+            forward: Vec3::NEG_Z,
+        }
+    }
+}
+
+/// The cutoff frequency treated as unfiltered.
+const OPEN_CUTOFF: f32 = 20_000.0;
+
+impl EmitterCone {
+    /// How far outside the inner cone a listener is, from
+    /// zero within the inner cone to one beyond the outer cone.
+    ///
+    /// `forward` is the emitter's world-space forward
+    /// direction, and `to_listener` points from the emitter
+    /// to the listener.
+    pub fn outside(&self, forward: Vec3, to_listener: Vec3) -> f32 {
+        let (Some(forward), Some(to_listener)) =
+            (forward.try_normalize(), to_listener.try_normalize())
+        else {
+            return 0.0;
+        };
+
+        let angle = forward.dot(to_listener).clamp(-1.0, 1.0).acos();
+        let inner = self.inner_angle.max(0.0) * 0.5;
+        let outer = (self.outer_angle * 0.5).max(inner);
+
+        if angle <= inner {
+            0.0
+        } else if angle >= outer {
+            1.0
+        } else {
+            (angle - inner) / (outer - inner)
+        }
+    }
+
+    /// Compute the linear gain for a value returned by [`EmitterCone::outside`].
+    pub fn gain(&self, outside: f32) -> f32 {
+        1.0 + (self.outer_gain - 1.0) * outside
+    }
+
+    /// Compute the cutoff frequency for a value returned by [`EmitterCone::outside`].
+    pub fn cutoff(&self, outside: f32) -> Option<f32> {
+        self.outer_cutoff.map(|outer| {
+            let outer = outer.clamp(1.0, OPEN_CUTOFF);
+
+            // Interpolating in octaves sounds more even than in hertz.
+            OPEN_CUTOFF * (outer / OPEN_CUTOFF).powf(outside)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cone() {
+        let cone = EmitterCone {
+            outer_cutoff: Some(1250.0),
+            ..Default::default()
+        };
+        let forward = Vec3::NEG_Z;
+
+        // In front, the emitter is unaffected.
+        let front = cone.outside(forward, Vec3::NEG_Z);
+        assert_eq!(front, 0.0);
+        assert_eq!(cone.gain(front), 1.0);
+        assert_eq!(cone.cutoff(front), Some(OPEN_CUTOFF));
+
+        // Directly behind, it's fully attenuated.
+        let behind = cone.outside(forward, Vec3::Z);
+        assert_eq!(behind, 1.0);
+        assert_eq!(cone.gain(behind), 0.5);
+        assert!((cone.cutoff(behind).unwrap() - 1250.0).abs() < 1e-2);
+
+        // To the side, it's halfway between the inner and outer cones.
+        let side = cone.outside(forward, Vec3::X);
+        assert!((side - 0.5).abs() < 1e-5, "{side}");
+        assert!((cone.cutoff(side).unwrap() - 5000.0).abs() < 1.0);
+    }
+}
@@ -0,0 +1,178 @@
+//! Doppler shift for moving emitters and listeners.
+
+use crate::sample::PlaybackSpeed;
+use bevy_ecs::prelude::*;
+use bevy_math::Vec3;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_time::Time;
+use bevy_transform::components::GlobalTransform;
+
+/// Applies a Doppler shift to a spatial emitter's sample.
+///
+/// As an emitter and its listener approach each other, the sample's
+/// pitch rises, and as they move apart, it falls. The shift is applied
+/// on top of the emitter's [`PlaybackSpeed`], so the emitter needs one
+/// to be shifted. Without a [`PlaybackSpeed`], the sample plays at its
+/// normal speed. Like other speed changes, the shift isn't
+/// supported for streamed samples.
+///
+/// Velocities are estimated from the movement of each entity's
+/// [`GlobalTransform`]. To provide them directly, such as from
+/// a physics engine, insert [`SpatialVelocity`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// fn spawn_car(mut commands: Commands, server: Res<AssetServer>) {
+///     commands
+///         .spawn((
+///             SamplePlayer::new(server.load("engine.wav")),
+///             PlaybackSettings::LOOP,
+///             PlaybackSpeed::default(),
+///             Transform::default(),
+///             Doppler::default(),
+///         ))
+///         .effect(SpatialBasicNode::default());
+/// }
+/// ```
+#[derive(Debug, Clone, Component, Reflect)]
+#[reflect(Component, Default)]
+#[require(TrackedVelocity, DopplerFactor)]
+pub struct Doppler {
+    /// Scales the relative velocity, where `0.0` disables the
+    /// effect and `1.0` is physically accurate.
+    pub scale: f32,
+    /// The speed of sound in units per second.
+    ///
+    /// Like distances, velocities are multiplied by the
+    /// [`SpatialScale`][super::SpatialScale] first, so when one scaled
+    /// unit corresponds to one meter, the default of `343.0` is accurate.
+    pub speed_of_sound: f32,
+}
+
+impl Default for Doppler {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            speed_of_sound: 343.0,
+        }
+    }
+}
+
+impl Doppler {
+    /// Compute the playback speed factor.
+    ///
+    /// `direction` points from the listener to the emitter, and
+    /// the velocities should already be spatially scaled.
+    pub fn factor(&self, direction: Vec3, listener_velocity: Vec3, emitter_velocity: Vec3) -> f32 {
+        let Some(direction) = direction.try_normalize() else {
+            return 1.0;
+        };
+
+        let c = self.speed_of_sound.max(f32::EPSILON);
+        // Speeds at or beyond the speed of sound have no meaningful shift.
+        let limit = c * 0.9;
+
+        let listener = (listener_velocity.dot(direction) * self.scale).clamp(-limit, limit);
+        let emitter = (emitter_velocity.dot(direction) * self.scale).clamp(-limit, limit);
+
+        (c + listener) / (c + emitter)
+    }
+}
+
+/// An explicit velocity for spatial listeners and emitters.
+///
+/// When present, this is used for [`Doppler`] calculations
+/// rather than estimating the velocity from the entity's
+/// [`GlobalTransform`].
+#[derive(Debug, Default, Clone, Copy, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct SpatialVelocity(pub Vec3);
+
+/// A velocity estimated from successive [`GlobalTransform`]s.
+#[derive(Debug, Default, Component)]
+pub(crate) struct TrackedVelocity {
+    previous: Option<Vec3>,
+    velocity: Vec3,
+}
+
+impl TrackedVelocity {
+    /// Resolve an entity's velocity, preferring an explicit [`SpatialVelocity`].
+    pub fn resolve(explicit: Option<&SpatialVelocity>, tracked: Option<&Self>) -> Vec3 {
+        match (explicit, tracked) {
+            (Some(explicit), _) => explicit.0,
+            (None, Some(tracked)) => tracked.velocity,
+            (None, None) => Vec3::ZERO,
+        }
+    }
+}
+
+/// The current Doppler shift of an emitter, applied to its sampler's speed.
+///
+/// This only takes effect for samples that play with a [`PlaybackSpeed`].
+#[derive(Debug, Component, Clone, Copy, PartialEq)]
+pub(crate) struct DopplerFactor(pub f32);
+
+impl Default for DopplerFactor {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+pub(crate) fn track_velocities(
+    mut entities: Query<(&mut TrackedVelocity, &GlobalTransform), Without<SpatialVelocity>>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_secs();
+
+    for (mut tracked, transform) in entities.iter_mut() {
+        let position = transform.translation();
+
+        tracked.velocity = match tracked.previous {
+            Some(previous) if delta > 0.0 => (position - previous) / delta,
+            _ => Vec3::ZERO,
+        };
+        tracked.previous = Some(position);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_doppler_factor() {
+        let doppler = Doppler::default();
+        let direction = Vec3::X * 10.0;
+
+        // An emitter approaching at a tenth of the speed of sound.
+        let approaching = doppler.factor(direction, Vec3::ZERO, Vec3::NEG_X * 34.3);
+        assert!((approaching - 1.0 / 0.9).abs() < 1e-4, "{approaching}");
+
+        // A listener moving away at the same speed.
+        let receding = doppler.factor(direction, Vec3::NEG_X * 34.3, Vec3::ZERO);
+        assert!((receding - 0.9).abs() < 1e-4, "{receding}");
+
+        // Perpendicular motion produces no shift.
+        assert_eq!(doppler.factor(direction, Vec3::ZERO, Vec3::Y * 50.0), 1.0);
+
+        let disabled = Doppler {
+            scale: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            disabled.factor(direction, Vec3::ZERO, Vec3::NEG_X * 50.0),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_speed_not_required() {
+        let mut world = World::new();
+        let emitter = world.spawn(Doppler::default()).id();
+
+        let emitter = world.entity(emitter);
+        assert!(emitter.contains::<DopplerFactor>());
+        assert!(!emitter.contains::<PlaybackSpeed>());
+    }
+}
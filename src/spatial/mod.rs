@@ -0,0 +1,1073 @@
+//! Spatial audio components.
+//!
+//! To enable spatial audio, three conditions are required:
+//!
+//! 1. The spatial audio node, [`SpatialBasicNode`], must have
+//!    a transform.
+//! 2. The spatial listener entity must have a [`SpatialListener2D`]
+//!    or [`SpatialListener3D`].
+//! 3. The spatial listener entity must have a transform.
+//!
+//! Typically, you'll want to include a [`SpatialBasicNode`] as an effect.
+//!
+//! ```
+//! # use bevy_seedling::prelude::*;
+//! # use bevy::prelude::*;
+//! fn spawn_spatial(mut commands: Commands, server: Res<AssetServer>) {
+//!     // Spawn a player with a transform (1).
+//!     commands
+//!         .spawn((
+//!             SamplePlayer::new(server.load("my_sample.wav")),
+//!             Transform::default(),
+//!         ))
+//!         .effect(SpatialBasicNode::default());
+//!
+//!     // Then, spawn a listener (2), which automatically inserts
+//!     // a transform if it doesn't already exist (3).
+//!     commands.spawn(SpatialListener2D::default());
+//! }
+//! ```
+//!
+//! Emitters are positioned relative to the listener's full transform,
+//! so rotating the listener rotates the sound field with it.
+//!
+//! Multiple listeners are supported. By default, `bevy_seedling`
+//! will simply select the closest listener for distance
+//! calculations. For local multiplayer, listeners can instead
+//! be mixed together with [`ListenerMixing::Mix`].
+
+use crate::nodes::lpf::LowPassNode;
+use crate::reflect::{NodeState, SpatialBasicNodeState};
+use bevy_ecs::prelude::*;
+use bevy_math::{Quat, Vec3};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_transform::components::{GlobalTransform, Transform};
+use firewheel::{nodes::spatial_basic::SpatialBasicNode, Volume};
+
+mod attenuation;
+mod cone;
+mod doppler;
+
+pub use attenuation::*;
+pub use cone::EmitterCone;
+pub(crate) use doppler::{track_velocities, DopplerFactor, TrackedVelocity};
+pub use doppler::{Doppler, SpatialVelocity};
+
+/// A scaling factor applied to the distance between spatial listeners and emitters.
+///
+/// To override the [global spatial scaling][DefaultSpatialScale] for an entity,
+/// simply insert [`SpatialScale`].
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// fn set_scale(mut commands: Commands, server: Res<AssetServer>) {
+///     commands
+///         .spawn((
+///             SamplePlayer::new(server.load("my_sample.wav")),
+///             Transform::default(),
+///             SpatialScale(Vec3::splat(0.25)),
+///         ))
+///         .effect(SpatialBasicNode::default());
+/// }
+/// ```
+///
+/// By default, a spatial signal's amplitude will be cut in half at 10 units. Then,
+/// for each doubling in distance, the signal will be successively halved.
+///
+/// | Distance | Amplitude |
+/// | -------- | --------- |
+/// | 10       | -6dB      |
+/// | 20       | -12dB     |
+/// | 40       | -18dB     |
+/// | 80       | -24dB     |
+///
+/// When one unit corresponds to one meter, this is a good default. If
+/// your game's scale differs significantly, however, you may need
+/// to adjust the spatial scaling.
+///
+/// The distance between listeners and emitters is multiplied by this
+/// factor, so if a meter in your game corresponds to more than one unit, you
+/// should provide a spatial scale of less than one to compensate.
+///
+/// To change the attenuation model itself, see [`SpatialAttenuation`].
+#[derive(Component, Debug, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+pub struct SpatialScale(pub Vec3);
+
+impl Default for SpatialScale {
+    fn default() -> Self {
+        Self(Vec3::ONE)
+    }
+}
+
+/// The global default spatial scale.
+///
+/// For more details on spatial scaling, see [`SpatialScale`].
+///
+/// The default scaling is 1 in every direction, [`Vec3::ONE`].
+#[derive(Resource, Debug, Default, Clone, Reflect)]
+#[reflect(Resource, Default)]
+pub struct DefaultSpatialScale(SpatialScale);
+
+impl core::ops::Deref for DefaultSpatialScale {
+    type Target = SpatialScale;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl core::ops::DerefMut for DefaultSpatialScale {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// A 2D spatial listener.
+///
+/// When this component is added to an entity with a transform,
+/// this transform is used to calculate spatial offsets for all
+/// emitters. An emitter is an entity with [`SpatialBasicNode`]
+/// and transform components.
+///
+/// The listener's orientation is determined by its transform's rotation
+/// along with [`forward`][Self::forward] and [`up`][Self::up]. The default
+/// axes suit top-down games, where the listener faces the top of the screen.
+/// For side-scrolling games, the listener should face into the screen.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// # fn system(mut commands: Commands) {
+/// commands.spawn(SpatialListener2D {
+///     forward: Vec3::NEG_Z,
+///     up: Vec3::Y,
+///     ..Default::default()
+/// });
+/// # }
+/// ```
+///
+/// Multiple listeners are supported. How they're combined
+/// is controlled with [`mixing`][Self::mixing].
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+#[reflect(Component, Default)]
+#[require(Transform, TrackedVelocity)]
+pub struct SpatialListener2D {
+    /// The direction the listener faces in its local space.
+    ///
+    /// Defaults to [`Vec3::Y`].
+    pub forward: Vec3,
+    /// The listener's up direction in its local space.
+    ///
+    /// Defaults to [`Vec3::Z`].
+    pub up: Vec3,
+    /// How this listener is combined with others.
+    pub mixing: ListenerMixing,
+}
+
+impl Default for SpatialListener2D {
+    fn default() -> Self {
+        Self {
+            forward: Vec3::Y,
+            up: Vec3::Z,
+            mixing: ListenerMixing::default(),
+        }
+    }
+}
+
+/// A 3D spatial listener.
+///
+/// When this component is added to an entity with a transform,
+/// this transform is used to calculate spatial offsets for all
+/// emitters. An emitter is an entity with [`SpatialBasicNode`]
+/// and transform components.
+///
+/// The listener's orientation is determined by its transform's rotation
+/// along with [`forward`][Self::forward] and [`up`][Self::up]. The default
+/// axes match Bevy's cameras, so a listener attached to a camera
+/// hears what it sees.
+///
+/// Multiple listeners are supported. How they're combined
+/// is controlled with [`mixing`][Self::mixing].
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+#[reflect(Component, Default)]
+#[require(Transform, TrackedVelocity)]
+pub struct SpatialListener3D {
+    /// The direction the listener faces in its local space.
+    ///
+    /// Defaults to [`Vec3::NEG_Z`].
+    pub forward: Vec3,
+    /// The listener's up direction in its local space.
+    ///
+    /// Defaults to [`Vec3::Y`].
+    pub up: Vec3,
+    /// How this listener is combined with others.
+    pub mixing: ListenerMixing,
+}
+
+impl Default for SpatialListener3D {
+    fn default() -> Self {
+        Self {
+            forward: Vec3::NEG_Z,
+            up: Vec3::Y,
+            mixing: ListenerMixing::default(),
+        }
+    }
+}
+
+/// How a spatial listener is combined with other listeners.
+///
+/// Each emitter is heard by every [`Mix`][ListenerMixing::Mix] listener
+/// along with the closest [`Closest`][ListenerMixing::Closest] listener.
+/// With more than one, the emitter's attenuation and panning are computed
+/// for each listener and summed. This suits split-screen games, where each
+/// player should hear their own surroundings.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_seedling::prelude::*;
+/// # fn system(mut commands: Commands) {
+/// for player in 0..2 {
+///     commands.spawn((
+///         SpatialListener3D {
+///             mixing: ListenerMixing::Mix,
+///             ..Default::default()
+///         },
+///         Transform::from_xyz(player as f32 * 20.0, 0.0, 0.0),
+///     ));
+/// }
+/// # }
+/// ```
+///
+/// Each listener's contribution is panned and attenuated as if by its
+/// own [`SpatialBasicNode`], using the emitter's [`SpatialAttenuation`]
+/// or the node's built-in attenuation without one. Since the node's output
+/// is stereo, their sum is reproduced exactly by a single gain and pan, so
+/// an emitter between two listeners is heard from both sides.
+///
+/// Filtering and pitch can't be rendered per listener, however. The
+/// node's damping and any [`LowPassNode`] cutoff follow the listener that
+/// hears the emitter brightest, and the [`Doppler`] shift follows the
+/// nearest listener.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Default)]
+pub enum ListenerMixing {
+    /// The listener is only heard if it's the closest of its kind.
+    #[default]
+    Closest,
+    /// The listener is always heard, mixed with other listeners.
+    Mix,
+}
+
+/// A listener's position and orientation.
+#[derive(Debug, Clone, Copy)]
+struct ListenerFrame {
+    position: Vec3,
+    /// Rotates world-space directions into the listener's local space.
+    inverse_rotation: Quat,
+    right: Vec3,
+    up: Vec3,
+    forward: Vec3,
+    velocity: Vec3,
+    mixing: ListenerMixing,
+}
+
+impl ListenerFrame {
+    fn new(transform: &GlobalTransform, forward: Vec3, up: Vec3) -> Self {
+        let (_, rotation, position) = transform.to_scale_rotation_translation();
+
+        let forward = forward.try_normalize().unwrap_or(Vec3::NEG_Z);
+        let up = (up - forward * up.dot(forward))
+            .try_normalize()
+            .unwrap_or_else(|| forward.any_orthonormal_vector());
+        let right = forward.cross(up);
+
+        Self {
+            position,
+            inverse_rotation: rotation.inverse(),
+            right,
+            up,
+            forward,
+            velocity: Vec3::ZERO,
+            mixing: ListenerMixing::Closest,
+        }
+    }
+
+    fn with_velocity(mut self, velocity: Vec3) -> Self {
+        self.velocity = velocity;
+        self
+    }
+
+    fn with_mixing(mut self, mixing: ListenerMixing) -> Self {
+        self.mixing = mixing;
+        self
+    }
+
+    /// Express a world-space offset from the listener in
+    /// [`SpatialBasicNode`]'s space, where the listener faces -Z
+    /// with +X to its right and +Y above.
+    fn localize(&self, offset: Vec3) -> Vec3 {
+        let local = self.inverse_rotation * offset;
+
+        Vec3::new(
+            local.dot(self.right),
+            local.dot(self.up),
+            -local.dot(self.forward),
+        )
+    }
+}
+
+pub(crate) fn update_2d_emitters(
+    listeners: Query<(
+        &SpatialListener2D,
+        &GlobalTransform,
+        Option<&SpatialVelocity>,
+        Option<&TrackedVelocity>,
+    )>,
+    emitters: EmitterQuery,
+    default_scale: Res<DefaultSpatialScale>,
+) {
+    let listeners = listeners
+        .iter()
+        .map(|(listener, transform, explicit, tracked)| {
+            ListenerFrame::new(transform, listener.forward, listener.up)
+                .with_velocity(TrackedVelocity::resolve(explicit, tracked))
+                .with_mixing(listener.mixing)
+        });
+
+    update_emitters(listeners, emitters, &default_scale);
+}
+
+pub(crate) fn update_3d_emitters(
+    listeners: Query<(
+        &SpatialListener3D,
+        &GlobalTransform,
+        Option<&SpatialVelocity>,
+        Option<&TrackedVelocity>,
+    )>,
+    emitters: EmitterQuery,
+    default_scale: Res<DefaultSpatialScale>,
+) {
+    let listeners = listeners
+        .iter()
+        .map(|(listener, transform, explicit, tracked)| {
+            ListenerFrame::new(transform, listener.forward, listener.up)
+                .with_velocity(TrackedVelocity::resolve(explicit, tracked))
+                .with_mixing(listener.mixing)
+        });
+
+    update_emitters(listeners, emitters, &default_scale);
+}
+
+type EmitterQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static mut SpatialBasicNode,
+        &'static mut EmitterBase,
+        Option<&'static SpatialScale>,
+        &'static GlobalTransform,
+        Option<&'static SpatialAttenuation>,
+        Option<&'static EmitterCone>,
+        Option<&'static mut LowPassNode>,
+        Option<(
+            &'static Doppler,
+            &'static mut DopplerFactor,
+            Option<&'static SpatialVelocity>,
+            Option<&'static TrackedVelocity>,
+        )>,
+    ),
+>;
+
+/// When an emitter is attenuated explicitly, only its direction is
+/// forwarded to [`SpatialBasicNode`] at this distance.
+///
+/// The node's built-in attenuation at this distance is
+/// `10^(-0.03 * 0.001)`, well under a thousandth of a decibel, while
+/// the direction still determines the panning.
+const NEUTRAL_DISTANCE: f32 = 0.001;
+
+/// A [`SpatialBasicNode::damping_distance`] that disables the node's damping.
+const NO_DAMPING: f32 = -1.0;
+
+/// A [`SpatialBasicNode::muffle_cutoff_hz`] that applies no filtering.
+const OPEN_MUFFLE: f32 = 20_480.0;
+
+/// A parameter overridden by the spatial systems.
+#[derive(Debug, Clone, Copy)]
+struct Managed<T> {
+    /// The value set outside the spatial systems.
+    base: T,
+    /// The value last written by the spatial systems.
+    applied: T,
+}
+
+impl<T: Copy + PartialEq> Managed<T> {
+    fn new(current: T) -> Self {
+        Self {
+            base: current,
+            applied: current,
+        }
+    }
+
+    /// Get the base value, adopting the current value
+    /// if it was changed since the last write.
+    fn base(&mut self, current: T) -> T {
+        if current != self.applied {
+            self.base = current;
+        }
+
+        self.base
+    }
+
+    /// The value to restore when the parameter is released.
+    ///
+    /// Changes made since the last write are kept.
+    fn restore(self, current: T) -> T {
+        if current == self.applied {
+            self.base
+        } else {
+            current
+        }
+    }
+}
+
+/// The parameters of an emitter's [`SpatialBasicNode`] and [`LowPassNode`]
+/// as they were before the spatial systems took them over.
+///
+/// This allows attenuation to be applied on top of the user's volume,
+/// and the original values to be restored once [`SpatialAttenuation`],
+/// [`EmitterCone`], or listener mixing no longer apply.
+#[derive(Debug, Default, Component)]
+pub(crate) struct EmitterBase {
+    volume: Option<Managed<Volume>>,
+    damping_distance: Option<Managed<f32>>,
+    muffle_cutoff: Option<Managed<f32>>,
+    cutoff: Option<Managed<f32>>,
+}
+
+/// Capture changes to emitters' [`SpatialBasicNode`]s in their
+/// [`SpatialBasicNodeState`], as they were before attenuation.
+pub(crate) fn sync_emitter_state(
+    mut emitters: Query<
+        (&SpatialBasicNode, &EmitterBase, &mut SpatialBasicNodeState),
+        Changed<SpatialBasicNode>,
+    >,
+) {
+    for (spatial, base, mut state) in emitters.iter_mut() {
+        state.set_if_neq(SpatialBasicNodeState::capture(&base.base_node(spatial)));
+    }
+}
+
+fn update_emitters(
+    listeners: impl Iterator<Item = ListenerFrame>,
+    mut emitters: EmitterQuery,
+    default_scale: &DefaultSpatialScale,
+) {
+    let listeners: Vec<_> = listeners.collect();
+
+    for (mut spatial, mut base, scale, transform, attenuation, cone, low_pass, doppler) in
+        emitters.iter_mut()
+    {
+        let emitter_pos = transform.translation();
+        let heard = heard_listeners(emitter_pos, &listeners);
+
+        let Some(nearest) =
+            find_closest_listener(emitter_pos, heard.iter().copied(), |l| l.position)
+        else {
+            continue;
+        };
+
+        let scale = scale.map(|s| s.0).unwrap_or(default_scale.0 .0);
+
+        if let Some((doppler, mut factor, explicit, tracked)) = doppler {
+            let emitter_velocity = TrackedVelocity::resolve(explicit, tracked);
+            let new = DopplerFactor(doppler.factor(
+                (emitter_pos - nearest.position) * scale,
+                nearest.velocity * scale,
+                emitter_velocity * scale,
+            ));
+
+            factor.set_if_neq(new);
+        }
+
+        let rendered = if let [listener] = heard.as_slice() {
+            Contribution::new(listener, emitter_pos, scale, transform, attenuation, cone)
+        } else {
+            let contributions = heard.iter().map(|listener| {
+                Contribution::new(listener, emitter_pos, scale, transform, attenuation, cone)
+            });
+
+            Contribution::mix(contributions, &base.base_node(&spatial))
+        };
+
+        spatial.offset = rendered.offset;
+        base.apply(&mut spatial, low_pass, &rendered);
+    }
+}
+
+impl EmitterBase {
+    /// The node's parameters as they were set outside the spatial systems.
+    pub(crate) fn base_node(&self, spatial: &SpatialBasicNode) -> SpatialBasicNode {
+        let mut node = *spatial;
+
+        if let Some(volume) = self.volume {
+            node.volume = volume.restore(node.volume);
+        }
+        if let Some(damping) = self.damping_distance {
+            node.damping_distance = damping.restore(node.damping_distance);
+        }
+        if let Some(muffle) = self.muffle_cutoff {
+            node.muffle_cutoff_hz = muffle.restore(node.muffle_cutoff_hz);
+        }
+
+        node
+    }
+
+    /// Apply an emitter's rendered gain and cutoff on top of its base
+    /// parameters, restoring any parameters that are no longer overridden.
+    fn apply(
+        &mut self,
+        spatial: &mut Mut<SpatialBasicNode>,
+        low_pass: Option<Mut<LowPassNode>>,
+        rendered: &Contribution,
+    ) {
+        match rendered.gain {
+            Some(gain) => {
+                let volume = self
+                    .volume
+                    .get_or_insert_with(|| Managed::new(spatial.volume));
+                let new = Volume::Linear(volume.base(spatial.volume).amp() * gain);
+
+                if spatial.volume != new {
+                    spatial.volume = new;
+                }
+                volume.applied = new;
+            }
+            None => {
+                if let Some(volume) = self.volume.take() {
+                    let restored = volume.restore(spatial.volume);
+                    if spatial.volume != restored {
+                        spatial.volume = restored;
+                    }
+                }
+            }
+        }
+
+        // The node's damping depends on its distance, which
+        // isn't meaningful once the offset is neutralized.
+        if rendered.attenuated {
+            let damping = self
+                .damping_distance
+                .get_or_insert_with(|| Managed::new(spatial.damping_distance));
+            damping.base(spatial.damping_distance);
+
+            if spatial.damping_distance != NO_DAMPING {
+                spatial.damping_distance = NO_DAMPING;
+            }
+            damping.applied = NO_DAMPING;
+        } else if let Some(damping) = self.damping_distance.take() {
+            let restored = damping.restore(spatial.damping_distance);
+            if spatial.damping_distance.to_bits() != restored.to_bits() {
+                spatial.damping_distance = restored;
+            }
+        }
+
+        match rendered.muffle_cutoff {
+            Some(muffle) => {
+                let current = spatial.muffle_cutoff_hz;
+                let managed = self
+                    .muffle_cutoff
+                    .get_or_insert_with(|| Managed::new(current));
+                managed.base(current);
+
+                if cutoff_changed(current, muffle) {
+                    spatial.muffle_cutoff_hz = muffle;
+                    managed.applied = muffle;
+                } else {
+                    managed.applied = current;
+                }
+            }
+            None => {
+                if let Some(managed) = self.muffle_cutoff.take() {
+                    let restored = managed.restore(spatial.muffle_cutoff_hz);
+                    if spatial.muffle_cutoff_hz != restored {
+                        spatial.muffle_cutoff_hz = restored;
+                    }
+                }
+            }
+        }
+
+        let Some(mut low_pass) = low_pass else {
+            return;
+        };
+
+        let current = low_pass.frequency.get();
+        match rendered.cutoff {
+            Some(cutoff) => {
+                let managed = self.cutoff.get_or_insert_with(|| Managed::new(current));
+                managed.base(current);
+
+                if cutoff_changed(current, cutoff) {
+                    low_pass.frequency.set(cutoff);
+                    managed.applied = cutoff;
+                } else {
+                    managed.applied = current;
+                }
+            }
+            None => {
+                if let Some(managed) = self.cutoff.take() {
+                    let restored = managed.restore(current);
+                    if restored != current {
+                        low_pass.frequency.set(restored);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A single listener's view of an emitter.
+struct Contribution {
+    offset: Vec3,
+    gain: Option<f32>,
+    cutoff: Option<f32>,
+    /// Whether the distance attenuation is included in
+    /// the gain rather than left to the node.
+    attenuated: bool,
+    /// The node's muffle cutoff, when its damping is computed explicitly.
+    muffle_cutoff: Option<f32>,
+}
+
+impl Contribution {
+    fn new(
+        listener: &ListenerFrame,
+        emitter_pos: Vec3,
+        scale: Vec3,
+        transform: &GlobalTransform,
+        attenuation: Option<&SpatialAttenuation>,
+        cone: Option<&EmitterCone>,
+    ) -> Self {
+        let offset = listener.localize((emitter_pos - listener.position) * scale);
+
+        let mut contribution = match attenuation {
+            Some(attenuation) => {
+                let distance = offset.length();
+
+                Self {
+                    offset: offset.normalize_or_zero() * NEUTRAL_DISTANCE,
+                    gain: Some(attenuation.gain(distance)),
+                    cutoff: attenuation
+                        .air_absorption
+                        .as_ref()
+                        .map(|absorption| absorption.cutoff(distance - attenuation.min_distance)),
+                    attenuated: true,
+                    muffle_cutoff: None,
+                }
+            }
+            None => Self {
+                offset,
+                gain: None,
+                cutoff: None,
+                attenuated: false,
+                muffle_cutoff: None,
+            },
+        };
+
+        if let Some(cone) = cone {
+            let (_, rotation, _) = transform.to_scale_rotation_translation();
+            let outside = cone.outside(rotation * cone.forward, listener.position - emitter_pos);
+
+            contribution.gain = Some(contribution.gain.unwrap_or(1.0) * cone.gain(outside));
+            contribution.cutoff = match (contribution.cutoff, cone.cutoff(outside)) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+
+        contribution
+    }
+
+    /// Combine several listeners' views of an emitter.
+    ///
+    /// Each contribution is panned and attenuated as if by its own
+    /// [`SpatialBasicNode`] with the `base` parameters. The summed
+    /// left and right gains are then expressed as a single gain and
+    /// direction, which the node's equal-power panning reproduces exactly.
+    fn mix(contributions: impl Iterator<Item = Self>, base: &SpatialBasicNode) -> Self {
+        let mut left = 0.0;
+        let mut right = 0.0;
+        let mut cutoff: Option<f32> = None;
+        let mut muffle_cutoff: f32 = 0.0;
+
+        for contribution in contributions {
+            let values = SpatialBasicNode {
+                volume: Volume::Linear(contribution.gain.unwrap_or(1.0)),
+                offset: contribution.offset,
+                damping_distance: if contribution.attenuated {
+                    NO_DAMPING
+                } else {
+                    base.damping_distance
+                },
+                ..*base
+            }
+            .compute_values(0.0);
+
+            left += values.gain_l;
+            right += values.gain_r;
+
+            // The emitter is as bright as it is for the listener that hears it best.
+            muffle_cutoff = muffle_cutoff.max(values.damping_cutoff_hz.unwrap_or(OPEN_MUFFLE));
+            cutoff = match (cutoff, contribution.cutoff) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            };
+        }
+
+        // Invert the node's pan law, where the left and right gains
+        // are the cosine and sine of the pan position.
+        let pan = right.atan2(left) / core::f32::consts::FRAC_PI_2 * 2.0 - 1.0;
+        let threshold = base.panning_threshold.clamp(0.0, 1.0);
+        let x = if threshold > 0.0 {
+            (pan / threshold).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+
+        Self {
+            offset: Vec3::new(x, 0.0, -(1.0 - x * x).max(0.0).sqrt()) * NEUTRAL_DISTANCE,
+            gain: Some(left.hypot(right)),
+            cutoff,
+            attenuated: true,
+            muffle_cutoff: Some(muffle_cutoff),
+        }
+    }
+}
+
+/// Select the listeners that hear an emitter: the closest
+/// [`ListenerMixing::Closest`] listener and every [`ListenerMixing::Mix`] listener.
+fn heard_listeners(emitter_pos: Vec3, listeners: &[ListenerFrame]) -> Vec<&ListenerFrame> {
+    let closest = find_closest_listener(
+        emitter_pos,
+        listeners
+            .iter()
+            .filter(|l| l.mixing == ListenerMixing::Closest),
+        |l| l.position,
+    );
+
+    closest
+        .into_iter()
+        .chain(listeners.iter().filter(|l| l.mixing == ListenerMixing::Mix))
+        .collect()
+}
+
+fn find_closest_listener<T>(
+    emitter_pos: Vec3,
+    listeners: impl Iterator<Item = T>,
+    position: impl Fn(&T) -> Vec3,
+) -> Option<T> {
+    let mut closest_listener: Option<(f32, T)> = None;
+
+    for listener in listeners {
+        let distance = emitter_pos.distance_squared(position(&listener));
+
+        match &mut closest_listener {
+            None => closest_listener = Some((distance, listener)),
+            Some((old_distance, old_listener)) => {
+                if distance < *old_distance {
+                    *old_distance = distance;
+                    *old_listener = listener;
+                }
+            }
+        }
+    }
+
+    closest_listener.map(|l| l.1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::prelude::*;
+
+    fn prepare_app() -> App {
+        let mut app = App::new();
+
+        app.init_resource::<DefaultSpatialScale>()
+            .register_required_components::<SpatialBasicNode, EmitterBase>()
+            .add_systems(Update, update_3d_emitters);
+
+        app.world_mut()
+            .spawn((SpatialListener3D::default(), GlobalTransform::IDENTITY));
+
+        app
+    }
+
+    fn spawn_emitter(app: &mut App, position: Vec3, components: impl Bundle) -> Entity {
+        app.world_mut()
+            .spawn((
+                SpatialBasicNode {
+                    volume: Volume::Linear(0.5),
+                    ..Default::default()
+                },
+                GlobalTransform::from_translation(position),
+                components,
+            ))
+            .id()
+    }
+
+    fn node(app: &App, entity: Entity) -> SpatialBasicNode {
+        *app.world().get::<SpatialBasicNode>(entity).unwrap()
+    }
+
+    #[test]
+    fn test_attenuation_volume() {
+        let mut app = prepare_app();
+        let emitter = spawn_emitter(&mut app, Vec3::X * 20.0, SpatialAttenuation::default());
+
+        app.update();
+
+        // The attenuation scales the emitter's own volume, and the
+        // node's distance attenuation and damping are neutralized.
+        let spatial = node(&app, emitter);
+        assert!((spatial.volume.amp() - 0.125).abs() < 1e-5);
+        assert_eq!(spatial.damping_distance, NO_DAMPING);
+        assert!((spatial.offset.length() - NEUTRAL_DISTANCE).abs() < 1e-6);
+
+        // Changes to the volume become the new base.
+        app.world_mut()
+            .get_mut::<SpatialBasicNode>(emitter)
+            .unwrap()
+            .volume = Volume::Linear(1.0);
+        app.update();
+        assert!((node(&app, emitter).volume.amp() - 0.25).abs() < 1e-5);
+
+        app.world_mut()
+            .entity_mut(emitter)
+            .remove::<SpatialAttenuation>();
+        app.update();
+
+        let spatial = node(&app, emitter);
+        assert_eq!(spatial.volume, Volume::Linear(1.0));
+        assert_eq!(spatial.damping_distance, 100.0);
+        assert_eq!(spatial.offset, Vec3::X * 20.0);
+    }
+
+    #[test]
+    fn test_cone_volume() {
+        let mut app = prepare_app();
+        // In front of the listener, facing away from it.
+        let emitter = spawn_emitter(
+            &mut app,
+            Vec3::NEG_Z * 10.0,
+            (
+                EmitterCone {
+                    outer_cutoff: Some(1000.0),
+                    ..Default::default()
+                },
+                LowPassNode::new(20_000.0),
+            ),
+        );
+
+        app.update();
+
+        // Without attenuation, the node still handles distance.
+        let spatial = node(&app, emitter);
+        assert!((spatial.volume.amp() - 0.25).abs() < 1e-5);
+        assert_eq!(spatial.damping_distance, 100.0);
+        assert_eq!(spatial.offset, Vec3::NEG_Z * 10.0);
+
+        let cutoff = app
+            .world()
+            .get::<LowPassNode>(emitter)
+            .unwrap()
+            .frequency
+            .get();
+        assert!((cutoff - 1000.0).abs() < 1.0, "{cutoff}");
+
+        app.world_mut().entity_mut(emitter).remove::<EmitterCone>();
+        app.update();
+
+        assert_eq!(node(&app, emitter).volume, Volume::Linear(0.5));
+        let cutoff = app
+            .world()
+            .get::<LowPassNode>(emitter)
+            .unwrap()
+            .frequency
+            .get();
+        assert_eq!(cutoff, 20_000.0);
+    }
+
+    #[test]
+    fn test_mixing_volume() {
+        let mut app = prepare_app();
+        let mixed = app
+            .world_mut()
+            .spawn((
+                SpatialListener3D {
+                    mixing: ListenerMixing::Mix,
+                    ..Default::default()
+                },
+                GlobalTransform::from_translation(Vec3::Z * 10.0),
+            ))
+            .id();
+        let emitter = spawn_emitter(&mut app, Vec3::X * 20.0, ());
+
+        app.update();
+
+        let spatial = node(&app, emitter);
+        assert_ne!(spatial.volume, Volume::Linear(0.5));
+        assert_eq!(spatial.damping_distance, NO_DAMPING);
+        assert!(spatial.muffle_cutoff_hz < OPEN_MUFFLE);
+
+        // With a single listener again, the node takes over.
+        app.world_mut().despawn(mixed);
+        app.update();
+
+        let spatial = node(&app, emitter);
+        assert_eq!(spatial.volume, Volume::Linear(0.5));
+        assert_eq!(spatial.damping_distance, 100.0);
+        assert_eq!(spatial.muffle_cutoff_hz, OPEN_MUFFLE);
+        assert_eq!(spatial.offset, Vec3::X * 20.0);
+    }
+
+    #[test]
+    fn test_opposite_listeners() {
+        let mut app = prepare_app();
+        let second = app
+            .world_mut()
+            .spawn((
+                SpatialListener3D {
+                    mixing: ListenerMixing::Mix,
+                    ..Default::default()
+                },
+                GlobalTransform::from_translation(Vec3::X * 20.0),
+            ))
+            .id();
+        // To the right of the first listener and the left of the second.
+        let emitter = spawn_emitter(&mut app, Vec3::X * 10.0, ());
+
+        // The gains of a separate node for each listener.
+        let separate = |offsets: [Vec3; 2]| {
+            offsets
+                .into_iter()
+                .map(|offset| {
+                    SpatialBasicNode {
+                        volume: Volume::Linear(0.5),
+                        offset,
+                        ..Default::default()
+                    }
+                    .compute_values(0.0)
+                })
+                .fold((0.0, 0.0), |(l, r), v| (l + v.gain_l, r + v.gain_r))
+        };
+
+        for (position, offsets) in [
+            (20.0, [Vec3::X * 10.0, Vec3::NEG_X * 10.0]),
+            (40.0, [Vec3::X * 10.0, Vec3::NEG_X * 30.0]),
+        ] {
+            app.world_mut()
+                .entity_mut(second)
+                .insert(GlobalTransform::from_translation(Vec3::X * position));
+            app.update();
+
+            let rendered = node(&app, emitter).compute_values(0.0);
+            let (left, right) = separate(offsets);
+
+            assert!(
+                (rendered.gain_l - left).abs() < 1e-3,
+                "{} {left}",
+                rendered.gain_l
+            );
+            assert!(
+                (rendered.gain_r - right).abs() < 1e-3,
+                "{} {right}",
+                rendered.gain_r
+            );
+        }
+
+        // Equidistant listeners on either side hear the emitter evenly
+        // rather than cancelling each other out.
+        app.world_mut()
+            .entity_mut(second)
+            .insert(GlobalTransform::from_translation(Vec3::X * 20.0));
+        app.update();
+
+        let rendered = node(&app, emitter).compute_values(0.0);
+        assert!((rendered.gain_l - rendered.gain_r).abs() < 1e-3);
+        assert!(rendered.gain_l > 0.3, "{}", rendered.gain_l);
+    }
+
+    #[test]
+    fn test_closest() {
+        let positions = [Vec3::splat(5.0), Vec3::splat(4.0), Vec3::splat(6.0)];
+        let emitter = Vec3::splat(0.0);
+        let closest = find_closest_listener(emitter, positions.iter().copied(), |p| *p).unwrap();
+
+        assert_eq!(closest, positions[1]);
+    }
+
+    #[test]
+    fn test_empty() {
+        let positions = [];
+
+        let emitter = Vec3::splat(0.0);
+        let closest = find_closest_listener(emitter, positions.iter().copied(), |p| *p);
+
+        assert!(closest.is_none());
+    }
+
+    #[test]
+    fn test_mixing() {
+        let frame = |x: f32, mixing| {
+            let transform = GlobalTransform::from(Transform::from_xyz(x, 0.0, 0.0));
+            ListenerFrame::new(&transform, Vec3::NEG_Z, Vec3::Y).with_mixing(mixing)
+        };
+
+        let listeners = [
+            frame(5.0, ListenerMixing::Closest),
+            frame(2.0, ListenerMixing::Closest),
+            frame(10.0, ListenerMixing::Mix),
+            frame(20.0, ListenerMixing::Mix),
+        ];
+
+        // Only the closest of the closest-mode listeners is heard,
+        // along with every mixed listener.
+        let heard: Vec<_> = heard_listeners(Vec3::ZERO, &listeners)
+            .iter()
+            .map(|l| l.position.x)
+            .collect();
+        assert_eq!(heard, [2.0, 10.0, 20.0]);
+    }
+
+    #[test]
+    fn test_orientation() {
+        let right = Vec3::new(5.0, 0.0, 0.0);
+
+        // A 3D listener turned to face the emitter hears it straight ahead.
+        let transform = GlobalTransform::from(Transform::default().looking_to(Vec3::X, Vec3::Y));
+        let default = SpatialListener3D::default();
+        let frame = ListenerFrame::new(&transform, default.forward, default.up);
+        assert!(frame
+            .localize(right)
+            .abs_diff_eq(Vec3::new(0.0, 0.0, -5.0), 1e-5));
+
+        // A top-down listener rotated a quarter turn counter-clockwise
+        // faces -X, so an emitter to the east is directly behind it.
+        let transform = GlobalTransform::from(Transform::from_rotation(Quat::from_rotation_z(
+            core::f32::consts::FRAC_PI_2,
+        )));
+        let default = SpatialListener2D::default();
+        let frame = ListenerFrame::new(&transform, default.forward, default.up);
+        assert!(frame
+            .localize(right)
+            .abs_diff_eq(Vec3::new(0.0, 0.0, 5.0), 1e-5));
+
+        // Unrotated, the same emitter is to its right.
+        let frame = ListenerFrame::new(&GlobalTransform::IDENTITY, default.forward, default.up);
+        assert!(frame
+            .localize(right)
+            .abs_diff_eq(Vec3::new(5.0, 0.0, 0.0), 1e-5));
+    }
+}
@@ -5,6 +5,7 @@ use bevy_math::{
     curve::{Ease, EaseFunction, EasingCurve},
     Curve,
 };
+use bevy_reflect::Reflect;
 use firewheel::{
     clock::ClockSeconds,
     diff::{Diff, EventQueue, Patch, PatchError, PathBuilder},
@@ -15,11 +16,18 @@ use firewheel::{
 ///
 /// This allows parameters to vary smoothly at audio-rate
 /// with minimal cross-thread communication.
-#[derive(Debug, Clone)]
+///
+/// Only the current value is reflected. Scheduled events are timestamped
+/// on the audio clock, so they aren't meaningful outside the running
+/// session. Writing the value through reflection, such as from an
+/// inspector, behaves like [`Timeline::set`].
+#[derive(Debug, Clone, Reflect)]
 pub struct Timeline<T> {
     value: T,
+    #[reflect(ignore)]
     events: FixedVec<TimelineEvent<T>>,
     /// The total number of events consumed.
+    #[reflect(ignore)]
     consumed: usize,
 }
 
@@ -252,10 +260,31 @@ impl<T: Ease + Clone> TimelineEvent<T> {
     }
 }
 
-impl<T: Clone + Send + Sync + 'static> Diff for Timeline<T> {
+/// A timeline event along with the sender's consumed count.
+///
+/// Carrying the count keeps a patched baseline in step with its source,
+/// even for events that weren't pushed to the source's queue.
+#[derive(Debug, Clone)]
+struct TimelinePatch<T> {
+    event: TimelineEvent<T>,
+    consumed: usize,
+}
+
+impl<T: Clone + PartialEq + Send + Sync + 'static> Diff for Timeline<T> {
     fn diff<E: EventQueue>(&self, baseline: &Self, path: PathBuilder, event_queue: &mut E) {
         let newly_consumed = self.consumed.saturating_sub(baseline.consumed);
         if newly_consumed == 0 {
+            // The value was written directly, such as through reflection.
+            if self.value != baseline.value {
+                event_queue.push_param(
+                    ParamData::any(TimelinePatch {
+                        event: TimelineEvent::Immediate(self.value.clone()),
+                        consumed: self.consumed,
+                    }),
+                    path,
+                );
+            }
+
             return;
         }
 
@@ -266,19 +295,26 @@ impl<T: Clone + Send + Sync + 'static> Diff for Timeline<T> {
         let start = self.events.len() - clamped_newly_consumed;
         let new_items = &self.events[start..];
 
-        for event in new_items.iter() {
-            event_queue.push_param(ParamData::any(event.clone()), path.clone());
+        for (i, event) in new_items.iter().enumerate() {
+            event_queue.push_param(
+                ParamData::any(TimelinePatch {
+                    event: event.clone(),
+                    consumed: self.consumed - clamped_newly_consumed + i + 1,
+                }),
+                path.clone(),
+            );
         }
     }
 }
 
 impl<T: Ease + Clone + 'static> Patch for Timeline<T> {
     fn patch(&mut self, data: &ParamData, _: &[u32]) -> Result<(), PatchError> {
-        let value: &TimelineEvent<T> = data.downcast_ref().ok_or(PatchError::InvalidData)?;
+        let value: &TimelinePatch<T> = data.downcast_ref().ok_or(PatchError::InvalidData)?;
 
         // There's not much error handling that can be
         // done in the audio thread.
-        let _ = self.push(value.clone());
+        let _ = self.push(value.event.clone());
+        self.consumed = value.consumed;
 
         Ok(())
     }
@@ -310,6 +346,32 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_reflected_value_diff() {
+        let a = Timeline::new(0f32);
+        let mut b = a.clone();
+
+        // Writing the field directly leaves the event queue untouched.
+        b.value = 0.5;
+
+        let mut events = Vec::new();
+        b.diff(&a, Default::default(), &mut events);
+        assert_eq!(events.len(), 1);
+
+        let mut baseline = a.clone();
+        for event in &events {
+            baseline.patch_event(event);
+        }
+        assert_eq!(baseline.get(), 0.5);
+
+        // Subsequent pushes must still be diffed.
+        b.set(1.0);
+
+        let mut events = Vec::new();
+        b.diff(&baseline, Default::default(), &mut events);
+        assert_eq!(events.len(), 1);
+    }
+
     // #[test]
     // fn test_full_diff() {
     //     let mut a = Timeline::new(0f32);